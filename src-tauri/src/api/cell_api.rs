@@ -1,7 +1,11 @@
 use crate::{
-    dto::update_cell_request::UpdateCellRequest,
+    dto::{
+        answer_comparison::AnswerComparison, duplicate_group::DuplicateGroup,
+        ordering_grade::OrderingGrade, update_cell_request::UpdateCellRequest,
+    },
     entity::cell::{self, CellType},
-    service::cell_service,
+    model::undo_history::UndoHistory,
+    service::{answer_service, cell_service, duplicate_service, undo_service},
 };
 use sea_orm::DbConn;
 use tauri::State;
@@ -29,9 +33,14 @@ pub async fn create_cell(
 }
 
 #[tauri::command]
-pub async fn delete_cell(db_conn: State<'_, Mutex<DbConn>>, cell_id: i32) -> Result<(), String> {
+pub async fn delete_cell(
+    db_conn: State<'_, Mutex<DbConn>>,
+    undo_history: State<'_, Mutex<UndoHistory>>,
+    cell_id: i32,
+) -> Result<(), String> {
     let db_conn = db_conn.lock().await;
-    cell_service::delete_cell(&db_conn, cell_id).await
+    let mut undo_history = undo_history.lock().await;
+    undo_service::delete_cell(&db_conn, &mut undo_history, cell_id).await
 }
 
 #[tauri::command]
@@ -47,10 +56,12 @@ pub async fn move_cell(
 #[tauri::command]
 pub async fn update_cells_contents(
     db_conn: State<'_, Mutex<DbConn>>,
+    undo_history: State<'_, Mutex<UndoHistory>>,
     requests: Vec<UpdateCellRequest>,
 ) -> Result<(), String> {
     let db_conn = db_conn.lock().await;
-    cell_service::update_cells_contents(&db_conn, requests).await
+    let mut undo_history = undo_history.lock().await;
+    undo_service::update_cells_contents(&db_conn, &mut undo_history, requests).await
 }
 
 #[tauri::command]
@@ -61,3 +72,47 @@ pub async fn get_cells_for_files(
     let db_conn = db_conn.lock().await;
     cell_service::get_cells_for_files(&db_conn, file_ids).await
 }
+
+/// Returns the groups of cells with an equal or a similar content across all
+/// files, `similarity_threshold` is between 0 and 1.
+#[tauri::command]
+pub async fn find_duplicate_cells(
+    db_conn: State<'_, Mutex<DbConn>>,
+    similarity_threshold: f32,
+) -> Result<Vec<DuplicateGroup>, String> {
+    let db_conn = db_conn.lock().await;
+    duplicate_service::find_duplicates(&db_conn, similarity_threshold).await
+}
+
+/// Keeps the most reviewed of the cells and deletes the others, returns the id
+/// of the kept cell.
+#[tauri::command]
+pub async fn merge_duplicate_cells(
+    db_conn: State<'_, Mutex<DbConn>>,
+    undo_history: State<'_, Mutex<UndoHistory>>,
+    cell_ids: Vec<i32>,
+) -> Result<i32, String> {
+    let db_conn = db_conn.lock().await;
+    let mut undo_history = undo_history.lock().await;
+    undo_service::merge_duplicate_cells(&db_conn, &mut undo_history, cell_ids).await
+}
+
+#[tauri::command]
+pub async fn check_typed_answer(
+    db_conn: State<'_, Mutex<DbConn>>,
+    cell_id: i32,
+    typed_answer: String,
+) -> Result<AnswerComparison, String> {
+    let db_conn = db_conn.lock().await;
+    answer_service::check_typed_answer(&db_conn, cell_id, &typed_answer).await
+}
+
+#[tauri::command]
+pub async fn grade_ordering(
+    db_conn: State<'_, Mutex<DbConn>>,
+    cell_id: i32,
+    submitted_order: Vec<usize>,
+) -> Result<OrderingGrade, String> {
+    let db_conn = db_conn.lock().await;
+    answer_service::grade_ordering(&db_conn, cell_id, &submitted_order).await
+}
@@ -1,4 +1,11 @@
-use crate::service::export_import_service;
+use crate::dto::anki_import_result::AnkiImportResult;
+use crate::dto::csv_import_options::CsvImportOptions;
+use crate::dto::csv_import_result::CsvImportResult;
+use crate::dto::import_options::ImportOptions;
+use crate::dto::import_result::ImportResult;
+use crate::service::{
+    anki_service, csv_service, export_import_service, markdown_service, settings_service,
+};
 use sea_orm::DbConn;
 use tauri::State;
 use tokio::sync::Mutex;
@@ -8,9 +15,10 @@ pub async fn export(
     db_conn: State<'_, Mutex<DbConn>>,
     item_id: i32,
     export_path: String,
+    include_progress: bool,
 ) -> Result<(), String> {
     let db_conn = db_conn.lock().await;
-    export_import_service::export(&db_conn, item_id, export_path).await
+    export_import_service::export(&db_conn, item_id, export_path, include_progress).await
 }
 
 #[tauri::command]
@@ -18,7 +26,133 @@ pub async fn import(
     db_conn: State<'_, Mutex<DbConn>>,
     import_item_path: String,
     import_into_folder_id: i32,
+    options: ImportOptions,
+) -> Result<ImportResult, String> {
+    let db_conn = db_conn.lock().await;
+    export_import_service::import(&db_conn, import_item_path, import_into_folder_id, &options).await
+}
+
+/// Exports the file or folder as a `.brainy` zip bundle, which contains the
+/// local media files referenced by the cells as well.
+#[tauri::command]
+pub async fn export_bundle(
+    db_conn: State<'_, Mutex<DbConn>>,
+    item_id: i32,
+    export_path: String,
+    include_progress: bool,
+) -> Result<(), String> {
+    let db_conn = db_conn.lock().await;
+    export_import_service::export_bundle(
+        &db_conn,
+        item_id,
+        export_path,
+        include_progress,
+        &settings_service::get_media_directory(),
+    )
+    .await
+}
+
+/// Imports a `.brainy` bundle, its media files are copied next to the
+/// database.
+#[tauri::command]
+pub async fn import_bundle(
+    db_conn: State<'_, Mutex<DbConn>>,
+    bundle_path: String,
+    import_into_folder_id: i32,
+    options: ImportOptions,
+) -> Result<ImportResult, String> {
+    let db_conn = db_conn.lock().await;
+    export_import_service::import_bundle(
+        &db_conn,
+        bundle_path,
+        import_into_folder_id,
+        &settings_service::get_media_directory(),
+        &options,
+    )
+    .await
+}
+
+/// Exports the file or folder as an Anki `.apkg` package, note cells are
+/// skipped unless a note type name is given for them.
+#[tauri::command]
+pub async fn export_anki_package(
+    db_conn: State<'_, Mutex<DbConn>>,
+    item_id: i32,
+    export_path: String,
+    note_type_name: Option<String>,
+) -> Result<(), String> {
+    let db_conn = db_conn.lock().await;
+    anki_service::export_anki_package(
+        &db_conn,
+        item_id,
+        export_path,
+        note_type_name,
+        &settings_service::get_media_directory(),
+    )
+    .await
+}
+
+/// Imports an Anki `.apkg` or `.colpkg` package, optionally with the review
+/// scheduling of its cards.
+#[tauri::command]
+pub async fn import_anki_package(
+    db_conn: State<'_, Mutex<DbConn>>,
+    package_path: String,
+    import_into_folder_id: i32,
+    import_scheduling: bool,
+) -> Result<AnkiImportResult, String> {
+    let db_conn = db_conn.lock().await;
+    anki_service::import_anki_package(
+        &db_conn,
+        package_path,
+        import_into_folder_id,
+        import_scheduling,
+        &settings_service::get_media_directory(),
+    )
+    .await
+}
+
+/// Writes the cells of the file or folder to a CSV file, or to a TSV file
+/// when the path ends with `.tsv`.
+#[tauri::command]
+pub async fn export_csv(
+    db_conn: State<'_, Mutex<DbConn>>,
+    item_id: i32,
+    export_path: String,
+) -> Result<(), String> {
+    let db_conn = db_conn.lock().await;
+    csv_service::export_csv(&db_conn, item_id, export_path).await
+}
+
+#[tauri::command]
+pub async fn import_csv(
+    db_conn: State<'_, Mutex<DbConn>>,
+    import_path: String,
+    file_id: i32,
+    options: CsvImportOptions,
+) -> Result<CsvImportResult, String> {
+    let db_conn = db_conn.lock().await;
+    csv_service::import_csv(&db_conn, import_path, file_id, &options).await
+}
+
+/// Writes the file or folder into the directory as Markdown files.
+#[tauri::command]
+pub async fn export_markdown(
+    db_conn: State<'_, Mutex<DbConn>>,
+    item_id: i32,
+    export_directory: String,
+) -> Result<(), String> {
+    let db_conn = db_conn.lock().await;
+    markdown_service::export_markdown(&db_conn, item_id, export_directory).await
+}
+
+/// Imports a Markdown file or a directory of Markdown files.
+#[tauri::command]
+pub async fn import_markdown(
+    db_conn: State<'_, Mutex<DbConn>>,
+    import_path: String,
+    import_into_folder_id: i32,
 ) -> Result<(), String> {
     let db_conn = db_conn.lock().await;
-    export_import_service::import(&db_conn, import_item_path, import_into_folder_id).await
+    markdown_service::import_markdown(&db_conn, import_path, import_into_folder_id).await
 }
@@ -1,4 +1,8 @@
-use crate::{dto::file_with_repetitions_count::FileWithRepetitionsCount, service::file_service};
+use crate::{
+    dto::file_with_repetitions_count::FileWithRepetitionsCount,
+    model::undo_history::UndoHistory,
+    service::{file_service, undo_service},
+};
 use sea_orm::DbConn;
 use tauri::State;
 use tokio::sync::Mutex;
@@ -24,18 +28,25 @@ pub async fn create_file(db_conn: State<'_, Mutex<DbConn>>, path: String) -> Res
 }
 
 #[tauri::command]
-pub async fn delete_file(db_conn: State<'_, Mutex<DbConn>>, file_id: i32) -> Result<(), String> {
+pub async fn delete_file(
+    db_conn: State<'_, Mutex<DbConn>>,
+    undo_history: State<'_, Mutex<UndoHistory>>,
+    file_id: i32,
+) -> Result<(), String> {
     let db_conn = db_conn.lock().await;
-    file_service::delete_file(&db_conn, file_id).await
+    let mut undo_history = undo_history.lock().await;
+    undo_service::delete_file(&db_conn, &mut undo_history, file_id).await
 }
 
 #[tauri::command]
 pub async fn delete_folder(
     db_conn: State<'_, Mutex<DbConn>>,
+    undo_history: State<'_, Mutex<UndoHistory>>,
     folder_id: i32,
 ) -> Result<(), String> {
     let db_conn = db_conn.lock().await;
-    file_service::delete_folder(&db_conn, folder_id).await
+    let mut undo_history = undo_history.lock().await;
+    undo_service::delete_folder(&db_conn, &mut undo_history, folder_id).await
 }
 
 #[tauri::command]
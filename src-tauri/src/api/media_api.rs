@@ -0,0 +1,45 @@
+use std::path::Path;
+
+use sea_orm::DbConn;
+use tauri::State;
+use tokio::sync::Mutex;
+
+use crate::dto::media_file::MediaFile;
+use crate::model::undo_history::UndoHistory;
+use crate::service::{media_service, settings_service};
+
+/// Copies the file into the media store, the returned URL can be used in the
+/// HTML of cells.
+#[tauri::command]
+pub async fn add_media_from_path(path: String) -> Result<MediaFile, String> {
+    media_service::add_media_from_path(&settings_service::get_media_directory(), Path::new(&path))
+}
+
+#[tauri::command]
+pub async fn add_media_from_bytes(bytes: Vec<u8>, extension: String) -> Result<MediaFile, String> {
+    media_service::add_media_from_bytes(
+        &settings_service::get_media_directory(),
+        &bytes,
+        &extension,
+    )
+}
+
+#[tauri::command]
+pub async fn list_unused_media(
+    db_conn: State<'_, Mutex<DbConn>>,
+) -> Result<Vec<MediaFile>, String> {
+    let db_conn = db_conn.lock().await;
+    media_service::list_unused_media(&db_conn, &settings_service::get_media_directory()).await
+}
+
+/// Deletes the media files no cell references, the undo history is cleared as
+/// deleted cells could not be restored with their media anymore.
+#[tauri::command]
+pub async fn delete_unused_media(
+    db_conn: State<'_, Mutex<DbConn>>,
+    undo_history: State<'_, Mutex<UndoHistory>>,
+) -> Result<u32, String> {
+    let db_conn = db_conn.lock().await;
+    undo_history.lock().await.clear();
+    media_service::delete_unused_media(&db_conn, &settings_service::get_media_directory()).await
+}
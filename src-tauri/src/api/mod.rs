@@ -1,17 +1,23 @@
 mod cell_api;
 mod export_import_api;
 mod file_api;
+mod media_api;
 mod repetition_api;
+mod scheduler_api;
 mod search_api;
 mod settings_api;
+mod tag_api;
+mod trash_api;
+mod undo_api;
 
 pub use repetition_api::{
     get_file_repetitions, get_repetitions_for_files, get_study_repetition_counts,
-    reset_repetitions_for_cell, update_repetition,
+    preview_repetition, reset_repetitions_for_cell, review_repetition,
 };
 
 pub use cell_api::{
-    create_cell, delete_cell, get_cells_for_files, get_file_cells_ordered_by_index, move_cell,
+    check_typed_answer, create_cell, delete_cell, find_duplicate_cells, get_cells_for_files,
+    get_file_cells_ordered_by_index, grade_ordering, merge_duplicate_cells, move_cell,
     update_cells_contents,
 };
 
@@ -20,8 +26,23 @@ pub use file_api::{
     rename_file, rename_folder,
 };
 
+pub use media_api::{
+    add_media_from_bytes, add_media_from_path, delete_unused_media, list_unused_media,
+};
+
+pub use scheduler_api::optimize_scheduler_parameters;
+
 pub use search_api::search_cells;
 
-pub use export_import_api::{export, import};
+pub use export_import_api::{
+    export, export_anki_package, export_bundle, export_csv, export_markdown, import,
+    import_anki_package, import_bundle, import_csv, import_markdown,
+};
 
 pub use settings_api::{get_settings, update_settings};
+
+pub use tag_api::{add_tag, get_tags, get_tags_for_cells, merge_tags, remove_tag, rename_tag};
+
+pub use trash_api::{empty_trash, list_trash, restore_from_trash};
+
+pub use undo_api::{get_undo_redo_state, redo, undo};
@@ -1,6 +1,9 @@
+use crate::dto::review_preview::ReviewPreview;
 use crate::entity::repetition;
 use crate::model::file_repetitions_count::FileRepetitionCounts;
-use crate::service::repetition_service;
+use crate::model::rating::Rating;
+use crate::model::undo_history::UndoHistory;
+use crate::service::{repetition_service, undo_service};
 use sea_orm::DbConn;
 use tauri::State;
 use tokio::sync::Mutex;
@@ -25,13 +28,35 @@ pub async fn get_file_repetitions(
     repetition_service::get_file_repetitions(&db_conn, file_id).await
 }
 
+/// Returns when the repetition would be due after each rating.
 #[tauri::command]
-pub async fn update_repetition(
+pub async fn preview_repetition(
     db_conn: State<'_, Mutex<DbConn>>,
-    repetition: repetition::Model,
-) -> Result<(), String> {
+    repetition_id: i32,
+) -> Result<ReviewPreview, String> {
+    let db_conn = db_conn.lock().await;
+    repetition_service::preview_repetition(&db_conn, repetition_id).await
+}
+
+/// Schedules the repetition in the backend and returns it after the review.
+#[tauri::command]
+pub async fn review_repetition(
+    db_conn: State<'_, Mutex<DbConn>>,
+    undo_history: State<'_, Mutex<UndoHistory>>,
+    repetition_id: i32,
+    rating: Rating,
+    review_duration: Option<i64>,
+) -> Result<repetition::Model, String> {
     let db_conn = db_conn.lock().await;
-    repetition_service::update_repetition(&db_conn, repetition).await
+    let mut undo_history = undo_history.lock().await;
+    undo_service::review_repetition(
+        &db_conn,
+        &mut undo_history,
+        repetition_id,
+        rating,
+        review_duration,
+    )
+    .await
 }
 
 #[tauri::command]
@@ -46,8 +71,10 @@ pub async fn get_repetitions_for_files(
 #[tauri::command]
 pub async fn reset_repetitions_for_cell(
     db_conn: State<'_, Mutex<DbConn>>,
+    undo_history: State<'_, Mutex<UndoHistory>>,
     cell_id: i32,
 ) -> Result<(), String> {
     let db_conn = db_conn.lock().await;
-    repetition_service::reset_repetitions_for_cell(&db_conn, cell_id).await
+    let mut undo_history = undo_history.lock().await;
+    undo_service::reset_repetitions_for_cell(&db_conn, &mut undo_history, cell_id).await
 }
@@ -0,0 +1,14 @@
+use crate::{dto::optimization_result::OptimizationResult, service::scheduler_service};
+use sea_orm::DbConn;
+use tauri::State;
+use tokio::sync::Mutex;
+
+/// Optimizes the scheduler parameters using the review history of the folder,
+/// pass 0 to use the whole collection.
+#[tauri::command]
+pub async fn optimize_scheduler_parameters(
+    db_conn: State<'_, Mutex<DbConn>>,
+    folder_id: i32,
+) -> Result<OptimizationResult, String> {
+    scheduler_service::optimize_parameters(&db_conn, folder_id).await
+}
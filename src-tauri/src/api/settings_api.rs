@@ -3,7 +3,8 @@ use tauri::State;
 use tokio::sync::Mutex;
 
 use crate::{
-    dto::update_settings_request::UpdateSettingsRequest, model::settings::Settings,
+    dto::update_settings_request::UpdateSettingsRequest,
+    model::{settings::Settings, undo_history::UndoHistory},
     service::settings_service,
 };
 
@@ -15,8 +16,8 @@ pub async fn get_settings() -> Result<Settings, ()> {
 #[tauri::command]
 pub async fn update_settings(
     db_conn: State<'_, Mutex<DbConn>>,
+    undo_history: State<'_, Mutex<UndoHistory>>,
     new_settings: UpdateSettingsRequest,
 ) -> Result<(), String> {
-    settings_service::update_settings(new_settings, &db_conn).await;
-    Ok(())
+    settings_service::update_settings(new_settings, &db_conn, &undo_history).await
 }
@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+
+use crate::{dto::tag_with_cells_count::TagWithCellsCount, service::tag_service};
+use sea_orm::DbConn;
+use tauri::State;
+use tokio::sync::Mutex;
+
+#[tauri::command]
+pub async fn get_tags(db_conn: State<'_, Mutex<DbConn>>) -> Result<Vec<TagWithCellsCount>, String> {
+    let db_conn = db_conn.lock().await;
+    tag_service::get_tags(&db_conn).await
+}
+
+#[tauri::command]
+pub async fn get_tags_for_cells(
+    db_conn: State<'_, Mutex<DbConn>>,
+    cell_ids: Vec<i32>,
+) -> Result<HashMap<i32, Vec<String>>, String> {
+    let db_conn = db_conn.lock().await;
+    tag_service::get_tags_for_cells(&db_conn, cell_ids).await
+}
+
+#[tauri::command]
+pub async fn add_tag(
+    db_conn: State<'_, Mutex<DbConn>>,
+    cell_ids: Vec<i32>,
+    name: String,
+) -> Result<(), String> {
+    let db_conn = db_conn.lock().await;
+    tag_service::add_tag(&db_conn, cell_ids, &name).await
+}
+
+#[tauri::command]
+pub async fn remove_tag(
+    db_conn: State<'_, Mutex<DbConn>>,
+    cell_ids: Vec<i32>,
+    name: String,
+) -> Result<(), String> {
+    let db_conn = db_conn.lock().await;
+    tag_service::remove_tag(&db_conn, cell_ids, &name).await
+}
+
+#[tauri::command]
+pub async fn rename_tag(
+    db_conn: State<'_, Mutex<DbConn>>,
+    name: String,
+    new_name: String,
+) -> Result<(), String> {
+    let db_conn = db_conn.lock().await;
+    tag_service::rename_tag(&db_conn, &name, &new_name).await
+}
+
+#[tauri::command]
+pub async fn merge_tags(
+    db_conn: State<'_, Mutex<DbConn>>,
+    name: String,
+    into_name: String,
+) -> Result<(), String> {
+    let db_conn = db_conn.lock().await;
+    tag_service::merge_tags(&db_conn, &name, &into_name).await
+}
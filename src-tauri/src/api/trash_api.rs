@@ -0,0 +1,45 @@
+use sea_orm::DbConn;
+use tauri::State;
+use tokio::sync::Mutex;
+
+use crate::{
+    dto::trash_item::TrashItem,
+    model::undo_history::UndoHistory,
+    service::{media_service, settings_service, trash_service},
+};
+
+#[tauri::command]
+pub async fn list_trash(db_conn: State<'_, Mutex<DbConn>>) -> Result<Vec<TrashItem>, String> {
+    let db_conn = db_conn.lock().await;
+    trash_service::list_trash(&db_conn).await
+}
+
+/// Returns the path of the restored file or folder, it differs from the
+/// original one when that path was taken in the meantime. The deletion cannot
+/// be undone anymore.
+#[tauri::command]
+pub async fn restore_from_trash(
+    db_conn: State<'_, Mutex<DbConn>>,
+    undo_history: State<'_, Mutex<UndoHistory>>,
+    trash_id: i32,
+) -> Result<String, String> {
+    let db_conn = db_conn.lock().await;
+    let path = trash_service::restore_from_trash(&db_conn, trash_id).await?;
+    undo_history.lock().await.remove_moves_to_trash(&[trash_id]);
+    Ok(path)
+}
+
+/// Permanently deletes the trash, the undo history is cleared as the deleted
+/// files cannot be restored anymore. The media only they referenced is
+/// deleted as well.
+#[tauri::command]
+pub async fn empty_trash(
+    db_conn: State<'_, Mutex<DbConn>>,
+    undo_history: State<'_, Mutex<UndoHistory>>,
+) -> Result<(), String> {
+    let db_conn = db_conn.lock().await;
+    trash_service::empty_trash(&db_conn).await?;
+    undo_history.lock().await.clear();
+    media_service::delete_unused_media(&db_conn, &settings_service::get_media_directory()).await?;
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use sea_orm::DbConn;
+use tauri::State;
+use tokio::sync::Mutex;
+
+use crate::{
+    dto::undo_redo_state::UndoRedoState, model::undo_history::UndoHistory, service::undo_service,
+};
+
+/// Returns what the next undo and redo will do, e.g. "rate card Good".
+#[tauri::command]
+pub async fn get_undo_redo_state(
+    undo_history: State<'_, Mutex<UndoHistory>>,
+) -> Result<UndoRedoState, String> {
+    let undo_history = undo_history.lock().await;
+    Ok(undo_service::get_undo_redo_state(&undo_history))
+}
+
+#[tauri::command]
+pub async fn undo(
+    db_conn: State<'_, Mutex<DbConn>>,
+    undo_history: State<'_, Mutex<UndoHistory>>,
+) -> Result<UndoRedoState, String> {
+    let db_conn = db_conn.lock().await;
+    let mut undo_history = undo_history.lock().await;
+    undo_service::undo(&db_conn, &mut undo_history).await
+}
+
+#[tauri::command]
+pub async fn redo(
+    db_conn: State<'_, Mutex<DbConn>>,
+    undo_history: State<'_, Mutex<UndoHistory>>,
+) -> Result<UndoRedoState, String> {
+    let db_conn = db_conn.lock().await;
+    let mut undo_history = undo_history.lock().await;
+    undo_service::redo(&db_conn, &mut undo_history).await
+}
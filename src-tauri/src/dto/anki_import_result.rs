@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnkiImportResult {
+    pub imported_notes_count: u32,
+    /// Note types without a matching cell type, their notes were skipped.
+    pub unmapped_note_types: Vec<UnmappedNoteType>,
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmappedNoteType {
+    pub name: String,
+    pub notes_count: u32,
+}
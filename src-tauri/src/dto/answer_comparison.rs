@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::rating::Rating;
+
+/// The result of comparing a typed answer with the answers of a cell.
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnswerComparison {
+    pub is_correct: bool,
+    /// The accepted answer closest to the typed answer.
+    pub expected_answer: String,
+    /// The characters of the typed answer and of the expected answer, in the
+    /// order to display them.
+    pub diff: Vec<DiffSegment>,
+    /// Good when the answer is correct, Again otherwise.
+    pub suggested_rating: Rating,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffSegment {
+    pub kind: DiffKind,
+    pub text: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DiffKind {
+    /// In both the typed and the expected answer.
+    #[default]
+    Equal,
+    /// Typed but not expected.
+    Extra,
+    /// Expected but not typed.
+    Missing,
+}
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+/// Which columns of a CSV or TSV file hold the cells, columns start at 0.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvImportOptions {
+    pub question_column: usize,
+    pub answer_column: usize,
+    /// Rows without a type column or with an empty type are flash cards.
+    pub type_column: Option<usize>,
+    pub has_header: bool,
+    /// Defaults to a tab for `.tsv` files and to a comma otherwise.
+    pub delimiter: Option<char>,
+}
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvImportResult {
+    pub imported_cells_count: u32,
+    /// The skipped rows, the other rows are imported.
+    pub row_errors: Vec<CsvRowError>,
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvRowError {
+    pub line: u64,
+    pub message: String,
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entity::cell;
+
+/// Cells with the same or a similar content, the most reviewed cell is first.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    /// The lowest similarity of the pairs that linked the group, 1 when their
+    /// normalized contents are equal. Groups are built transitively, so two
+    /// cells of the group may be less similar.
+    pub similarity: f32,
+    pub cells: Vec<DuplicateCell>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateCell {
+    pub cell: cell::Model,
+    pub file_path: String,
+    /// The sum of the reviews of the repetitions of the cell.
+    pub reviews_count: i32,
+}
@@ -0,0 +1,18 @@
+use sea_orm::prelude::DateTimeUtc;
+use serde::{Deserialize, Serialize};
+
+use super::exported_item::ExportedItem;
+
+/// The file written by the JSON export. The format version is increased on
+/// every incompatible change, older files are upgraded when imported.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportEnvelope {
+    pub format_version: u32,
+    /// Unknown for files exported before the format was versioned.
+    pub app_version: Option<String>,
+    pub created_at: Option<DateTimeUtc>,
+    /// The hex encoded SHA-256 of the item as compact JSON.
+    pub checksum: String,
+    pub item: ExportedItem,
+}
@@ -1,8 +1,10 @@
+use sea_orm::prelude::DateTimeUtc;
 use serde::{Deserialize, Serialize};
 
 use crate::entity::cell::{self, CellType};
+use crate::entity::repetition::{self, State};
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportedItem {
     pub path: String,
@@ -27,11 +29,16 @@ impl ExportedItem {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportedCell {
     pub content: String,
     pub cell_type: CellType,
+    /// Only exported when the review progress is included.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repetitions: Option<Vec<ExportedRepetition>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
 }
 
 impl From<cell::Model> for ExportedCell {
@@ -39,6 +46,41 @@ impl From<cell::Model> for ExportedCell {
         ExportedCell {
             cell_type: value.cell_type,
             content: value.content,
+            repetitions: None,
+            tags: vec![],
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedRepetition {
+    pub due: DateTimeUtc,
+    pub stability: f32,
+    pub difficulty: f32,
+    pub elapsed_days: i32,
+    pub scheduled_days: i32,
+    pub reps: i32,
+    pub lapses: i32,
+    pub state: State,
+    pub last_review: DateTimeUtc,
+    /// The cloze index for cloze cells.
+    pub additional_content: Option<String>,
+}
+
+impl From<repetition::Model> for ExportedRepetition {
+    fn from(value: repetition::Model) -> Self {
+        ExportedRepetition {
+            due: value.due,
+            stability: value.stability,
+            difficulty: value.difficulty,
+            elapsed_days: value.elapsed_days,
+            scheduled_days: value.scheduled_days,
+            reps: value.reps,
+            lapses: value.lapses,
+            state: value.state,
+            last_review: value.last_review,
+            additional_content: value.additional_content,
         }
     }
 }
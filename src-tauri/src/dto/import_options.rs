@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportOptions {
+    pub mode: ImportMode,
+    /// Returns the actions the import would do without changing anything.
+    pub dry_run: bool,
+}
+
+/// What happens when an imported file has the path of an existing one, the
+/// contents of existing folders are always merged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ImportMode {
+    /// Imports the item as `Deck (2)`.
+    #[default]
+    Rename,
+    /// Adds the cells at the end of the existing file.
+    Merge,
+    /// Like `Merge`, but skips cells with the same type and content as a cell
+    /// of the existing file.
+    SkipDuplicates,
+    /// Replaces the cells of the existing file.
+    Overwrite,
+}
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportResult {
+    pub actions: Vec<ImportAction>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportAction {
+    /// The path the item is imported to.
+    pub path: String,
+    pub kind: ImportActionKind,
+    pub added_cells_count: u32,
+    pub skipped_cells_count: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportActionKind {
+    CreateFolder,
+    MergeFolder,
+    CreateFile,
+    MergeFile,
+    OverwriteFile,
+}
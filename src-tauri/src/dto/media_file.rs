@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaFile {
+    /// The SHA-256 of the content with the file extension.
+    pub name: String,
+    /// The `brainy-media://` reference to the file to use in the cells.
+    pub url: String,
+    pub size: u64,
+}
@@ -1,5 +1,21 @@
+pub mod anki_import_result;
+pub mod answer_comparison;
+pub mod csv_import_options;
+pub mod csv_import_result;
+pub mod duplicate_group;
+pub mod export_envelope;
 pub mod exported_item;
 pub mod file_with_repetitions_count;
+pub mod import_options;
+pub mod import_result;
+pub mod media_file;
+pub mod optimization_result;
+pub mod ordering_grade;
+pub mod review_preview;
+pub mod search_request;
 pub mod search_result;
+pub mod tag_with_cells_count;
+pub mod trash_item;
+pub mod undo_redo_state;
 pub mod update_cell_request;
 pub mod update_settings_request;
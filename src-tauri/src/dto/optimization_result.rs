@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptimizationResult {
+    pub weights: Vec<f64>,
+    pub log_loss_before: f64,
+    pub log_loss_after: f64,
+    pub rmse_before: f64,
+    pub rmse_after: f64,
+    pub review_count: i32,
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::rating::Rating;
+
+/// The result of grading the order in which the items of a cell were put.
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderingGrade {
+    pub is_correct: bool,
+    /// Whether the item at every position of the submitted order is in its
+    /// correct position.
+    pub in_place: Vec<bool>,
+    pub items_in_place_count: usize,
+    /// The most items that are in the correct order relative to each other,
+    /// the other items are the ones to move to correct the order.
+    pub longest_ordered_count: usize,
+    /// Good when the order is correct, Hard when one item is out of order and
+    /// Again otherwise.
+    pub suggested_rating: Rating,
+}
@@ -0,0 +1,13 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// When the repetition would be due again after being answered with each
+/// rating, computed by the same scheduler that reviews it.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewPreview {
+    pub again: DateTime<Utc>,
+    pub hard: DateTime<Utc>,
+    pub good: DateTime<Utc>,
+    pub easy: DateTime<Utc>,
+}
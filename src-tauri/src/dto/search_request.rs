@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_PAGE_SIZE: u64 = 50;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SearchSort {
+    /// Best matches first, falls back to `CreationTime` when there is no text
+    /// to match.
+    #[default]
+    Relevance,
+    FilePath,
+    /// Soonest due first, cells without repetitions last.
+    Due,
+    /// Most recently reviewed first, cells without repetitions last.
+    LastReview,
+    /// Newest first.
+    CreationTime,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchRequest {
+    pub search_text: String,
+    #[serde(default)]
+    pub sort: SearchSort,
+    #[serde(default)]
+    pub offset: u64,
+    #[serde(default = "default_page_size")]
+    pub limit: u64,
+}
+
+impl Default for SearchRequest {
+    fn default() -> Self {
+        Self {
+            search_text: String::new(),
+            sort: SearchSort::default(),
+            offset: 0,
+            limit: DEFAULT_PAGE_SIZE,
+        }
+    }
+}
+
+fn default_page_size() -> u64 {
+    DEFAULT_PAGE_SIZE
+}
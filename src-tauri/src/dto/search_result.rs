@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 use crate::entity::{cell, repetition};
@@ -7,4 +9,9 @@ use crate::entity::{cell, repetition};
 pub struct SearchResult {
     pub cells: Vec<cell::Model>,
     pub repetitions: Vec<repetition::Model>,
+    /// The matching part of each cell by cell id, HTML with the matched words
+    /// inside `<mark>` tags.
+    pub snippets: HashMap<i32, String>,
+    /// The number of matching cells across all the pages.
+    pub total_count: u64,
 }
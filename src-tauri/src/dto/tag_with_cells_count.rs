@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagWithCellsCount {
+    pub id: i32,
+    /// The full name, e.g. `lang::spanish::verbs`.
+    pub name: String,
+    pub cells_count: i32,
+}
@@ -8,4 +8,5 @@ pub struct UpdateSettingsRequest {
     pub database_location: Option<String>,
     pub theme: Option<Theme>,
     pub zoom_percentage: Option<f32>,
+    pub trash_retention_days: Option<u32>,
 }
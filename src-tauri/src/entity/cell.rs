@@ -16,6 +16,14 @@ pub enum CellType {
     Cloze,
     #[sea_orm(string_value = "TrueFalse")]
     TrueFalse,
+    #[sea_orm(string_value = "MultipleChoice")]
+    MultipleChoice,
+    #[sea_orm(string_value = "TypeAnswer")]
+    TypeAnswer,
+    #[sea_orm(string_value = "ImageOcclusion")]
+    ImageOcclusion,
+    #[sea_orm(string_value = "Ordering")]
+    Ordering,
 }
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Default, Serialize, Deserialize)]
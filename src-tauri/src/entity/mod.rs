@@ -1,3 +1,8 @@
 pub mod cell;
+pub mod cell_tag;
 pub mod file;
 pub mod repetition;
+pub mod review_log;
+pub mod scheduler_parameters;
+pub mod tag;
+pub mod trash;
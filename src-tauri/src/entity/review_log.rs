@@ -0,0 +1,73 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::repetition::State;
+use crate::model::rating::Rating;
+
+/// A single answer given while reviewing a repetition, with the scheduling
+/// state before and after it.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[sea_orm(table_name = "review_log")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub repetition_id: i32,
+    pub cell_id: i32,
+    pub file_id: i32,
+    pub rating: Rating,
+    pub previous_state: State,
+    pub new_state: State,
+    pub previous_stability: f32,
+    pub new_stability: f32,
+    pub previous_difficulty: f32,
+    pub new_difficulty: f32,
+    pub elapsed_days: i32,
+    pub scheduled_days: i32,
+    /// The time spent on the review in milliseconds.
+    pub review_duration: Option<i64>,
+    pub reviewed_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {
+    Repetition,
+    Cell,
+    File,
+}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::Repetition => Entity::belongs_to(super::repetition::Entity)
+                .from(Column::RepetitionId)
+                .to(super::repetition::Column::Id)
+                .on_delete(ForeignKeyAction::Cascade)
+                .into(),
+            Self::Cell => Entity::belongs_to(super::cell::Entity)
+                .from(Column::CellId)
+                .to(super::cell::Column::Id)
+                .on_delete(ForeignKeyAction::Cascade)
+                .into(),
+            Self::File => Entity::belongs_to(super::file::Entity)
+                .from(Column::FileId)
+                .to(super::file::Column::Id)
+                .on_delete(ForeignKeyAction::Cascade)
+                .into(),
+        }
+    }
+}
+
+impl Related<super::repetition::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Repetition.def()
+    }
+}
+
+impl Related<super::file::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::File.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
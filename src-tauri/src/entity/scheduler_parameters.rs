@@ -0,0 +1,46 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Scheduler weights optimized on the review history of a folder, or of the
+/// whole collection when `folder_id` is `None`.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[sea_orm(table_name = "scheduler_parameters")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub folder_id: Option<i32>,
+    /// JSON array of the FSRS weights.
+    pub weights: String,
+    pub log_loss_before: f64,
+    pub log_loss_after: f64,
+    pub rmse_before: f64,
+    pub rmse_after: f64,
+    pub review_count: i32,
+    pub optimized_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {
+    Folder,
+}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::Folder => Entity::belongs_to(super::file::Entity)
+                .from(Column::FolderId)
+                .to(super::file::Column::Id)
+                .on_delete(ForeignKeyAction::Cascade)
+                .into(),
+        }
+    }
+}
+
+impl Related<super::file::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Folder.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
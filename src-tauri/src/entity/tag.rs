@@ -0,0 +1,34 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A tag such as `lang::spanish::verbs`, `::` separates the levels.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[sea_orm(table_name = "tag")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    #[sea_orm(unique)]
+    pub name: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {
+    CellTag,
+}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::CellTag => Entity::has_many(super::cell_tag::Entity).into(),
+        }
+    }
+}
+
+impl Related<super::cell_tag::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CellTag.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,22 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A deleted file or folder, kept until the trash is emptied or purged.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[sea_orm(table_name = "trash")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    /// The path of the deleted file or folder at the time of the deletion.
+    pub path: String,
+    pub is_folder: bool,
+    pub deleted_at: DateTimeUtc,
+    /// JSON of the deleted items, see `DeletedItems`.
+    pub items: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -3,21 +3,39 @@ mod dto;
 mod entity;
 mod migration;
 mod model;
+mod scheduler;
 mod service;
 mod util;
 
-use service::settings_service;
+use service::{media_service, settings_service, trash_service};
 use tauri::Manager;
+use tauri::http::{Response, StatusCode, header::CONTENT_TYPE};
 
 use api::*;
+use model::undo_history::UndoHistory;
 use tauri_plugin_window_state::StateFlags;
 use tokio::sync::Mutex;
 use util::database_util::load_database;
+use util::media_util::MEDIA_SCHEME;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub async fn run() -> Result<(), String> {
     settings_service::init_settings();
-    let db_conn = load_database(&settings_service::get_settings().database_location).await;
+    let settings = settings_service::get_settings();
+    let db_conn = load_database(&settings.database_location).await?;
+    // The trash is purged again on the next start, so a failure must not keep
+    // the app from opening.
+    if let Err(err) = trash_service::purge_trash(&db_conn, settings.trash_retention_days).await {
+        eprintln!("Cannot purge the trash: {err}");
+    }
+    // Nothing can be undone after a restart, so the media of deleted cells is
+    // not needed anymore. Failing to clean it up must not keep the app from
+    // opening either.
+    if let Err(err) =
+        media_service::delete_unused_media(&db_conn, &settings_service::get_media_directory()).await
+    {
+        eprintln!("Cannot delete the unused media: {err}");
+    }
 
     let mut tauri_builder = tauri::Builder::default();
 
@@ -41,8 +59,24 @@ pub async fn run() -> Result<(), String> {
         )
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
+        // Cells reference the media store with `brainy-media://` URLs, which are
+        // served from the store of the opened database.
+        .register_uri_scheme_protocol(MEDIA_SCHEME, |_, request| {
+            let url = request.uri().to_string();
+            match media_service::read_media(&settings_service::get_media_directory(), &url) {
+                Ok((bytes, media_type)) => Response::builder()
+                    .header(CONTENT_TYPE, media_type)
+                    .body(bytes)
+                    .unwrap(),
+                Err(err) => Response::builder()
+                    .status(StatusCode::NOT_FOUND)
+                    .body(err.into_bytes())
+                    .unwrap(),
+            }
+        })
         .setup(|app| {
             app.manage(Mutex::new(db_conn));
+            app.manage(Mutex::new(UndoHistory::default()));
             #[cfg(dev)]
             {
                 let _ = app
@@ -54,14 +88,25 @@ pub async fn run() -> Result<(), String> {
         })
         .invoke_handler(tauri::generate_handler![
             // Cells
+            check_typed_answer,
             create_cell,
             delete_cell,
+            find_duplicate_cells,
             get_cells_for_files,
             get_file_cells_ordered_by_index,
+            grade_ordering,
+            merge_duplicate_cells,
             move_cell,
             update_cells_contents,
             // Search
             search_cells,
+            // Tags
+            add_tag,
+            get_tags,
+            get_tags_for_cells,
+            merge_tags,
+            remove_tag,
+            rename_tag,
             // Files & Folders
             create_file,
             create_folder,
@@ -72,18 +117,42 @@ pub async fn run() -> Result<(), String> {
             move_folder,
             rename_file,
             rename_folder,
+            // Trash
+            empty_trash,
+            list_trash,
+            restore_from_trash,
             // Repetitions
             get_file_repetitions,
             get_repetitions_for_files,
             get_study_repetition_counts,
+            preview_repetition,
             reset_repetitions_for_cell,
-            update_repetition,
+            review_repetition,
+            // Scheduler
+            optimize_scheduler_parameters,
+            // Undo/Redo
+            get_undo_redo_state,
+            redo,
+            undo,
             // Settings
             get_settings,
             update_settings,
+            // Media
+            add_media_from_path,
+            add_media_from_bytes,
+            list_unused_media,
+            delete_unused_media,
             // Export/Import
             export,
+            export_bundle,
+            export_anki_package,
             import,
+            import_bundle,
+            import_anki_package,
+            export_csv,
+            import_csv,
+            export_markdown,
+            import_markdown,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,48 @@
+use super::Migration;
+
+/// The schema as it was created by `create_table_from_entity` before the
+/// migrations were versioned. Every statement uses `IF NOT EXISTS` so that
+/// databases created by older versions are adopted without changes.
+pub const MIGRATION: Migration = Migration {
+    version: 1,
+    name: "baseline",
+    up: &[
+        r#"CREATE TABLE IF NOT EXISTS "file" (
+            "id" integer NOT NULL PRIMARY KEY AUTOINCREMENT,
+            "path" varchar NOT NULL,
+            "is_folder" boolean NOT NULL
+        )"#,
+        r#"CREATE TABLE IF NOT EXISTS "cell" (
+            "id" integer NOT NULL PRIMARY KEY AUTOINCREMENT,
+            "file_id" integer NOT NULL,
+            "index" integer NOT NULL,
+            "content" varchar NOT NULL,
+            "searchable_content" varchar NOT NULL,
+            "cell_type" varchar NOT NULL,
+            FOREIGN KEY ("file_id") REFERENCES "file" ("id") ON DELETE CASCADE
+        )"#,
+        r#"CREATE TABLE IF NOT EXISTS "repetition" (
+            "id" integer NOT NULL PRIMARY KEY AUTOINCREMENT,
+            "file_id" integer NOT NULL,
+            "cell_id" integer NOT NULL,
+            "due" timestamp_with_timezone_text NOT NULL,
+            "stability" float NOT NULL,
+            "difficulty" float NOT NULL,
+            "elapsed_days" integer NOT NULL,
+            "scheduled_days" integer NOT NULL,
+            "reps" integer NOT NULL,
+            "lapses" integer NOT NULL,
+            "state" varchar NOT NULL,
+            "last_review" timestamp_with_timezone_text NOT NULL,
+            "additional_content" varchar,
+            FOREIGN KEY ("file_id") REFERENCES "file" ("id") ON DELETE CASCADE,
+            FOREIGN KEY ("cell_id") REFERENCES "cell" ("id") ON DELETE CASCADE
+        )"#,
+        r#"CREATE UNIQUE INDEX IF NOT EXISTS "idx-path" ON "file" ("path", "is_folder")"#,
+    ],
+    down: &[
+        r#"DROP TABLE "repetition""#,
+        r#"DROP TABLE "cell""#,
+        r#"DROP TABLE "file""#,
+    ],
+};
@@ -0,0 +1,30 @@
+use super::Migration;
+
+pub const MIGRATION: Migration = Migration {
+    version: 2,
+    name: "review_log",
+    up: &[
+        r#"CREATE TABLE "review_log" (
+            "id" integer NOT NULL PRIMARY KEY AUTOINCREMENT,
+            "repetition_id" integer NOT NULL,
+            "cell_id" integer NOT NULL,
+            "file_id" integer NOT NULL,
+            "rating" varchar NOT NULL,
+            "previous_state" varchar NOT NULL,
+            "new_state" varchar NOT NULL,
+            "previous_stability" float NOT NULL,
+            "new_stability" float NOT NULL,
+            "previous_difficulty" float NOT NULL,
+            "new_difficulty" float NOT NULL,
+            "elapsed_days" integer NOT NULL,
+            "scheduled_days" integer NOT NULL,
+            "review_duration" bigint,
+            "reviewed_at" timestamp_with_timezone_text NOT NULL,
+            FOREIGN KEY ("repetition_id") REFERENCES "repetition" ("id") ON DELETE CASCADE,
+            FOREIGN KEY ("cell_id") REFERENCES "cell" ("id") ON DELETE CASCADE,
+            FOREIGN KEY ("file_id") REFERENCES "file" ("id") ON DELETE CASCADE
+        )"#,
+        r#"CREATE INDEX "idx-review_log-repetition_id" ON "review_log" ("repetition_id")"#,
+    ],
+    down: &[r#"DROP TABLE "review_log""#],
+};
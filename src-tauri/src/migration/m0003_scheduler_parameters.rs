@@ -0,0 +1,19 @@
+use super::Migration;
+
+pub const MIGRATION: Migration = Migration {
+    version: 3,
+    name: "scheduler_parameters",
+    up: &[r#"CREATE TABLE "scheduler_parameters" (
+            "id" integer NOT NULL PRIMARY KEY AUTOINCREMENT,
+            "folder_id" integer,
+            "weights" varchar NOT NULL,
+            "log_loss_before" double NOT NULL,
+            "log_loss_after" double NOT NULL,
+            "rmse_before" double NOT NULL,
+            "rmse_after" double NOT NULL,
+            "review_count" integer NOT NULL,
+            "optimized_at" timestamp_with_timezone_text NOT NULL,
+            FOREIGN KEY ("folder_id") REFERENCES "file" ("id") ON DELETE CASCADE
+        )"#],
+    down: &[r#"DROP TABLE "scheduler_parameters""#],
+};
@@ -0,0 +1,17 @@
+use super::Migration;
+
+pub const MIGRATION: Migration = Migration {
+    version: 4,
+    name: "trash",
+    up: &[
+        r#"CREATE TABLE "trash" (
+            "id" integer NOT NULL PRIMARY KEY AUTOINCREMENT,
+            "path" varchar NOT NULL,
+            "is_folder" boolean NOT NULL,
+            "deleted_at" timestamp_with_timezone_text NOT NULL,
+            "items" varchar NOT NULL
+        )"#,
+        r#"CREATE INDEX "idx-trash-deleted_at" ON "trash" ("deleted_at")"#,
+    ],
+    down: &[r#"DROP TABLE "trash""#],
+};
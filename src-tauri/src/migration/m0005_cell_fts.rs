@@ -0,0 +1,37 @@
+use super::Migration;
+
+/// Full-text index over `cell.searchable_content`, kept in sync by triggers.
+/// The content is read from the `cell` table so it is not stored twice.
+pub const MIGRATION: Migration = Migration {
+    version: 5,
+    name: "cell_fts",
+    up: &[
+        r#"CREATE VIRTUAL TABLE "cell_fts" USING fts5(
+            "searchable_content",
+            content = 'cell',
+            content_rowid = 'id',
+            tokenize = 'unicode61 remove_diacritics 2'
+        )"#,
+        r#"CREATE TRIGGER "cell_fts_after_insert" AFTER INSERT ON "cell" BEGIN
+            INSERT INTO "cell_fts" ("rowid", "searchable_content")
+            VALUES (new."id", new."searchable_content");
+        END"#,
+        r#"CREATE TRIGGER "cell_fts_after_delete" AFTER DELETE ON "cell" BEGIN
+            INSERT INTO "cell_fts" ("cell_fts", "rowid", "searchable_content")
+            VALUES ('delete', old."id", old."searchable_content");
+        END"#,
+        r#"CREATE TRIGGER "cell_fts_after_update" AFTER UPDATE OF "searchable_content" ON "cell" BEGIN
+            INSERT INTO "cell_fts" ("cell_fts", "rowid", "searchable_content")
+            VALUES ('delete', old."id", old."searchable_content");
+            INSERT INTO "cell_fts" ("rowid", "searchable_content")
+            VALUES (new."id", new."searchable_content");
+        END"#,
+        r#"INSERT INTO "cell_fts" ("cell_fts") VALUES ('rebuild')"#,
+    ],
+    down: &[
+        r#"DROP TRIGGER "cell_fts_after_update""#,
+        r#"DROP TRIGGER "cell_fts_after_delete""#,
+        r#"DROP TRIGGER "cell_fts_after_insert""#,
+        r#"DROP TABLE "cell_fts""#,
+    ],
+};
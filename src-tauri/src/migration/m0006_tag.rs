@@ -0,0 +1,24 @@
+use super::Migration;
+
+/// Tags of cells. Hierarchical tags like `lang::spanish::verbs` are stored
+/// with their full name, their parents exist only as prefixes.
+pub const MIGRATION: Migration = Migration {
+    version: 6,
+    name: "tag",
+    up: &[
+        r#"CREATE TABLE "tag" (
+            "id" integer NOT NULL PRIMARY KEY AUTOINCREMENT,
+            "name" varchar NOT NULL
+        )"#,
+        r#"CREATE UNIQUE INDEX "idx-tag-name" ON "tag" ("name")"#,
+        r#"CREATE TABLE "cell_tag" (
+            "cell_id" integer NOT NULL,
+            "tag_id" integer NOT NULL,
+            PRIMARY KEY ("cell_id", "tag_id"),
+            FOREIGN KEY ("cell_id") REFERENCES "cell" ("id") ON DELETE CASCADE,
+            FOREIGN KEY ("tag_id") REFERENCES "tag" ("id") ON DELETE CASCADE
+        )"#,
+        r#"CREATE INDEX "idx-cell_tag-tag_id" ON "cell_tag" ("tag_id")"#,
+    ],
+    down: &[r#"DROP TABLE "cell_tag""#, r#"DROP TABLE "tag""#],
+};
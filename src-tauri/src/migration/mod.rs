@@ -0,0 +1,274 @@
+mod m0001_baseline;
+mod m0002_review_log;
+mod m0003_scheduler_parameters;
+mod m0004_trash;
+mod m0005_cell_fts;
+mod m0006_tag;
+
+use chrono::Utc;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbErr, Statement, TransactionTrait};
+
+/// A numbered schema change. Migrations are applied in ascending order of
+/// their version and reverted in descending order.
+pub struct Migration {
+    pub version: i32,
+    pub name: &'static str,
+    pub up: &'static [&'static str],
+    pub down: &'static [&'static str],
+}
+
+const MIGRATIONS: &[Migration] = &[
+    m0001_baseline::MIGRATION,
+    m0002_review_log::MIGRATION,
+    m0003_scheduler_parameters::MIGRATION,
+    m0004_trash::MIGRATION,
+    m0005_cell_fts::MIGRATION,
+    m0006_tag::MIGRATION,
+];
+
+pub fn get_latest_version() -> i32 {
+    MIGRATIONS.last().map_or(0, |migration| migration.version)
+}
+
+/// Applies all the migrations that are newer than the database version. Fails
+/// without touching the database if it was created by a newer version of the
+/// app.
+pub async fn setup_schema(db: &DatabaseConnection) -> Result<(), DbErr> {
+    create_schema_version_table(db).await?;
+
+    let current_version = get_schema_version(db).await?;
+    let latest_version = get_latest_version();
+    if current_version > latest_version {
+        return Err(DbErr::Custom(format!(
+            "The database has schema version {current_version} but this version of Brainy \
+            only supports up to version {latest_version}, please update the app!"
+        )));
+    }
+
+    for migration in MIGRATIONS
+        .iter()
+        .filter(|migration| migration.version > current_version)
+    {
+        let txn = db.begin().await?;
+        for statement in migration.up {
+            txn.execute_unprepared(statement).await?;
+        }
+        txn.execute(Statement::from_sql_and_values(
+            db.get_database_backend(),
+            r#"INSERT INTO "schema_version" ("version", "name", "applied_at") VALUES (?, ?, ?)"#,
+            [
+                migration.version.into(),
+                migration.name.into(),
+                Utc::now().to_rfc3339().into(),
+            ],
+        ))
+        .await?;
+        txn.commit().await?;
+    }
+
+    Ok(())
+}
+
+/// Reverts the applied migrations that are newer than `target_version`. The
+/// app never downgrades on its own, this is meant for rolling back a database
+/// by hand during development.
+#[allow(dead_code)]
+pub async fn migrate_down_to(db: &DatabaseConnection, target_version: i32) -> Result<(), DbErr> {
+    let current_version = get_schema_version(db).await?;
+
+    for migration in MIGRATIONS.iter().rev().filter(|migration| {
+        migration.version > target_version && migration.version <= current_version
+    }) {
+        let txn = db.begin().await?;
+        for statement in migration.down {
+            txn.execute_unprepared(statement).await?;
+        }
+        txn.execute(Statement::from_sql_and_values(
+            db.get_database_backend(),
+            r#"DELETE FROM "schema_version" WHERE "version" = ?"#,
+            [migration.version.into()],
+        ))
+        .await?;
+        txn.commit().await?;
+    }
+
+    Ok(())
+}
+
+pub async fn get_schema_version(db: &impl ConnectionTrait) -> Result<i32, DbErr> {
+    let row = db
+        .query_one(Statement::from_string(
+            db.get_database_backend(),
+            r#"SELECT MAX("version") AS "version" FROM "schema_version""#,
+        ))
+        .await?;
+    match row {
+        Some(row) => Ok(row.try_get::<Option<i32>>("", "version")?.unwrap_or(0)),
+        None => Ok(0),
+    }
+}
+
+async fn create_schema_version_table(db: &impl ConnectionTrait) -> Result<(), DbErr> {
+    db.execute_unprepared(
+        r#"CREATE TABLE IF NOT EXISTS "schema_version" (
+            "version" integer NOT NULL PRIMARY KEY,
+            "name" varchar NOT NULL,
+            "applied_at" varchar NOT NULL
+        )"#,
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use sea_orm::Database;
+
+    use super::*;
+    use crate::service::tests::get_random_file_path;
+
+    /// The schema that `create_table_from_entity` produced before migrations
+    /// were versioned, without any `schema_version` table.
+    const BASELINE_SCHEMA: &str = r#"
+        CREATE TABLE "file" ( "id" integer NOT NULL PRIMARY KEY AUTOINCREMENT, "path" varchar NOT NULL, "is_folder" boolean NOT NULL );
+        CREATE TABLE "cell" ( "id" integer NOT NULL PRIMARY KEY AUTOINCREMENT, "file_id" integer NOT NULL, "index" integer NOT NULL, "content" varchar NOT NULL, "searchable_content" varchar NOT NULL, "cell_type" varchar NOT NULL, FOREIGN KEY ("file_id") REFERENCES "file" ("id") ON DELETE CASCADE );
+        CREATE TABLE "repetition" ( "id" integer NOT NULL PRIMARY KEY AUTOINCREMENT, "file_id" integer NOT NULL, "cell_id" integer NOT NULL, "due" timestamp_with_timezone_text NOT NULL, "stability" float NOT NULL, "difficulty" float NOT NULL, "elapsed_days" integer NOT NULL, "scheduled_days" integer NOT NULL, "reps" integer NOT NULL, "lapses" integer NOT NULL, "state" varchar NOT NULL, "last_review" timestamp_with_timezone_text NOT NULL, "additional_content" varchar, FOREIGN KEY ("file_id") REFERENCES "file" ("id") ON DELETE CASCADE, FOREIGN KEY ("cell_id") REFERENCES "cell" ("id") ON DELETE CASCADE );
+        CREATE UNIQUE INDEX "idx-path" ON "file" ("path", "is_folder");
+        INSERT INTO "file" ("id", "path", "is_folder") VALUES (1, 'folder', true), (2, 'folder/file', false);
+        INSERT INTO "cell" ("id", "file_id", "index", "content", "searchable_content", "cell_type")
+            VALUES (1, 2, 0, '{"question":"Question","answer":"Answer"}', 'question answer', 'FlashCard');
+        INSERT INTO "repetition" ("id", "file_id", "cell_id", "due", "stability", "difficulty", "elapsed_days",
+            "scheduled_days", "reps", "lapses", "state", "last_review", "additional_content")
+            VALUES (1, 2, 1, '2025-01-10 10:00:00 +00:00', 3.5, 5.2, 2, 4, 3, 1, 'Review', '2025-01-06 10:00:00 +00:00', NULL);
+    "#;
+
+    async fn connect(path: &Path) -> DatabaseConnection {
+        Database::connect(format!("sqlite:///{}?mode=rwc", path.to_str().unwrap()))
+            .await
+            .unwrap()
+    }
+
+    async fn count_rows(db: &DatabaseConnection, table: &str) -> i32 {
+        db.query_one(Statement::from_string(
+            db.get_database_backend(),
+            format!(r#"SELECT COUNT(*) AS "count" FROM "{table}""#),
+        ))
+        .await
+        .unwrap()
+        .unwrap()
+        .try_get("", "count")
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn setup_schema_empty_database_applied_all_migrations() {
+        // Arrange
+
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+
+        // Act
+
+        setup_schema(&db).await.unwrap();
+
+        // Assert
+
+        let actual = get_schema_version(&db).await.unwrap();
+        assert_eq!(actual, get_latest_version());
+    }
+
+    #[tokio::test]
+    async fn setup_schema_called_twice_no_error() {
+        // Arrange
+
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        setup_schema(&db).await.unwrap();
+
+        // Act
+
+        let actual = setup_schema(&db).await;
+
+        // Assert
+
+        assert!(actual.is_ok());
+        assert_eq!(get_schema_version(&db).await.unwrap(), get_latest_version());
+    }
+
+    #[tokio::test]
+    async fn setup_schema_baseline_database_file_migrated_forward_and_kept_data() {
+        // Arrange
+
+        let path = get_random_file_path("db");
+        let db = connect(&path).await;
+        db.execute_unprepared(BASELINE_SCHEMA).await.unwrap();
+        db.close().await.unwrap();
+        let db = connect(&path).await;
+
+        // Act
+
+        setup_schema(&db).await.unwrap();
+
+        // Assert
+
+        assert_eq!(get_schema_version(&db).await.unwrap(), get_latest_version());
+        assert_eq!(count_rows(&db, "file").await, 2);
+        assert_eq!(count_rows(&db, "cell").await, 1);
+        assert_eq!(count_rows(&db, "repetition").await, 1);
+    }
+
+    #[tokio::test]
+    async fn setup_schema_newer_database_returned_error() {
+        // Arrange
+
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        setup_schema(&db).await.unwrap();
+        let newer_version = get_latest_version() + 1;
+        db.execute_unprepared(&format!(
+            r#"INSERT INTO "schema_version" ("version", "name", "applied_at")
+            VALUES ({newer_version}, 'future', '')"#
+        ))
+        .await
+        .unwrap();
+
+        // Act
+
+        let actual = setup_schema(&db).await;
+
+        // Assert
+
+        assert!(actual.is_err());
+        assert_eq!(get_schema_version(&db).await.unwrap(), newer_version);
+    }
+
+    #[tokio::test]
+    async fn migrate_down_to_zero_reverted_all_migrations() {
+        // Arrange
+
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        setup_schema(&db).await.unwrap();
+
+        // Act
+
+        migrate_down_to(&db, 0).await.unwrap();
+
+        // Assert
+
+        assert_eq!(get_schema_version(&db).await.unwrap(), 0);
+        let tables_count = db
+            .query_one(Statement::from_string(
+                db.get_database_backend(),
+                r#"SELECT COUNT(*) AS "count" FROM "sqlite_master"
+                WHERE "type" = 'table' AND "name" IN ('file', 'cell', 'repetition', 'review_log',
+                'scheduler_parameters', 'trash', 'cell_fts', 'tag', 'cell_tag')"#,
+            ))
+            .await
+            .unwrap()
+            .unwrap()
+            .try_get::<i32>("", "count")
+            .unwrap();
+        assert_eq!(tables_count, 0);
+        setup_schema(&db).await.unwrap();
+        assert_eq!(get_schema_version(&db).await.unwrap(), get_latest_version());
+    }
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{cell, cell_tag, file, repetition, review_log, scheduler_parameters, tag};
+
+/// Everything that is removed by the cascades when deleting a cell, a file or
+/// a folder.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedItems {
+    pub files: Vec<file::Model>,
+    pub cells: Vec<cell::Model>,
+    pub repetitions: Vec<repetition::Model>,
+    pub review_logs: Vec<review_log::Model>,
+    pub scheduler_parameters: Vec<scheduler_parameters::Model>,
+    /// Missing in items deleted before tags existed.
+    #[serde(default)]
+    pub cell_tags: Vec<cell_tag::Model>,
+    /// The tags of `cell_tags`, which are restored by name.
+    #[serde(default)]
+    pub tags: Vec<tag::Model>,
+}
@@ -5,4 +5,17 @@ use serde::{Deserialize, Serialize};
 pub struct FlashCard {
     pub question: String,
     pub answer: String,
+    /// Also asks for the question given the answer, with a repetition of its
+    /// own.
+    #[serde(default)]
+    pub is_reversible: bool,
+}
+
+impl FlashCard {
+    /// The additional content of the back repetition of reversible flash
+    /// cards, the front repetition has none.
+    pub const BACK: &str = "back";
+    /// Some exports name the front repetition, it is imported as the one
+    /// without additional content.
+    pub const FRONT: &str = "front";
 }
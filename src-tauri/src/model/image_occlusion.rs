@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageOcclusion {
+    /// The `src` of the image, a media URL or a data URI.
+    pub image: String,
+    /// Every mask is reviewed on its own, with the other masks shown.
+    pub masks: Vec<OcclusionMask>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcclusionMask {
+    /// Stays the same when other masks are added or removed, it is the
+    /// additional content of the repetition of the mask.
+    pub id: u32,
+    /// What the mask hides, shown once the answer is shown.
+    #[serde(default)]
+    pub label: String,
+    pub shape: MaskShape,
+}
+
+/// The coordinates are fractions of the width and height of the image, so
+/// that the masks fit the image at any size.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum MaskShape {
+    Rectangle {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    },
+    Polygon {
+        points: Vec<(f64, f64)>,
+    },
+}
+
+impl ImageOcclusion {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.image.trim().is_empty() {
+            return Err("An image occlusion must have an image!".into());
+        }
+        if self.masks.is_empty() {
+            return Err("An image occlusion must have at least one mask!".into());
+        }
+        for (i, mask) in self.masks.iter().enumerate() {
+            if self.masks[..i].iter().any(|other| other.id == mask.id) {
+                return Err(format!("The mask {} is repeated!", mask.id));
+            }
+            mask.validate()?;
+        }
+        Ok(())
+    }
+}
+
+impl OcclusionMask {
+    fn validate(&self) -> Result<(), String> {
+        let is_inside = |value: f64| (0.0..=1.0).contains(&value);
+        let is_valid = match &self.shape {
+            MaskShape::Rectangle {
+                x,
+                y,
+                width,
+                height,
+            } => {
+                *width > 0.0
+                    && *height > 0.0
+                    && is_inside(*x)
+                    && is_inside(*y)
+                    && is_inside(x + width)
+                    && is_inside(y + height)
+            }
+            MaskShape::Polygon { points } => {
+                if points.len() < 3 {
+                    return Err(format!(
+                        "The polygon of the mask {} must have at least three points!",
+                        self.id
+                    ));
+                }
+                points.iter().all(|(x, y)| is_inside(*x) && is_inside(*y))
+            }
+        };
+        if !is_valid {
+            return Err(format!("The mask {} is outside of the image!", self.id));
+        }
+        Ok(())
+    }
+}
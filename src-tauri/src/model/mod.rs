@@ -1,4 +1,12 @@
+pub mod deleted_items;
 pub mod file_repetitions_count;
 pub mod flash_card;
+pub mod image_occlusion;
+pub mod multiple_choice;
+pub mod ordering;
+pub mod rating;
+pub mod search_query;
 pub mod settings;
 pub mod true_false;
+pub mod type_answer;
+pub mod undo_history;
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultipleChoice {
+    pub question: String,
+    pub options: Vec<String>,
+    /// The indices of the correct options, there can be several.
+    pub correct_indices: Vec<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<String>,
+    /// Shows the options in a random order on every review.
+    #[serde(default)]
+    pub shuffle_options: bool,
+}
+
+impl MultipleChoice {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.options.len() < 2 {
+            return Err("A multiple choice must have at least two options!".into());
+        }
+        if self.correct_indices.is_empty() {
+            return Err("A multiple choice must have at least one correct option!".into());
+        }
+        for (i, index) in self.correct_indices.iter().enumerate() {
+            if *index >= self.options.len() {
+                return Err(format!("The correct option {index} does not exist!"));
+            }
+            if self.correct_indices[..i].contains(index) {
+                return Err(format!("The correct option {index} is repeated!"));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ordering {
+    pub question: String,
+    /// The items in their correct order, they are shuffled when reviewing.
+    pub items: Vec<String>,
+}
+
+impl Ordering {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.items.len() < 2 {
+            return Err("An ordering must have at least two items!".into());
+        }
+        if self.items.iter().any(|item| item.trim().is_empty()) {
+            return Err("An item of the ordering is empty!".into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+pub enum Rating {
+    #[sea_orm(string_value = "Again")]
+    Again,
+    #[sea_orm(string_value = "Hard")]
+    Hard,
+    #[default]
+    #[sea_orm(string_value = "Good")]
+    Good,
+    #[sea_orm(string_value = "Easy")]
+    Easy,
+}
+
+impl Rating {
+    /// The numeric grade used by FSRS, from 1 (again) to 4 (easy).
+    pub fn grade(&self) -> i32 {
+        match self {
+            Rating::Again => 1,
+            Rating::Hard => 2,
+            Rating::Good => 3,
+            Rating::Easy => 4,
+        }
+    }
+}
@@ -0,0 +1,350 @@
+use sea_orm::Iterable;
+
+use crate::entity::{cell::CellType, repetition::State};
+
+/// A parsed search such as
+/// `type:cloze folder:"Biology/Cells" tag:lang::spanish state:review due:<3d lapses:>4 -word "exact phrase"`.
+/// Every term and filter must match, a leading `-` negates it.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SearchQuery {
+    pub text_terms: Vec<TextTerm>,
+    pub filters: Vec<Filter>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextTerm {
+    pub text: String,
+    /// Phrases match consecutive words, other terms match word prefixes.
+    pub is_phrase: bool,
+    pub is_negated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    pub kind: FilterKind,
+    pub is_negated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterKind {
+    CellType(CellType),
+    /// Files inside the folder, at any depth.
+    Folder(String),
+    File(String),
+    /// Cells with the tag or one of its child tags.
+    Tag(String),
+    State(State),
+    /// Compares the due date with now plus the given number of days.
+    Due(Comparison, i64),
+    Lapses(Comparison, i32),
+    Reps(Comparison, i32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+struct Token {
+    text: String,
+    is_quoted: bool,
+    is_negated: bool,
+}
+
+impl SearchQuery {
+    pub fn parse(search_text: &str) -> Result<Self, String> {
+        let mut query = SearchQuery::default();
+
+        for token in tokenize(search_text) {
+            if token.is_quoted {
+                query.text_terms.push(TextTerm {
+                    text: token.text,
+                    is_phrase: true,
+                    is_negated: token.is_negated,
+                });
+                continue;
+            }
+
+            let filter_kind = match token.text.split_once(':') {
+                Some((key, value)) => parse_filter(key, value)?,
+                None => None,
+            };
+            match filter_kind {
+                Some(kind) => query.filters.push(Filter {
+                    kind,
+                    is_negated: token.is_negated,
+                }),
+                None => query.text_terms.push(TextTerm {
+                    text: token.text,
+                    is_phrase: false,
+                    is_negated: token.is_negated,
+                }),
+            }
+        }
+
+        Ok(query)
+    }
+}
+
+/// Splits on whitespace outside of double quotes, the quotes are removed.
+fn tokenize(search_text: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut chars = search_text.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            return tokens;
+        }
+
+        let is_negated = chars.next_if_eq(&'-').is_some();
+        let is_quoted = chars.peek() == Some(&'"');
+        let mut text = String::new();
+        let mut inside_quotes = false;
+        while let Some(c) = chars.next_if(|c| inside_quotes || !c.is_whitespace()) {
+            if c == '"' {
+                inside_quotes = !inside_quotes;
+            } else {
+                text.push(c);
+            }
+        }
+
+        // A lone dash is searched as text.
+        if is_negated && text.is_empty() {
+            tokens.push(Token {
+                text: "-".into(),
+                is_quoted: false,
+                is_negated: false,
+            });
+            continue;
+        }
+        tokens.push(Token {
+            text,
+            is_quoted,
+            is_negated,
+        });
+    }
+}
+
+/// Returns `None` when the key is not a filter, so that text like `1:2` is
+/// searched as is.
+fn parse_filter(key: &str, value: &str) -> Result<Option<FilterKind>, String> {
+    let kind = match key.to_lowercase().as_str() {
+        "type" => FilterKind::CellType(parse_variant(CellType::iter(), value, key)?),
+        "folder" => FilterKind::Folder(value.trim_matches('/').to_string()),
+        "file" => FilterKind::File(value.trim_matches('/').to_string()),
+        "tag" => FilterKind::Tag(value.to_string()),
+        "state" => FilterKind::State(parse_variant(State::iter(), value, key)?),
+        "due" => {
+            let (comparison, value) = parse_comparison(value);
+            FilterKind::Due(comparison, parse_days(value)?)
+        }
+        "lapses" => {
+            let (comparison, value) = parse_comparison(value);
+            FilterKind::Lapses(comparison, parse_number(value, key)?)
+        }
+        "reps" => {
+            let (comparison, value) = parse_comparison(value);
+            FilterKind::Reps(comparison, parse_number(value, key)?)
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(kind))
+}
+
+/// Matches the variant names case-insensitively, ignoring `_` and `-` so that
+/// `true_false` matches `TrueFalse`.
+pub fn parse_variant<T: std::fmt::Debug>(
+    variants: impl Iterator<Item = T>,
+    value: &str,
+    key: &str,
+) -> Result<T, String> {
+    let normalize = |text: &str| {
+        text.chars()
+            .filter(|c| *c != '_' && *c != '-')
+            .collect::<String>()
+            .to_lowercase()
+    };
+    let normalized_value = normalize(value);
+    let mut names = vec![];
+    for variant in variants {
+        let name = format!("{variant:?}");
+        if normalize(&name) == normalized_value {
+            return Ok(variant);
+        }
+        names.push(name.to_lowercase());
+    }
+    Err(format!(
+        "Invalid {key} \"{value}\", expected one of: {}!",
+        names.join(", ")
+    ))
+}
+
+fn parse_comparison(value: &str) -> (Comparison, &str) {
+    for (prefix, comparison) in [
+        ("<=", Comparison::LessOrEqual),
+        (">=", Comparison::GreaterOrEqual),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater),
+        ("=", Comparison::Equal),
+    ] {
+        if let Some(value) = value.strip_prefix(prefix) {
+            return (comparison, value);
+        }
+    }
+    (Comparison::Equal, value)
+}
+
+fn parse_number(value: &str, key: &str) -> Result<i32, String> {
+    match value.parse() {
+        Ok(number) => Ok(number),
+        Err(_) => Err(format!("Invalid {key} \"{value}\", expected a number!")),
+    }
+}
+
+/// Parses durations like `3d`, `2w` or `-1d`, a number alone is in days.
+fn parse_days(value: &str) -> Result<i64, String> {
+    let (number, days_per_unit) = if let Some(number) = value.strip_suffix('d') {
+        (number, 1)
+    } else if let Some(number) = value.strip_suffix('w') {
+        (number, 7)
+    } else {
+        (value, 1)
+    };
+    match number
+        .parse::<i64>()
+        .ok()
+        .and_then(|number| number.checked_mul(days_per_unit))
+    {
+        Some(days) => Ok(days),
+        None => Err(format!(
+            "Invalid due \"{value}\", expected a number of days like 3d or 2w!"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_full_query_parsed_terms_and_filters() {
+        // Arrange
+
+        let search_text = r#"type:cloze folder:"Biology/Cells" tag:lang::spanish state:review due:<3d lapses:>4 -word "exact phrase""#;
+
+        // Act
+
+        let actual = SearchQuery::parse(search_text).unwrap();
+
+        // Assert
+
+        let filter = |kind| Filter {
+            kind,
+            is_negated: false,
+        };
+        assert_eq!(
+            actual,
+            SearchQuery {
+                text_terms: vec![
+                    TextTerm {
+                        text: "word".into(),
+                        is_phrase: false,
+                        is_negated: true,
+                    },
+                    TextTerm {
+                        text: "exact phrase".into(),
+                        is_phrase: true,
+                        is_negated: false,
+                    },
+                ],
+                filters: vec![
+                    filter(FilterKind::CellType(CellType::Cloze)),
+                    filter(FilterKind::Folder("Biology/Cells".into())),
+                    filter(FilterKind::Tag("lang::spanish".into())),
+                    filter(FilterKind::State(State::Review)),
+                    filter(FilterKind::Due(Comparison::Less, 3)),
+                    filter(FilterKind::Lapses(Comparison::Greater, 4)),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn parse_negated_filter_and_unknown_key_parsed_as_text() {
+        // Arrange
+
+        let search_text = "-type:true_false ratio:1 reps:<=2 due:-2w";
+
+        // Act
+
+        let actual = SearchQuery::parse(search_text).unwrap();
+
+        // Assert
+
+        assert_eq!(
+            actual.filters,
+            vec![
+                Filter {
+                    kind: FilterKind::CellType(CellType::TrueFalse),
+                    is_negated: true,
+                },
+                Filter {
+                    kind: FilterKind::Reps(Comparison::LessOrEqual, 2),
+                    is_negated: false,
+                },
+                Filter {
+                    kind: FilterKind::Due(Comparison::Equal, -14),
+                    is_negated: false,
+                },
+            ]
+        );
+        assert_eq!(actual.text_terms.len(), 1);
+        assert_eq!(actual.text_terms[0].text, "ratio:1");
+    }
+
+    #[test]
+    fn parse_invalid_value_returned_error() {
+        // Arrange
+
+        let search_text = "state:forgotten";
+
+        // Act
+
+        let actual = SearchQuery::parse(search_text);
+
+        // Assert
+
+        assert_eq!(
+            actual,
+            Err(
+                "Invalid state \"forgotten\", expected one of: new, learning, relearning, review!"
+                    .into()
+            )
+        );
+    }
+    #[test]
+    fn parse_overflowing_due_returned_error() {
+        // Arrange
+
+        let search_text = "due:<2000000000000000000w";
+
+        // Act
+
+        let actual = SearchQuery::parse(search_text);
+
+        // Assert
+
+        assert_eq!(
+            actual,
+            Err(
+                "Invalid due \"2000000000000000000w\", expected a number of days like 3d or 2w!"
+                    .into()
+            )
+        );
+    }
+}
@@ -1,11 +1,21 @@
 use serde::{Deserialize, Serialize};
 
+pub const DEFAULT_TRASH_RETENTION_DAYS: u32 = 30;
+
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Settings {
     pub database_location: String,
     pub theme: Theme,
     pub zoom_percentage: f32,
+    /// Days after which deleted files are purged from the trash, 0 keeps them
+    /// until the trash is emptied.
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u32,
+}
+
+fn default_trash_retention_days() -> u32 {
+    DEFAULT_TRASH_RETENTION_DAYS
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -17,11 +27,17 @@ pub enum Theme {
 }
 
 impl Settings {
-    pub fn new(database_location: String, theme: Theme, zoom_percentage: f32) -> Self {
+    pub fn new(
+        database_location: String,
+        theme: Theme,
+        zoom_percentage: f32,
+        trash_retention_days: u32,
+    ) -> Self {
         Self {
             database_location,
             theme,
             zoom_percentage,
+            trash_retention_days,
         }
     }
 }
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeAnswer {
+    pub question: String,
+    /// Plain text that the typed answer is compared with.
+    pub answer: String,
+    /// Other answers that are accepted as correct.
+    #[serde(default)]
+    pub alternative_answers: Vec<String>,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    #[serde(default)]
+    pub accent_sensitive: bool,
+    /// Otherwise the leading and trailing whitespace is ignored and the other
+    /// runs of whitespace are compared as a single space.
+    #[serde(default)]
+    pub whitespace_sensitive: bool,
+}
+
+impl TypeAnswer {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.answer.trim().is_empty() {
+            return Err("A typed answer must have an answer!".into());
+        }
+        if self
+            .alternative_answers
+            .iter()
+            .any(|answer| answer.trim().is_empty())
+        {
+            return Err("An alternative answer is empty!".into());
+        }
+        Ok(())
+    }
+}
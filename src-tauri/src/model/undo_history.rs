@@ -0,0 +1,123 @@
+use crate::entity::{repetition, review_log};
+
+use super::deleted_items::DeletedItems;
+
+const MAX_HISTORY_LENGTH: usize = 100;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UndoAction {
+    UpdateRepetition {
+        previous: repetition::Model,
+        next: repetition::Model,
+        review_log: review_log::Model,
+    },
+    DeleteCell(DeletedItems),
+    /// Duplicate cells deleted in favor of the most reviewed one, which got
+    /// their tags.
+    MergeCells {
+        kept_cell_id: i32,
+        deleted: DeletedItems,
+    },
+    /// A file or a folder moved to the trash, `path` is where it is restored.
+    MoveToTrash {
+        file_id: i32,
+        trash_id: i32,
+        path: String,
+        is_folder: bool,
+    },
+}
+
+impl UndoAction {
+    /// A short description to show to the user, e.g. "rate card Good".
+    pub fn describe(&self) -> String {
+        match self {
+            UndoAction::UpdateRepetition { review_log, .. } => {
+                format!("rate card {:?}", review_log.rating)
+            }
+            UndoAction::DeleteCell(_) => "delete cell".into(),
+            UndoAction::MergeCells { .. } => "merge duplicate cells".into(),
+            UndoAction::MoveToTrash {
+                path, is_folder, ..
+            } => {
+                if *is_folder {
+                    format!("delete folder \"{path}\"")
+                } else {
+                    format!("delete file \"{path}\"")
+                }
+            }
+        }
+    }
+}
+
+/// The actions that can be undone and redone, the most recent is last.
+#[derive(Debug, Default)]
+pub struct UndoHistory {
+    undo_stack: Vec<UndoAction>,
+    redo_stack: Vec<UndoAction>,
+}
+
+impl UndoHistory {
+    /// Records a new action, this clears the actions that can be redone.
+    pub fn push(&mut self, action: UndoAction) {
+        self.redo_stack.clear();
+        push_bounded(&mut self.undo_stack, action);
+    }
+
+    pub fn pop_undo(&mut self) -> Option<UndoAction> {
+        self.undo_stack.pop()
+    }
+
+    pub fn pop_redo(&mut self) -> Option<UndoAction> {
+        self.redo_stack.pop()
+    }
+
+    pub fn push_undone(&mut self, action: UndoAction) {
+        push_bounded(&mut self.redo_stack, action);
+    }
+
+    pub fn push_redone(&mut self, action: UndoAction) {
+        push_bounded(&mut self.undo_stack, action);
+    }
+
+    pub fn peek_undo(&self) -> Option<&UndoAction> {
+        self.undo_stack.last()
+    }
+
+    pub fn peek_redo(&self) -> Option<&UndoAction> {
+        self.redo_stack.last()
+    }
+
+    /// Drops the reviews of the cells, they cannot be reverted once the
+    /// repetitions of the cells were reset or changed by an edit.
+    pub fn remove_reviews_of_cells(&mut self, cell_ids: &[i32]) {
+        self.retain(|action| match action {
+            UndoAction::UpdateRepetition { previous, .. } => !cell_ids.contains(&previous.cell_id),
+            _ => true,
+        });
+    }
+
+    /// Drops the moves to the trash whose trash items were restored or purged.
+    pub fn remove_moves_to_trash(&mut self, trash_ids: &[i32]) {
+        self.retain(|action| match action {
+            UndoAction::MoveToTrash { trash_id, .. } => !trash_ids.contains(trash_id),
+            _ => true,
+        });
+    }
+
+    fn retain(&mut self, keep: impl Fn(&UndoAction) -> bool) {
+        self.undo_stack.retain(&keep);
+        self.redo_stack.retain(&keep);
+    }
+
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+}
+
+fn push_bounded(stack: &mut Vec<UndoAction>, action: UndoAction) {
+    if stack.len() == MAX_HISTORY_LENGTH {
+        stack.remove(0);
+    }
+    stack.push(action);
+}
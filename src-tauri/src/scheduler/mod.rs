@@ -0,0 +1,439 @@
+pub mod optimizer;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::entity::repetition::{self, State};
+use crate::model::rating::Rating;
+
+const DECAY: f64 = -0.5;
+const FACTOR: f64 = 19.0 / 81.0;
+
+/// The default FSRS-5 weights, the same ones used by `ts-fsrs`.
+pub const DEFAULT_WEIGHTS: [f64; 19] = [
+    0.40255, 1.18385, 3.173, 15.69105, 7.1949, 0.5345, 1.4604, 0.0046, 1.54575, 0.1192, 1.01925,
+    1.9395, 0.11, 0.29605, 2.2698, 0.2315, 2.9898, 0.51655, 0.6621,
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameters {
+    pub weights: [f64; 19],
+    pub request_retention: f64,
+    pub maximum_interval: i32,
+}
+
+impl Default for Parameters {
+    fn default() -> Self {
+        Self {
+            weights: DEFAULT_WEIGHTS,
+            request_retention: 0.9,
+            maximum_interval: 36500,
+        }
+    }
+}
+
+/// Computes the next scheduling state of repetitions using the FSRS-5
+/// algorithm with short-term (learning) steps and without fuzzing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Scheduler {
+    parameters: Parameters,
+}
+
+impl Scheduler {
+    pub fn new(parameters: Parameters) -> Self {
+        Self { parameters }
+    }
+
+    /// Returns the repetition after being reviewed with the given rating at
+    /// `now`. Only the scheduling fields are changed.
+    pub fn review(
+        &self,
+        repetition: &repetition::Model,
+        rating: Rating,
+        now: DateTime<Utc>,
+    ) -> repetition::Model {
+        let mut next = repetition.clone();
+        next.elapsed_days = if repetition.state == State::New {
+            0
+        } else {
+            (now.date_naive() - repetition.last_review.date_naive())
+                .num_days()
+                .max(0) as i32
+        };
+        next.last_review = now;
+        next.reps += 1;
+
+        match repetition.state {
+            State::New => self.review_new(&mut next, rating, now),
+            State::Learning | State::Relearning => {
+                self.review_learning(repetition, &mut next, rating, now)
+            }
+            State::Review => self.review_review(repetition, &mut next, rating, now),
+        }
+
+        next
+    }
+
+    fn review_new(&self, next: &mut repetition::Model, rating: Rating, now: DateTime<Utc>) {
+        next.difficulty = self.init_difficulty(rating) as f32;
+        next.stability = self.init_stability(rating) as f32;
+
+        match rating {
+            Rating::Again => schedule_minutes(next, now, 1, State::Learning),
+            Rating::Hard => schedule_minutes(next, now, 5, State::Learning),
+            Rating::Good => schedule_minutes(next, now, 10, State::Learning),
+            Rating::Easy => {
+                let interval = self.next_interval(next.stability as f64);
+                schedule_days(next, now, interval);
+            }
+        }
+    }
+
+    fn review_learning(
+        &self,
+        last: &repetition::Model,
+        next: &mut repetition::Model,
+        rating: Rating,
+        now: DateTime<Utc>,
+    ) {
+        let last_stability = last.stability as f64;
+        next.difficulty = self.next_difficulty(last.difficulty as f64, rating) as f32;
+        next.stability = self.next_short_term_stability(last_stability, rating) as f32;
+
+        match rating {
+            Rating::Again => schedule_minutes(next, now, 5, last.state.clone()),
+            Rating::Hard => schedule_minutes(next, now, 10, last.state.clone()),
+            Rating::Good => {
+                let interval = self.next_interval(next.stability as f64);
+                schedule_days(next, now, interval);
+            }
+            Rating::Easy => {
+                let good_stability = self.next_short_term_stability(last_stability, Rating::Good);
+                let good_interval = self.next_interval(good_stability);
+                let interval = self
+                    .next_interval(next.stability as f64)
+                    .max(good_interval + 1);
+                schedule_days(next, now, interval);
+            }
+        }
+    }
+
+    fn review_review(
+        &self,
+        last: &repetition::Model,
+        next: &mut repetition::Model,
+        rating: Rating,
+        now: DateTime<Utc>,
+    ) {
+        let difficulty = last.difficulty as f64;
+        let stability = last.stability as f64;
+        let retrievability = forgetting_curve(next.elapsed_days as f64, stability);
+        next.difficulty = self.next_difficulty(difficulty, rating) as f32;
+
+        if rating == Rating::Again {
+            next.stability =
+                self.next_forget_stability(difficulty, stability, retrievability) as f32;
+            next.lapses += 1;
+            schedule_minutes(next, now, 5, State::Relearning);
+            return;
+        }
+
+        let hard_interval = self.next_interval(self.next_recall_stability(
+            difficulty,
+            stability,
+            retrievability,
+            Rating::Hard,
+        ));
+        let good_interval = self.next_interval(self.next_recall_stability(
+            difficulty,
+            stability,
+            retrievability,
+            Rating::Good,
+        ));
+        let hard_interval = hard_interval.min(good_interval);
+        let good_interval = good_interval.max(hard_interval + 1);
+
+        next.stability =
+            self.next_recall_stability(difficulty, stability, retrievability, rating) as f32;
+        let interval = match rating {
+            Rating::Hard => hard_interval,
+            Rating::Good => good_interval,
+            _ => self
+                .next_interval(next.stability as f64)
+                .max(good_interval + 1),
+        };
+        schedule_days(next, now, interval);
+    }
+
+    fn weight(&self, index: usize) -> f64 {
+        self.parameters.weights[index]
+    }
+
+    fn init_stability(&self, rating: Rating) -> f64 {
+        self.weight(rating.grade() as usize - 1).max(0.1)
+    }
+
+    fn init_difficulty(&self, rating: Rating) -> f64 {
+        constrain_difficulty(
+            self.weight(4) - (self.weight(5) * (rating.grade() - 1) as f64).exp() + 1.0,
+        )
+    }
+
+    fn next_interval(&self, stability: f64) -> i32 {
+        let interval_modifier =
+            (self.parameters.request_retention.powf(1.0 / DECAY) - 1.0) / FACTOR;
+        ((stability * interval_modifier).round() as i32).clamp(1, self.parameters.maximum_interval)
+    }
+
+    fn next_difficulty(&self, difficulty: f64, rating: Rating) -> f64 {
+        let delta = -self.weight(6) * (rating.grade() - 3) as f64;
+        let next = difficulty + delta * (10.0 - difficulty) / 9.0;
+        let mean_reversion =
+            self.weight(7) * self.init_difficulty(Rating::Easy) + (1.0 - self.weight(7)) * next;
+        constrain_difficulty(mean_reversion)
+    }
+
+    fn next_recall_stability(
+        &self,
+        difficulty: f64,
+        stability: f64,
+        retrievability: f64,
+        rating: Rating,
+    ) -> f64 {
+        let hard_penalty = if rating == Rating::Hard {
+            self.weight(15)
+        } else {
+            1.0
+        };
+        let easy_bonus = if rating == Rating::Easy {
+            self.weight(16)
+        } else {
+            1.0
+        };
+        stability
+            * (1.0
+                + self.weight(8).exp()
+                    * (11.0 - difficulty)
+                    * stability.powf(-self.weight(9))
+                    * (((1.0 - retrievability) * self.weight(10)).exp() - 1.0)
+                    * hard_penalty
+                    * easy_bonus)
+    }
+
+    /// A lapse never makes the memory more stable than it was.
+    fn next_forget_stability(&self, difficulty: f64, stability: f64, retrievability: f64) -> f64 {
+        (self.weight(11)
+            * difficulty.powf(-self.weight(12))
+            * ((stability + 1.0).powf(self.weight(13)) - 1.0)
+            * ((1.0 - retrievability) * self.weight(14)).exp())
+        .min(stability)
+    }
+
+    fn next_short_term_stability(&self, stability: f64, rating: Rating) -> f64 {
+        stability * (self.weight(17) * ((rating.grade() - 3) as f64 + self.weight(18))).exp()
+    }
+}
+
+/// The probability of recalling a card after `elapsed_days` days.
+pub fn forgetting_curve(elapsed_days: f64, stability: f64) -> f64 {
+    (1.0 + FACTOR * elapsed_days / stability).powf(DECAY)
+}
+
+fn constrain_difficulty(difficulty: f64) -> f64 {
+    difficulty.clamp(1.0, 10.0)
+}
+
+fn schedule_minutes(next: &mut repetition::Model, now: DateTime<Utc>, minutes: i64, state: State) {
+    next.scheduled_days = 0;
+    next.due = now + Duration::minutes(minutes);
+    next.state = state;
+}
+
+fn schedule_days(next: &mut repetition::Model, now: DateTime<Utc>, days: i32) {
+    next.scheduled_days = days;
+    next.due = now + Duration::days(days as i64);
+    next.state = State::Review;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_repetition() -> repetition::Model {
+        repetition::Model {
+            id: 1,
+            file_id: 2,
+            cell_id: 3,
+            state: State::New,
+            ..Default::default()
+        }
+    }
+
+    fn assert_close(actual: f32, expected: f64) {
+        assert!(
+            (actual as f64 - expected).abs() < 1e-3,
+            "expected {expected} but got {actual}"
+        );
+    }
+
+    #[test]
+    fn review_new_repetition_good_started_learning() {
+        // Arrange
+
+        let scheduler = Scheduler::default();
+        let now = Utc::now();
+
+        // Act
+
+        let actual = scheduler.review(&new_repetition(), Rating::Good, now);
+
+        // Assert
+
+        assert_eq!(actual.state, State::Learning);
+        assert_eq!(actual.due, now + Duration::minutes(10));
+        assert_eq!(actual.scheduled_days, 0);
+        assert_eq!(actual.reps, 1);
+        assert_eq!(actual.lapses, 0);
+        assert_eq!(actual.last_review, now);
+        assert_close(actual.stability, 3.173);
+        assert_close(actual.difficulty, 5.28243);
+        assert_eq!(actual.id, 1);
+        assert_eq!(actual.cell_id, 3);
+    }
+
+    #[test]
+    fn review_new_repetition_easy_moved_to_review() {
+        // Arrange
+
+        let scheduler = Scheduler::default();
+        let now = Utc::now();
+
+        // Act
+
+        let actual = scheduler.review(&new_repetition(), Rating::Easy, now);
+
+        // Assert
+
+        assert_eq!(actual.state, State::Review);
+        assert_close(actual.stability, 15.69105);
+        assert_eq!(actual.scheduled_days, 16);
+        assert_eq!(actual.due, now + Duration::days(16));
+    }
+
+    #[test]
+    fn review_learning_repetition_good_graduated() {
+        // Arrange
+
+        let scheduler = Scheduler::default();
+        let now = Utc::now();
+        let learning = scheduler.review(&new_repetition(), Rating::Good, now);
+
+        // Act
+
+        let actual = scheduler.review(&learning, Rating::Good, now + Duration::minutes(10));
+
+        // Assert
+
+        assert_eq!(actual.state, State::Review);
+        assert_eq!(actual.reps, 2);
+        assert_eq!(actual.elapsed_days, 0);
+        assert_close(actual.stability, 3.173 * (0.51655f64 * 0.6621).exp());
+        assert_eq!(actual.scheduled_days, 4);
+    }
+
+    #[test]
+    fn review_review_repetition_again_lapsed() {
+        // Arrange
+
+        let scheduler = Scheduler::default();
+        let now = Utc::now();
+        let repetition = repetition::Model {
+            state: State::Review,
+            stability: 10.0,
+            difficulty: 5.0,
+            reps: 5,
+            lapses: 1,
+            last_review: now - Duration::days(10),
+            ..new_repetition()
+        };
+
+        // Act
+
+        let actual = scheduler.review(&repetition, Rating::Again, now);
+
+        // Assert
+
+        assert_eq!(actual.state, State::Relearning);
+        assert_eq!(actual.lapses, 2);
+        assert_eq!(actual.elapsed_days, 10);
+        assert_eq!(actual.scheduled_days, 0);
+        assert_eq!(actual.due, now + Duration::minutes(5));
+        assert!(actual.stability < repetition.stability);
+        assert!(actual.difficulty > repetition.difficulty);
+    }
+
+    #[test]
+    fn review_review_repetition_again_long_overdue_stability_not_increased() {
+        // Arrange
+
+        let scheduler = Scheduler::default();
+        let now = Utc::now();
+        let repetition = repetition::Model {
+            state: State::Review,
+            stability: 0.5,
+            difficulty: 1.0,
+            reps: 5,
+            last_review: now - Duration::days(100),
+            ..new_repetition()
+        };
+
+        // Act
+
+        let actual = scheduler.review(&repetition, Rating::Again, now);
+
+        // Assert
+
+        assert_eq!(actual.state, State::Relearning);
+        assert!(actual.stability <= repetition.stability);
+    }
+
+    #[test]
+    fn review_review_repetition_intervals_ordered_by_rating() {
+        // Arrange
+
+        let scheduler = Scheduler::default();
+        let now = Utc::now();
+        let repetition = repetition::Model {
+            state: State::Review,
+            stability: 10.0,
+            difficulty: 5.0,
+            reps: 5,
+            last_review: now - Duration::days(10),
+            ..new_repetition()
+        };
+
+        // Act
+
+        let hard = scheduler.review(&repetition, Rating::Hard, now);
+        let good = scheduler.review(&repetition, Rating::Good, now);
+        let easy = scheduler.review(&repetition, Rating::Easy, now);
+
+        // Assert
+
+        assert!(hard.scheduled_days < good.scheduled_days);
+        assert!(good.scheduled_days < easy.scheduled_days);
+        assert!(hard.stability > repetition.stability);
+        assert_eq!(good.state, State::Review);
+        assert_eq!(good.lapses, 0);
+    }
+
+    #[test]
+    fn forgetting_curve_elapsed_equals_stability_returned_requested_retention() {
+        // Act
+
+        let actual = forgetting_curve(10.0, 10.0);
+
+        // Assert
+
+        assert!((actual - 0.9).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,386 @@
+use super::{Parameters, Scheduler, forgetting_curve};
+use crate::model::rating::Rating;
+
+const EPOCHS: usize = 150;
+const LEARNING_RATE: f64 = 0.03;
+const MINIMUM_STABILITY: f64 = 0.01;
+const MINIMUM_TRAINING_REVIEWS: usize = 8;
+
+/// The allowed range of each FSRS-5 weight, the same as the ones used by the
+/// reference optimizer.
+const WEIGHT_BOUNDS: [(f64, f64); 19] = [
+    (0.01, 100.0),
+    (0.01, 100.0),
+    (0.01, 100.0),
+    (0.01, 100.0),
+    (1.0, 10.0),
+    (0.001, 4.0),
+    (0.001, 4.0),
+    (0.001, 0.75),
+    (0.0, 4.5),
+    (0.0, 0.8),
+    (0.001, 3.5),
+    (0.001, 5.0),
+    (0.001, 0.25),
+    (0.001, 0.9),
+    (0.0, 4.0),
+    (0.0, 1.0),
+    (1.0, 6.0),
+    (0.0, 2.0),
+    (0.0, 2.0),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Review {
+    pub rating: Rating,
+    /// Days since the previous review, zero for the first review.
+    pub elapsed_days: i32,
+}
+
+/// The ordered answers given to a single repetition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewHistory {
+    /// The stability and difficulty before the first review, `None` when the
+    /// history starts from a new repetition.
+    pub initial_memory_state: Option<(f64, f64)>,
+    pub reviews: Vec<Review>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evaluation {
+    pub log_loss: f64,
+    pub rmse: f64,
+    pub review_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Optimization {
+    pub parameters: Parameters,
+    pub before: Evaluation,
+    pub after: Evaluation,
+}
+
+/// Fits the weights to the review histories using gradient descent on the
+/// log loss of the predicted recall probabilities. The result only depends on
+/// the input, so the same history always gives the same weights.
+pub fn optimize(
+    histories: &[ReviewHistory],
+    initial_parameters: &Parameters,
+) -> Result<Optimization, String> {
+    let before = evaluate(histories, initial_parameters);
+    if before.review_count < MINIMUM_TRAINING_REVIEWS {
+        return Err(format!(
+            "At least {MINIMUM_TRAINING_REVIEWS} reviews done after a day or more are needed \
+            to optimize the parameters, only {} were found!",
+            before.review_count
+        ));
+    }
+
+    let mut weights = initial_parameters.weights;
+    let mut first_moments = [0f64; 19];
+    let mut second_moments = [0f64; 19];
+
+    for epoch in 1..=EPOCHS {
+        let gradient = compute_gradient(histories, initial_parameters, &weights);
+        for i in 0..weights.len() {
+            // Adam keeps the step size similar for weights of different scales.
+            first_moments[i] = 0.9 * first_moments[i] + 0.1 * gradient[i];
+            second_moments[i] = 0.999 * second_moments[i] + 0.001 * gradient[i] * gradient[i];
+            let first_moment = first_moments[i] / (1.0 - 0.9f64.powi(epoch as i32));
+            let second_moment = second_moments[i] / (1.0 - 0.999f64.powi(epoch as i32));
+            weights[i] -= LEARNING_RATE * first_moment / (second_moment.sqrt() + 1e-8);
+            weights[i] = weights[i].clamp(WEIGHT_BOUNDS[i].0, WEIGHT_BOUNDS[i].1);
+        }
+    }
+
+    let optimized_parameters = Parameters {
+        weights,
+        ..initial_parameters.clone()
+    };
+    let after = evaluate(histories, &optimized_parameters);
+    if after.log_loss >= before.log_loss {
+        return Ok(Optimization {
+            parameters: initial_parameters.clone(),
+            after: before.clone(),
+            before,
+        });
+    }
+
+    Ok(Optimization {
+        parameters: optimized_parameters,
+        before,
+        after,
+    })
+}
+
+/// Measures how well the parameters predict the answers, only the reviews done
+/// at least a day after the previous one are taken into account.
+pub fn evaluate(histories: &[ReviewHistory], parameters: &Parameters) -> Evaluation {
+    let scheduler = Scheduler::new(parameters.clone());
+    let mut log_loss = 0.0;
+    let mut squared_error = 0.0;
+    let mut review_count = 0;
+
+    for history in histories {
+        replay_history(&scheduler, history, |retrievability, recalled| {
+            let retrievability = retrievability.clamp(1e-6, 1.0 - 1e-6);
+            let label = if recalled { 1.0 } else { 0.0 };
+            log_loss -= label * retrievability.ln() + (1.0 - label) * (1.0 - retrievability).ln();
+            squared_error += (retrievability - label).powi(2);
+            review_count += 1;
+        });
+    }
+
+    if review_count == 0 {
+        return Evaluation {
+            log_loss: 0.0,
+            rmse: 0.0,
+            review_count,
+        };
+    }
+
+    Evaluation {
+        log_loss: log_loss / review_count as f64,
+        rmse: (squared_error / review_count as f64).sqrt(),
+        review_count,
+    }
+}
+
+fn compute_gradient(
+    histories: &[ReviewHistory],
+    parameters: &Parameters,
+    weights: &[f64; 19],
+) -> [f64; 19] {
+    let mut gradient = [0f64; 19];
+    for i in 0..weights.len() {
+        let step = 1e-4 * weights[i].abs().max(1.0);
+        let mut increased = *weights;
+        increased[i] = (weights[i] + step).min(WEIGHT_BOUNDS[i].1);
+        let mut decreased = *weights;
+        decreased[i] = (weights[i] - step).max(WEIGHT_BOUNDS[i].0);
+        if increased[i] == decreased[i] {
+            continue;
+        }
+
+        let increased_loss = evaluate(
+            histories,
+            &Parameters {
+                weights: increased,
+                ..parameters.clone()
+            },
+        )
+        .log_loss;
+        let decreased_loss = evaluate(
+            histories,
+            &Parameters {
+                weights: decreased,
+                ..parameters.clone()
+            },
+        )
+        .log_loss;
+        gradient[i] = (increased_loss - decreased_loss) / (increased[i] - decreased[i]);
+    }
+    gradient
+}
+
+/// Replays the memory state through the history and calls `on_prediction`
+/// with the predicted recall probability and the actual answer of every review
+/// done on a different day than the previous one.
+fn replay_history(
+    scheduler: &Scheduler,
+    history: &ReviewHistory,
+    mut on_prediction: impl FnMut(f64, bool),
+) {
+    let mut memory_state = history.initial_memory_state;
+
+    for review in &history.reviews {
+        let rating = review.rating;
+        memory_state = Some(match memory_state {
+            None => (
+                scheduler.init_stability(rating),
+                scheduler.init_difficulty(rating),
+            ),
+            Some((stability, difficulty)) => {
+                let stability = stability.max(MINIMUM_STABILITY);
+                let next_stability = if review.elapsed_days <= 0 {
+                    scheduler.next_short_term_stability(stability, rating)
+                } else {
+                    let retrievability = forgetting_curve(review.elapsed_days as f64, stability);
+                    on_prediction(retrievability, rating != Rating::Again);
+                    if rating == Rating::Again {
+                        scheduler.next_forget_stability(difficulty, stability, retrievability)
+                    } else {
+                        scheduler.next_recall_stability(
+                            difficulty,
+                            stability,
+                            retrievability,
+                            rating,
+                        )
+                    }
+                };
+                (
+                    next_stability.max(MINIMUM_STABILITY),
+                    scheduler.next_difficulty(difficulty, rating),
+                )
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    /// Simulates a learner whose memory follows `parameters` and who reviews
+    /// every card after a fixed number of days.
+    fn simulate_histories(parameters: &Parameters) -> Vec<ReviewHistory> {
+        let scheduler = Scheduler::new(parameters.clone());
+        let mut rng = ChaCha8Rng::from_seed([7u8; 32]);
+        let mut histories = vec![];
+
+        for card in 0..120 {
+            let first_rating = if card % 3 == 0 {
+                Rating::Again
+            } else {
+                Rating::Good
+            };
+            let mut stability = scheduler.init_stability(first_rating);
+            let mut difficulty = scheduler.init_difficulty(first_rating);
+            let mut reviews = vec![Review {
+                rating: first_rating,
+                elapsed_days: 0,
+            }];
+
+            for _ in 0..6 {
+                let elapsed_days = rng.random_range(1..30);
+                let retrievability = forgetting_curve(elapsed_days as f64, stability);
+                let rating = if rng.random::<f64>() < retrievability {
+                    Rating::Good
+                } else {
+                    Rating::Again
+                };
+                stability = if rating == Rating::Again {
+                    scheduler.next_forget_stability(difficulty, stability, retrievability)
+                } else {
+                    scheduler.next_recall_stability(difficulty, stability, retrievability, rating)
+                }
+                .max(MINIMUM_STABILITY);
+                difficulty = scheduler.next_difficulty(difficulty, rating);
+                reviews.push(Review {
+                    rating,
+                    elapsed_days,
+                });
+            }
+
+            histories.push(ReviewHistory {
+                initial_memory_state: None,
+                reviews,
+            });
+        }
+
+        histories
+    }
+
+    fn get_strong_memory_parameters() -> Parameters {
+        let mut parameters = Parameters::default();
+        parameters.weights[0] = 3.0;
+        parameters.weights[2] = 12.0;
+        parameters.weights[8] = 2.2;
+        parameters
+    }
+
+    #[test]
+    fn optimize_synthetic_history_reduced_log_loss() {
+        // Arrange
+
+        let histories = simulate_histories(&get_strong_memory_parameters());
+
+        // Act
+
+        let actual = optimize(&histories, &Parameters::default()).unwrap();
+
+        // Assert
+
+        assert!(actual.after.log_loss < actual.before.log_loss);
+        assert!(actual.after.rmse < actual.before.rmse);
+        assert_eq!(actual.before.review_count, 120 * 6);
+        assert_ne!(actual.parameters.weights, Parameters::default().weights);
+        for (weight, (min, max)) in actual.parameters.weights.iter().zip(WEIGHT_BOUNDS) {
+            assert!(*weight >= min && *weight <= max);
+        }
+    }
+
+    #[test]
+    fn optimize_same_history_returned_same_parameters() {
+        // Arrange
+
+        let histories = simulate_histories(&get_strong_memory_parameters());
+
+        // Act
+
+        let first = optimize(&histories, &Parameters::default()).unwrap();
+        let second = optimize(&histories, &Parameters::default()).unwrap();
+
+        // Assert
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn optimize_not_enough_reviews_returned_error() {
+        // Arrange
+
+        let histories = vec![ReviewHistory {
+            initial_memory_state: None,
+            reviews: vec![
+                Review {
+                    rating: Rating::Good,
+                    elapsed_days: 0,
+                },
+                Review {
+                    rating: Rating::Good,
+                    elapsed_days: 3,
+                },
+            ],
+        }];
+
+        // Act
+
+        let actual = optimize(&histories, &Parameters::default());
+
+        // Assert
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn evaluate_history_with_initial_memory_state_counted_every_review() {
+        // Arrange
+
+        let histories = vec![ReviewHistory {
+            initial_memory_state: Some((10.0, 5.0)),
+            reviews: vec![
+                Review {
+                    rating: Rating::Good,
+                    elapsed_days: 10,
+                },
+                Review {
+                    rating: Rating::Again,
+                    elapsed_days: 0,
+                },
+            ],
+        }];
+
+        // Act
+
+        let actual = evaluate(&histories, &Parameters::default());
+
+        // Assert
+
+        assert_eq!(actual.review_count, 1);
+        assert!((actual.log_loss + 0.9f64.ln()).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,1897 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
+use lol_html::html_content::Element;
+use lol_html::{RewriteStrSettings, element, rewrite_str};
+use rand::Rng;
+use regex::{Captures, Regex};
+use sea_orm::{Database, DbBackend, DbConn, FromQueryResult, Statement, entity::*, query::*};
+use serde::Deserialize;
+use serde::de::IgnoredAny;
+use serde_json::json;
+use sha1::{Digest, Sha1};
+use zip::{ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+use crate::dto::anki_import_result::{AnkiImportResult, UnmappedNoteType};
+use crate::entity::cell::{self, CellType};
+use crate::entity::repetition::{self, State};
+use crate::model::{
+    flash_card::FlashCard, multiple_choice::MultipleChoice, ordering::Ordering,
+    true_false::TrueFalse, type_answer::TypeAnswer,
+};
+use crate::util::media_util::{
+    get_media_reference, get_media_store_path, get_referenced_media_name,
+};
+
+use super::{
+    cell_service, export_import_service, file_service, media_service, repetition_service,
+    tag_service,
+};
+
+/// Separates the fields of a note in the `flds` column.
+const FIELD_SEPARATOR: char = '\u{1f}';
+const DECK_SEPARATOR: &str = "::";
+const CLOZE_NOTE_TYPE: i32 = 1;
+const SECONDS_PER_DAY: i64 = 86_400;
+/// Due values above this are timestamps of learning cards, the others are days.
+const DUE_TIMESTAMP_THRESHOLD: i64 = 1_000_000_000;
+const DEFAULT_DIFFICULTY: f32 = 5.0;
+
+#[derive(Deserialize)]
+struct AnkiNoteType {
+    name: String,
+    #[serde(rename = "type", default)]
+    kind: i32,
+    flds: Vec<IgnoredAny>,
+}
+
+#[derive(Deserialize)]
+struct AnkiDeck {
+    name: String,
+}
+
+#[derive(FromQueryResult)]
+struct AnkiCollectionRow {
+    crt: i64,
+    models: String,
+    decks: String,
+}
+
+#[derive(FromQueryResult)]
+struct AnkiNote {
+    id: i64,
+    mid: i64,
+    flds: String,
+    /// Separated by spaces, `::` separates the levels like in our tags.
+    tags: String,
+}
+
+#[derive(FromQueryResult)]
+struct AnkiCard {
+    nid: i64,
+    did: i64,
+    ord: i32,
+    /// 0 new, 1 learning, 2 review and 3 relearning.
+    card_type: i32,
+    due: i64,
+    ivl: i32,
+    /// The ease in permille.
+    factor: i32,
+    reps: i32,
+    lapses: i32,
+}
+
+struct AnkiCollection {
+    /// Review due dates are days after the creation of the collection.
+    creation_time: i64,
+    note_types: HashMap<String, AnkiNoteType>,
+    decks: HashMap<String, AnkiDeck>,
+    notes: Vec<AnkiNote>,
+    cards: Vec<AnkiCard>,
+}
+
+/// Imports the notes of an Anki `.apkg` or `.colpkg` package into the folder.
+/// Basic note types become flash cards and cloze note types become cloze
+/// cells, notes of other types are skipped and reported in the result. The
+/// media files of the package are streamed into the media store.
+pub async fn import_anki_package(
+    db_conn: &DbConn,
+    package_path: String,
+    import_into_folder_id: i32,
+    import_scheduling: bool,
+    media_directory: &Path,
+) -> Result<AnkiImportResult, String> {
+    let package_file = match File::open(package_path) {
+        Ok(file) => file,
+        Err(err) => return Err(err.to_string()),
+    };
+    let mut archive = match ZipArchive::new(package_file) {
+        Ok(archive) => archive,
+        Err(err) => return Err(err.to_string()),
+    };
+    let collection = read_collection(&mut archive).await?;
+    let media = import_media(&mut archive, media_directory)?;
+
+    let import_into_folder_path = if import_into_folder_id == 0 {
+        "".into()
+    } else {
+        file_service::get_by_id(db_conn, import_into_folder_id)
+            .await?
+            .path
+    };
+
+    let txn = match db_conn.begin().await {
+        Ok(txn) => txn,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let import_result = import_collection(
+        &txn,
+        &collection,
+        &media,
+        &import_into_folder_path,
+        import_scheduling,
+    )
+    .await?;
+
+    let result = txn.commit().await;
+    match result {
+        Ok(_) => Ok(import_result),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+async fn read_collection(archive: &mut ZipArchive<File>) -> Result<AnkiCollection, String> {
+    // Packages of recent Anki versions contain a compressed collection in
+    // `collection.anki21b` next to a placeholder `collection.anki2`.
+    let collection_name = if archive.index_for_name("collection.anki21").is_some() {
+        "collection.anki21"
+    } else if archive.index_for_name("collection.anki21b").is_some() {
+        return Err(
+            "The package uses the newest Anki format, export it again with \"Support older Anki versions\" checked!"
+                .into(),
+        );
+    } else if archive.index_for_name("collection.anki2").is_some() {
+        "collection.anki2"
+    } else {
+        return Err("The package does not contain an Anki collection!".into());
+    };
+
+    let collection_path = std::env::temp_dir().join(format!(
+        "brainy-anki-{}.sqlite",
+        rand::rng().random::<u64>()
+    ));
+    if let Err(err) = fs::write(
+        &collection_path,
+        read_archive_file(archive, collection_name)?,
+    ) {
+        return Err(err.to_string());
+    }
+
+    let result = read_collection_database(&collection_path).await;
+    let _ = fs::remove_file(&collection_path);
+    result
+}
+
+async fn read_collection_database(path: &Path) -> Result<AnkiCollection, String> {
+    let db_conn = match Database::connect(format!("sqlite:{}?mode=ro", path.display())).await {
+        Ok(db_conn) => db_conn,
+        Err(err) => return Err(err.to_string()),
+    };
+    let result = query_collection(&db_conn).await;
+    let _ = db_conn.close().await;
+    result
+}
+
+async fn query_collection(db_conn: &DbConn) -> Result<AnkiCollection, String> {
+    let result = AnkiCollectionRow::find_by_statement(Statement::from_string(
+        DbBackend::Sqlite,
+        "SELECT crt, models, decks FROM col",
+    ))
+    .one(db_conn)
+    .await;
+    let collection_row = match result {
+        Ok(Some(row)) => row,
+        Ok(None) => return Err("The Anki collection is empty!".into()),
+        Err(err) => return Err(err.to_string()),
+    };
+    let note_types = match serde_json::from_str(&collection_row.models) {
+        Ok(note_types) => note_types,
+        Err(err) => return Err(err.to_string()),
+    };
+    let decks = match serde_json::from_str(&collection_row.decks) {
+        Ok(decks) => decks,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let result = AnkiNote::find_by_statement(Statement::from_string(
+        DbBackend::Sqlite,
+        "SELECT id, mid, flds, tags FROM notes ORDER BY id",
+    ))
+    .all(db_conn)
+    .await;
+    let notes = match result {
+        Ok(notes) => notes,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let result = AnkiCard::find_by_statement(Statement::from_string(
+        DbBackend::Sqlite,
+        "SELECT nid, did, ord, type AS card_type, due, ivl, factor, reps, lapses
+        FROM cards ORDER BY nid, ord",
+    ))
+    .all(db_conn)
+    .await;
+    let cards = match result {
+        Ok(cards) => cards,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    Ok(AnkiCollection {
+        creation_time: collection_row.crt,
+        note_types,
+        decks,
+        notes,
+        cards,
+    })
+}
+
+/// Stores the media files in the media store and returns their references by
+/// their original file names. The package stores them under numbers listed in
+/// the `media` JSON file.
+fn import_media(
+    archive: &mut ZipArchive<File>,
+    media_directory: &Path,
+) -> Result<HashMap<String, String>, String> {
+    if archive.index_for_name("media").is_none() {
+        return Ok(HashMap::new());
+    }
+    let media_names: HashMap<String, String> =
+        match serde_json::from_slice(&read_archive_file(archive, "media")?) {
+            Ok(media_names) => media_names,
+            Err(err) => return Err(format!("Cannot read the media list: {err}")),
+        };
+
+    let mut media = HashMap::with_capacity(media_names.len());
+    for (archive_name, file_name) in media_names {
+        let archive_file = match archive.by_name(&archive_name) {
+            Ok(archive_file) => archive_file,
+            Err(_) => continue,
+        };
+        let extension = file_name.rsplit_once('.').map(|(_, extension)| extension);
+        let stored_name = media_service::store_media(media_directory, archive_file, extension)?;
+        media.insert(file_name, get_media_reference(&stored_name));
+    }
+    Ok(media)
+}
+
+fn read_archive_file(archive: &mut ZipArchive<File>, name: &str) -> Result<Vec<u8>, String> {
+    let mut archive_file = match archive.by_name(name) {
+        Ok(archive_file) => archive_file,
+        Err(err) => return Err(err.to_string()),
+    };
+    let mut bytes = vec![];
+    match archive_file.read_to_end(&mut bytes) {
+        Ok(_) => Ok(bytes),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+async fn import_collection(
+    db_conn: &impl ConnectionTrait,
+    collection: &AnkiCollection,
+    media: &HashMap<String, String>,
+    import_into_folder_path: &str,
+    import_scheduling: bool,
+) -> Result<AnkiImportResult, String> {
+    let mut note_cards: HashMap<i64, Vec<&AnkiCard>> = HashMap::new();
+    for card in &collection.cards {
+        note_cards.entry(card.nid).or_default().push(card);
+    }
+
+    // A note goes to the deck of its first card.
+    let mut deck_notes: BTreeMap<i64, Vec<(&AnkiNote, CellType)>> = BTreeMap::new();
+    let mut unmapped_note_types: BTreeMap<String, u32> = BTreeMap::new();
+    for note in &collection.notes {
+        let Some(cards) = note_cards.get(&note.id) else {
+            continue;
+        };
+        let note_type = collection.note_types.get(&note.mid.to_string());
+        match note_type.and_then(get_cell_type) {
+            Some(cell_type) => deck_notes
+                .entry(cards[0].did)
+                .or_default()
+                .push((note, cell_type)),
+            None => {
+                let name = match note_type {
+                    Some(note_type) => note_type.name.clone(),
+                    None => format!("Unknown note type {}", note.mid),
+                };
+                *unmapped_note_types.entry(name).or_default() += 1;
+            }
+        }
+    }
+
+    let deck_names: HashMap<i64, String> = deck_notes
+        .keys()
+        .map(|deck_id| {
+            let name = match collection.decks.get(&deck_id.to_string()) {
+                Some(deck) => deck.name.clone(),
+                None => "Default".into(),
+            };
+            (*deck_id, name)
+        })
+        .collect();
+    let file_paths = get_deck_file_paths(&deck_names);
+
+    let mut imported_notes_count = 0;
+    for (deck_id, notes) in deck_notes {
+        let file_id = file_service::create_file(
+            db_conn,
+            format!("{import_into_folder_path}/{}", file_paths[&deck_id]),
+        )
+        .await?;
+
+        for (index, (note, cell_type)) in notes.into_iter().enumerate() {
+            let fields: Vec<String> = note
+                .flds
+                .split(FIELD_SEPARATOR)
+                .map(|field| link_media(field, media))
+                .collect();
+            let is_reversible = note_cards[&note.id].iter().any(|card| card.ord == 1);
+            let content = export_import_service::purify_html(&get_content(
+                &fields,
+                &cell_type,
+                is_reversible,
+            ));
+            let cell_id = cell_service::create_cell_no_transaction(
+                db_conn,
+                file_id,
+                &content,
+                &cell_type,
+                index as i32,
+            )
+            .await?;
+            // Anki accepts tags with empty levels, which are skipped.
+            let tags: Vec<String> = note
+                .tags
+                .split_whitespace()
+                .filter(|tag| tag_service::normalize_tag_name(tag).is_ok())
+                .map(String::from)
+                .collect();
+            tag_service::add_tags_no_transaction(db_conn, cell_id, &tags).await?;
+
+            if import_scheduling {
+                import_scheduling_states(
+                    db_conn,
+                    cell_id,
+                    &note_cards[&note.id],
+                    collection.creation_time,
+                )
+                .await?;
+            }
+            imported_notes_count += 1;
+        }
+    }
+
+    Ok(AnkiImportResult {
+        imported_notes_count,
+        unmapped_note_types: unmapped_note_types
+            .into_iter()
+            .map(|(name, notes_count)| UnmappedNoteType { name, notes_count })
+            .collect(),
+    })
+}
+
+/// Note types with two fields, like Basic or Basic (and reversed card), are
+/// imported as flash cards, which are reversible when the note has a second
+/// card.
+fn get_cell_type(note_type: &AnkiNoteType) -> Option<CellType> {
+    if note_type.kind == CLOZE_NOTE_TYPE {
+        Some(CellType::Cloze)
+    } else if note_type.flds.len() == 2 {
+        Some(CellType::FlashCard)
+    } else {
+        None
+    }
+}
+
+/// Decks with subdecks become folders, their own notes go to a file with the
+/// same name inside the folder.
+fn get_deck_file_paths(deck_names: &HashMap<i64, String>) -> HashMap<i64, String> {
+    deck_names
+        .iter()
+        .map(|(deck_id, name)| {
+            let names: Vec<String> = name
+                .split(DECK_SEPARATOR)
+                .map(|name| name.replace('/', "-").trim().to_string())
+                .collect();
+            let mut path = names.join("/");
+            let subdeck_prefix = format!("{name}{DECK_SEPARATOR}");
+            if deck_names
+                .values()
+                .any(|other_name| other_name.starts_with(&subdeck_prefix))
+            {
+                path = format!("{path}/{}", names[names.len() - 1]);
+            }
+            (*deck_id, path)
+        })
+        .collect()
+}
+
+fn get_content(fields: &[String], cell_type: &CellType, is_reversible: bool) -> String {
+    match cell_type {
+        CellType::Cloze => {
+            // The other fields, like Back Extra, are shown under the text.
+            let mut content = convert_clozes(&fields[0]);
+            for field in fields[1..].iter().filter(|field| !field.trim().is_empty()) {
+                content.push_str("<br>");
+                content.push_str(field);
+            }
+            content
+        }
+        _ => serde_json::to_string(&FlashCard {
+            question: fields[0].clone(),
+            answer: fields.get(1).cloned().unwrap_or_default(),
+            is_reversible,
+        })
+        .unwrap(),
+    }
+}
+
+/// Converts `{{c1::text::hint}}` to `<cloze index="1">text</cloze>`, hints
+/// are dropped.
+pub fn convert_clozes(text: &str) -> String {
+    let re = Regex::new(r"(?s)\{\{c(\d+)::(.*?)(?:::.*?)?\}\}").expect("Invalid regex");
+    re.replace_all(text, "<cloze index=\"$1\">$2</cloze>")
+        .to_string()
+}
+
+/// Replaces references to media files of the package with references to the
+/// media store, Anki sounds like `[sound:file.mp3]` become audio elements.
+fn link_media(html: &str, media: &HashMap<String, String>) -> String {
+    let sound_regex = Regex::new(r"\[sound:([^\]]+)\]").expect("Invalid regex");
+    let html = sound_regex.replace_all(html, |captures: &Captures| {
+        format!(
+            "<audio controls src=\"{}\"></audio>",
+            captures[1].replace('"', "&quot;")
+        )
+    });
+    if media.is_empty() {
+        return html.to_string();
+    }
+
+    let handler = |el: &mut Element| {
+        if let Some(reference) = el.get_attribute("src").and_then(|src| media.get(&src)) {
+            el.set_attribute("src", reference)?;
+        }
+
+        Ok(())
+    };
+
+    rewrite_str(
+        &html,
+        RewriteStrSettings {
+            element_content_handlers: vec![element!("[src]", handler)],
+            ..RewriteStrSettings::default()
+        },
+    )
+    .unwrap()
+}
+
+async fn import_scheduling_states(
+    db_conn: &impl ConnectionTrait,
+    cell_id: i32,
+    cards: &[&AnkiCard],
+    creation_time: i64,
+) -> Result<(), String> {
+    let result = repetition::Entity::find()
+        .filter(repetition::Column::CellId.eq(cell_id))
+        .all(db_conn)
+        .await;
+    let repetitions = match result {
+        Ok(repetitions) => repetitions,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    for repetition in repetitions {
+        // Cloze indices start at 1 and Anki card ordinals at 0, the back of a
+        // reversible flash card is the second card.
+        let ord = match repetition.additional_content.as_deref() {
+            Some(FlashCard::BACK) => 1,
+            Some(index) => index.parse::<i32>().unwrap_or(1) - 1,
+            None => 0,
+        };
+        if let Some(card) = cards.iter().find(|card| card.ord == ord) {
+            repetition_service::save_repetition(
+                db_conn,
+                get_scheduled_repetition(repetition, card, creation_time),
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+fn get_scheduled_repetition(
+    mut repetition: repetition::Model,
+    card: &AnkiCard,
+    creation_time: i64,
+) -> repetition::Model {
+    let state = match card.card_type {
+        1 => State::Learning,
+        2 => State::Review,
+        3 => State::Relearning,
+        _ => return repetition,
+    };
+    let due_time = if card.due > DUE_TIMESTAMP_THRESHOLD {
+        card.due
+    } else {
+        creation_time + card.due * SECONDS_PER_DAY
+    };
+    // Negative intervals of learning cards are in seconds.
+    let interval = card.ivl.max(1);
+
+    repetition.due = DateTime::from_timestamp(due_time, 0).unwrap_or_else(Utc::now);
+    repetition.scheduled_days = if state == State::Review { interval } else { 0 };
+    // FSRS stability is the interval at which the retention drops to 90%,
+    // which is close to what the Anki interval aims for.
+    repetition.stability = interval as f32;
+    repetition.difficulty = get_difficulty(card.factor);
+    repetition.reps = card.reps;
+    repetition.lapses = card.lapses;
+    repetition.last_review =
+        (repetition.due - Duration::days(repetition.scheduled_days as i64)).min(Utc::now());
+    repetition.state = state;
+    repetition
+}
+
+/// Maps the Anki ease to an FSRS difficulty from 1 to 10, the minimum ease of
+/// 130% is the hardest.
+fn get_difficulty(factor: i32) -> f32 {
+    if factor <= 0 {
+        return DEFAULT_DIFFICULTY;
+    }
+    let ease = factor as f32 / 1000.0;
+    (10.0 - (ease - 1.3) * 5.0).clamp(1.0, 10.0)
+}
+
+struct AnkiExport {
+    note_type_ids: AnkiNoteTypeIds,
+    /// Deck ids by their names.
+    decks: BTreeMap<String, i64>,
+    notes: Vec<ExportedNote>,
+    next_id: i64,
+}
+
+struct AnkiNoteTypeIds {
+    basic: i64,
+    /// The note type of reversible flash cards, with a card per direction.
+    reversed: i64,
+    cloze: i64,
+    /// The note type of note cells, they are skipped without it.
+    note: Option<i64>,
+}
+
+struct ExportedNote {
+    note_type_id: i64,
+    deck_id: i64,
+    fields: Vec<String>,
+    card_ords: Vec<i32>,
+    tags: Vec<String>,
+}
+
+impl AnkiExport {
+    fn new(exports_notes: bool) -> Self {
+        let start_id = Utc::now().timestamp_millis();
+        Self {
+            note_type_ids: AnkiNoteTypeIds {
+                basic: start_id,
+                reversed: start_id + 1,
+                cloze: start_id + 2,
+                note: if exports_notes {
+                    Some(start_id + 3)
+                } else {
+                    None
+                },
+            },
+            decks: BTreeMap::new(),
+            notes: vec![],
+            next_id: start_id + 4,
+        }
+    }
+
+    /// Anki ids are timestamps in milliseconds, consecutive ones keep them
+    /// unique.
+    fn get_next_id(&mut self) -> i64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    /// Returns the id of the deck, creating it together with its parents.
+    fn get_deck_id(&mut self, name: &str) -> i64 {
+        if let Some(deck_id) = self.decks.get(name) {
+            return *deck_id;
+        }
+        if let Some((parent_name, _)) = name.rsplit_once(DECK_SEPARATOR) {
+            self.get_deck_id(parent_name);
+        }
+        let deck_id = self.get_next_id();
+        self.decks.insert(name.to_string(), deck_id);
+        deck_id
+    }
+}
+
+/// Exports the file or folder as an Anki `.apkg` package. Folders become
+/// parent decks and files become decks. Flash cards and true or false cells
+/// become Basic notes, cloze cells become Cloze notes, and note cells are
+/// exported with a single field note type named `note_type_name` when given.
+pub async fn export_anki_package(
+    db_conn: &DbConn,
+    item_id: i32,
+    export_path: String,
+    note_type_name: Option<String>,
+    media_directory: &Path,
+) -> Result<(), String> {
+    let item = file_service::get_by_id(db_conn, item_id).await?;
+    let skip_prefix_length = match item.path.rfind('/') {
+        Some(index) => index + 1,
+        None => 0,
+    };
+    let mut files = if item.is_folder {
+        file_service::list_folder_children_recursively(db_conn, item_id)
+            .await?
+            .into_iter()
+            .filter(|file| !file.is_folder)
+            .collect()
+    } else {
+        vec![item]
+    };
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut export = AnkiExport::new(note_type_name.is_some());
+    for file in files {
+        let deck_name = file.path[skip_prefix_length..].replace('/', DECK_SEPARATOR);
+        let deck_id = export.get_deck_id(&deck_name);
+        let cells = cell_service::get_file_cells_ordered_by_index(db_conn, file.id).await?;
+        let mut cell_tags =
+            tag_service::get_tags_for_cells(db_conn, cells.iter().map(|cell| cell.id).collect())
+                .await?;
+        for cell in cells {
+            if let Some(mut note) = get_exported_note(&cell, deck_id, &export.note_type_ids) {
+                note.tags = cell_tags.remove(&cell.id).unwrap_or_default();
+                export.notes.push(note);
+            }
+        }
+    }
+
+    let media_paths = export_media(&mut export, media_directory);
+
+    let collection_path = std::env::temp_dir().join(format!(
+        "brainy-anki-{}.sqlite",
+        rand::rng().random::<u64>()
+    ));
+    let result = write_collection_database(&collection_path, &mut export, note_type_name).await;
+    let collection = fs::read(&collection_path);
+    let _ = fs::remove_file(&collection_path);
+    result?;
+    let collection = match collection {
+        Ok(collection) => collection,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    write_package(&export_path, &collection, &media_paths)
+}
+
+/// Changes the media store references in the fields to plain file names,
+/// which is how Anki references the media of the package, and returns the
+/// paths of the files by these names. Other local files are never exported.
+fn export_media(export: &mut AnkiExport, media_directory: &Path) -> BTreeMap<String, PathBuf> {
+    let mut media_paths = BTreeMap::new();
+    for field in export
+        .notes
+        .iter_mut()
+        .flat_map(|note| note.fields.iter_mut())
+    {
+        *field = export_import_service::rewrite_sources(field, |src| {
+            let name = get_referenced_media_name(src)?;
+            let path = get_media_store_path(src, media_directory).filter(|path| path.is_file())?;
+            media_paths.insert(name.to_string(), path);
+            Some(name.to_string())
+        });
+    }
+    media_paths
+}
+
+/// Lists the options under the question, and the correct options followed by
+/// the explanation on the back, since Anki has no multiple choice notes.
+fn get_multiple_choice_fields(multiple_choice: MultipleChoice) -> Vec<String> {
+    let list_items = |indices: &mut dyn Iterator<Item = usize>| {
+        indices
+            .filter_map(|index| multiple_choice.options.get(index))
+            .map(|option| format!("<li>{option}</li>"))
+            .collect::<String>()
+    };
+    let front = format!(
+        "{}<ol>{}</ol>",
+        multiple_choice.question,
+        list_items(&mut (0..multiple_choice.options.len()))
+    );
+    let mut back = format!(
+        "<ul>{}</ul>",
+        list_items(&mut multiple_choice.correct_indices.iter().copied())
+    );
+    if let Some(explanation) = &multiple_choice.explanation {
+        back.push_str(explanation);
+    }
+    vec![front, back]
+}
+
+/// Lists the items in alphabetical order under the question, so that their
+/// order is not given away, and in their correct order on the back.
+fn get_ordering_fields(ordering: Ordering) -> Vec<String> {
+    let list_items = |items: &[String]| {
+        items
+            .iter()
+            .map(|item| format!("<li>{item}</li>"))
+            .collect::<String>()
+    };
+    let mut sorted_items = ordering.items.clone();
+    sorted_items.sort();
+    let front = format!(
+        "{}<ul>{}</ul>",
+        ordering.question,
+        list_items(&sorted_items)
+    );
+    let back = format!("<ol>{}</ol>", list_items(&ordering.items));
+    vec![front, back]
+}
+
+fn get_exported_note(
+    cell: &cell::Model,
+    deck_id: i64,
+    note_type_ids: &AnkiNoteTypeIds,
+) -> Option<ExportedNote> {
+    let (note_type_id, fields, card_ords) = match cell.cell_type {
+        CellType::FlashCard => {
+            let flash_card: FlashCard = serde_json::from_str(&cell.content).ok()?;
+            if flash_card.is_reversible {
+                (
+                    note_type_ids.reversed,
+                    vec![flash_card.question, flash_card.answer],
+                    vec![0, 1],
+                )
+            } else {
+                (
+                    note_type_ids.basic,
+                    vec![flash_card.question, flash_card.answer],
+                    vec![0],
+                )
+            }
+        }
+        CellType::TrueFalse => {
+            let true_false: TrueFalse = serde_json::from_str(&cell.content).ok()?;
+            let verdict = if true_false.is_true { "True" } else { "False" };
+            (
+                note_type_ids.basic,
+                vec![true_false.question, verdict.into()],
+                vec![0],
+            )
+        }
+        CellType::MultipleChoice => {
+            let multiple_choice: MultipleChoice = serde_json::from_str(&cell.content).ok()?;
+            (
+                note_type_ids.basic,
+                get_multiple_choice_fields(multiple_choice),
+                vec![0],
+            )
+        }
+        CellType::TypeAnswer => {
+            let type_answer: TypeAnswer = serde_json::from_str(&cell.content).ok()?;
+            let mut answers = vec![type_answer.answer];
+            answers.extend(type_answer.alternative_answers);
+            (
+                note_type_ids.basic,
+                vec![type_answer.question, answers.join(" / ")],
+                vec![0],
+            )
+        }
+        CellType::Ordering => {
+            let ordering: Ordering = serde_json::from_str(&cell.content).ok()?;
+            (note_type_ids.basic, get_ordering_fields(ordering), vec![0])
+        }
+        CellType::Cloze => {
+            let (text, indices) = convert_cloze_cells(&cell.content);
+            // Anki does not accept cloze notes without clozes.
+            if indices.is_empty() {
+                return None;
+            }
+            (
+                note_type_ids.cloze,
+                vec![text, "".into()],
+                indices.into_iter().map(|index| index - 1).collect(),
+            )
+        }
+        CellType::Note => (note_type_ids.note?, vec![cell.content.clone()], vec![0]),
+        // The masks need the image occlusion note type of newer Anki versions,
+        // which the exported collection format does not have.
+        CellType::ImageOcclusion => return None,
+    };
+
+    Some(ExportedNote {
+        note_type_id,
+        deck_id,
+        fields,
+        card_ords,
+        tags: vec![],
+    })
+}
+
+/// Converts `<cloze index="1">text</cloze>` to `{{c1::text}}` and returns the
+/// used indices in order.
+fn convert_cloze_cells(content: &str) -> (String, Vec<i32>) {
+    let re =
+        Regex::new(r#"(?s)<cloze[^>]*index="(\d+)"[^>]*>(.*?)</cloze>"#).expect("Invalid regex");
+    let mut indices: Vec<i32> = re
+        .captures_iter(content)
+        .filter_map(|captures| captures[1].parse().ok())
+        .filter(|index| *index > 0)
+        .collect();
+    indices.sort();
+    indices.dedup();
+    let text = re.replace_all(content, "{{c$1::$2}}").to_string();
+    (text, indices)
+}
+
+async fn write_collection_database(
+    path: &Path,
+    export: &mut AnkiExport,
+    note_type_name: Option<String>,
+) -> Result<(), String> {
+    let db_conn = match Database::connect(format!("sqlite:{}?mode=rwc", path.display())).await {
+        Ok(db_conn) => db_conn,
+        Err(err) => return Err(err.to_string()),
+    };
+    let result = insert_collection(&db_conn, export, note_type_name).await;
+    let _ = db_conn.close().await;
+    result
+}
+
+async fn insert_collection(
+    db_conn: &DbConn,
+    export: &mut AnkiExport,
+    note_type_name: Option<String>,
+) -> Result<(), String> {
+    for statement in COLLECTION_SCHEMA {
+        if let Err(err) = db_conn.execute_unprepared(statement).await {
+            return Err(err.to_string());
+        }
+    }
+
+    let now = Utc::now();
+    let modification_time = now.timestamp();
+    let creation_time = now
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_utc()
+        .timestamp();
+    let ids = &export.note_type_ids;
+    let mut note_types = serde_json::Map::new();
+    note_types.insert(
+        ids.basic.to_string(),
+        get_note_type_json(
+            ids.basic,
+            "Basic",
+            0,
+            &["Front", "Back"],
+            &[("{{Front}}", "{{FrontSide}}\n\n<hr id=answer>\n\n{{Back}}")],
+        ),
+    );
+    note_types.insert(
+        ids.reversed.to_string(),
+        get_note_type_json(
+            ids.reversed,
+            "Basic (and reversed card)",
+            0,
+            &["Front", "Back"],
+            &[
+                ("{{Front}}", "{{FrontSide}}\n\n<hr id=answer>\n\n{{Back}}"),
+                ("{{Back}}", "{{FrontSide}}\n\n<hr id=answer>\n\n{{Front}}"),
+            ],
+        ),
+    );
+    note_types.insert(
+        ids.cloze.to_string(),
+        get_note_type_json(
+            ids.cloze,
+            "Cloze",
+            CLOZE_NOTE_TYPE,
+            &["Text", "Back Extra"],
+            &[("{{cloze:Text}}", "{{cloze:Text}}<br>\n{{Back Extra}}")],
+        ),
+    );
+    if let (Some(note_id), Some(name)) = (ids.note, note_type_name) {
+        note_types.insert(
+            note_id.to_string(),
+            get_note_type_json(
+                note_id,
+                &name,
+                0,
+                &["Text"],
+                &[("{{Text}}", "{{FrontSide}}")],
+            ),
+        );
+    }
+    let mut decks = serde_json::Map::new();
+    decks.insert("1".into(), get_deck_json(1, "Default"));
+    for (name, deck_id) in &export.decks {
+        decks.insert(deck_id.to_string(), get_deck_json(*deck_id, name));
+    }
+    let configuration = json!({
+        "activeDecks": [1],
+        "curDeck": 1,
+        "curModel": ids.basic,
+        "nextPos": export.notes.len() + 1,
+        "sortType": "noteFld",
+        "sortBackwards": false,
+        "addToCur": true,
+        "newSpread": 0,
+        "dueCounts": true,
+        "estTimes": true,
+        "collapseTime": 1200,
+        "timeLim": 0,
+    });
+
+    let result = db_conn
+        .execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT INTO col VALUES (1, ?, ?, ?, 11, 0, 0, 0, ?, ?, ?, ?, '{}')",
+            [
+                creation_time.into(),
+                (modification_time * 1000).into(),
+                (modification_time * 1000).into(),
+                configuration.to_string().into(),
+                serde_json::Value::Object(note_types).to_string().into(),
+                serde_json::Value::Object(decks).to_string().into(),
+                json!({ "1": get_deck_configuration_json() })
+                    .to_string()
+                    .into(),
+            ],
+        ))
+        .await;
+    if let Err(err) = result {
+        return Err(err.to_string());
+    }
+
+    let notes = std::mem::take(&mut export.notes);
+    let remove_html_regex = Regex::new("<[^>]*>").expect("Invalid regex");
+    for (position, note) in notes.iter().enumerate() {
+        let note_id = export.get_next_id();
+        let sort_field = remove_html_regex
+            .replace_all(&note.fields[0], "")
+            .to_string();
+        let result = db_conn
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "INSERT INTO notes VALUES (?, ?, ?, ?, -1, ?, ?, ?, ?, 0, '')",
+                [
+                    note_id.into(),
+                    get_guid().into(),
+                    note.note_type_id.into(),
+                    modification_time.into(),
+                    get_tags_field(&note.tags).into(),
+                    note.fields.join(&FIELD_SEPARATOR.to_string()).into(),
+                    sort_field.clone().into(),
+                    get_checksum(&sort_field).into(),
+                ],
+            ))
+            .await;
+        if let Err(err) = result {
+            return Err(err.to_string());
+        }
+
+        for ord in &note.card_ords {
+            let card_id = export.get_next_id();
+            let result = db_conn
+                .execute(Statement::from_sql_and_values(
+                    DbBackend::Sqlite,
+                    "INSERT INTO cards VALUES
+                    (?, ?, ?, ?, ?, -1, 0, 0, ?, 0, 0, 0, 0, 0, 0, 0, 0, '')",
+                    [
+                        card_id.into(),
+                        note_id.into(),
+                        note.deck_id.into(),
+                        (*ord).into(),
+                        modification_time.into(),
+                        (position as i64 + 1).into(),
+                    ],
+                ))
+                .await;
+            if let Err(err) = result {
+                return Err(err.to_string());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the package, the media files are stored under numbers which the
+/// `media` JSON file maps to their names.
+fn write_package(
+    export_path: &str,
+    collection: &[u8],
+    media_paths: &BTreeMap<String, PathBuf>,
+) -> Result<(), String> {
+    let package_file = match File::create(export_path) {
+        Ok(file) => file,
+        Err(err) => return Err(err.to_string()),
+    };
+    let mut zip = ZipWriter::new(package_file);
+    let media_names: HashMap<String, &String> = media_paths
+        .keys()
+        .enumerate()
+        .map(|(i, name)| (i.to_string(), name))
+        .collect();
+    let media_json = serde_json::to_vec(&media_names).unwrap();
+    for (name, bytes) in [("collection.anki2", collection), ("media", &media_json)] {
+        if let Err(err) = zip.start_file(name, SimpleFileOptions::default()) {
+            return Err(err.to_string());
+        }
+        if let Err(err) = zip.write_all(bytes) {
+            return Err(err.to_string());
+        }
+    }
+    for (i, path) in media_paths.values().enumerate() {
+        if let Err(err) = zip.start_file(i.to_string(), SimpleFileOptions::default()) {
+            return Err(err.to_string());
+        }
+        let mut media_file = match File::open(path) {
+            Ok(file) => file,
+            Err(err) => return Err(err.to_string()),
+        };
+        if let Err(err) = io::copy(&mut media_file, &mut zip) {
+            return Err(err.to_string());
+        }
+    }
+    match zip.finish() {
+        Ok(_) => Ok(()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+fn get_note_type_json(
+    id: i64,
+    name: &str,
+    kind: i32,
+    field_names: &[&str],
+    // The question and answer formats of every card.
+    templates: &[(&str, &str)],
+) -> serde_json::Value {
+    let fields: Vec<serde_json::Value> = field_names
+        .iter()
+        .enumerate()
+        .map(|(ord, name)| {
+            json!({
+                "name": name,
+                "ord": ord,
+                "sticky": false,
+                "rtl": false,
+                "font": "Arial",
+                "size": 20,
+                "media": [],
+            })
+        })
+        .collect();
+    let template_jsons: Vec<serde_json::Value> = templates
+        .iter()
+        .enumerate()
+        .map(|(ord, (question_format, answer_format))| {
+            json!({
+                "name": if kind == CLOZE_NOTE_TYPE {
+                    "Cloze".into()
+                } else {
+                    format!("Card {}", ord + 1)
+                },
+                "ord": ord,
+                "qfmt": question_format,
+                "afmt": answer_format,
+                "bqfmt": "",
+                "bafmt": "",
+                "did": null,
+            })
+        })
+        .collect();
+    // Every card requires the field with its ordinal, like in the Anki
+    // defaults.
+    let requirements: Vec<serde_json::Value> = (0..templates.len())
+        .map(|ord| json!([ord, "any", [ord]]))
+        .collect();
+    json!({
+        "id": id,
+        "name": name,
+        "type": kind,
+        "mod": Utc::now().timestamp(),
+        "usn": -1,
+        "sortf": 0,
+        "did": 1,
+        "flds": fields,
+        "tmpls": template_jsons,
+        "css": ".card {\n font-family: arial;\n font-size: 20px;\n text-align: center;\n color: black;\n background-color: white;\n}\n\n.cloze {\n font-weight: bold;\n color: blue;\n}",
+        "latexPre": "\\documentclass[12pt]{article}\n\\special{papersize=3in,5in}\n\\usepackage[utf8]{inputenc}\n\\usepackage{amssymb,amsmath}\n\\pagestyle{empty}\n\\setlength{\\parindent}{0in}\n\\begin{document}\n",
+        "latexPost": "\\end{document}",
+        "latexsvg": false,
+        "req": requirements,
+        "tags": [],
+        "vers": [],
+    })
+}
+
+fn get_deck_json(id: i64, name: &str) -> serde_json::Value {
+    json!({
+        "id": id,
+        "name": name,
+        "mod": Utc::now().timestamp(),
+        "usn": -1,
+        "desc": "",
+        "dyn": 0,
+        "conf": 1,
+        "collapsed": false,
+        "browserCollapsed": false,
+        "extendNew": 0,
+        "extendRev": 0,
+        "newToday": [0, 0],
+        "revToday": [0, 0],
+        "lrnToday": [0, 0],
+        "timeToday": [0, 0],
+    })
+}
+
+fn get_deck_configuration_json() -> serde_json::Value {
+    json!({
+        "id": 1,
+        "name": "Default",
+        "mod": 0,
+        "usn": 0,
+        "dyn": false,
+        "maxTaken": 60,
+        "timer": 0,
+        "autoplay": true,
+        "replayq": true,
+        "new": {
+            "delays": [1.0, 10.0],
+            "ints": [1, 4, 0],
+            "initialFactor": 2500,
+            "order": 1,
+            "perDay": 20,
+            "bury": false,
+        },
+        "rev": {
+            "perDay": 200,
+            "ease4": 1.3,
+            "ivlFct": 1.0,
+            "maxIvl": 36500,
+            "hardFactor": 1.2,
+            "bury": false,
+        },
+        "lapse": {
+            "delays": [10.0],
+            "mult": 0.0,
+            "minInt": 1,
+            "leechFails": 8,
+            "leechAction": 1,
+        },
+    })
+}
+
+/// Anki surrounds the tags with spaces so that a tag can be searched with
+/// `LIKE '% tag %'`.
+fn get_tags_field(tags: &[String]) -> String {
+    if tags.is_empty() {
+        String::new()
+    } else {
+        format!(" {} ", tags.join(" "))
+    }
+}
+
+/// Anki note guids are random 64-bit numbers in base 91.
+fn get_guid() -> String {
+    const CHARACTERS: &[u8] =
+        b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!#$%&()*+,-./:;<=>?@[]^_`{|}~";
+    let mut number = rand::rng().random::<u64>();
+    let mut guid = String::new();
+    while number > 0 {
+        guid.push(CHARACTERS[(number % CHARACTERS.len() as u64) as usize] as char);
+        number /= CHARACTERS.len() as u64;
+    }
+    guid
+}
+
+/// The first 32 bits of the SHA-1 of the sort field, Anki uses it to find
+/// duplicates.
+fn get_checksum(sort_field: &str) -> i64 {
+    let digest = Sha1::digest(sort_field.as_bytes());
+    u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]) as i64
+}
+
+/// The schema 11 collection, which all Anki versions can import.
+const COLLECTION_SCHEMA: &[&str] = &[
+    "CREATE TABLE col (
+        id integer PRIMARY KEY, crt integer NOT NULL, mod integer NOT NULL,
+        scm integer NOT NULL, ver integer NOT NULL, dty integer NOT NULL,
+        usn integer NOT NULL, ls integer NOT NULL, conf text NOT NULL,
+        models text NOT NULL, decks text NOT NULL, dconf text NOT NULL, tags text NOT NULL
+    )",
+    "CREATE TABLE notes (
+        id integer PRIMARY KEY, guid text NOT NULL, mid integer NOT NULL,
+        mod integer NOT NULL, usn integer NOT NULL, tags text NOT NULL,
+        flds text NOT NULL, sfld integer NOT NULL, csum integer NOT NULL,
+        flags integer NOT NULL, data text NOT NULL
+    )",
+    "CREATE TABLE cards (
+        id integer PRIMARY KEY, nid integer NOT NULL, did integer NOT NULL,
+        ord integer NOT NULL, mod integer NOT NULL, usn integer NOT NULL,
+        type integer NOT NULL, queue integer NOT NULL, due integer NOT NULL,
+        ivl integer NOT NULL, factor integer NOT NULL, reps integer NOT NULL,
+        lapses integer NOT NULL, left integer NOT NULL, odue integer NOT NULL,
+        odid integer NOT NULL, flags integer NOT NULL, data text NOT NULL
+    )",
+    "CREATE TABLE revlog (
+        id integer PRIMARY KEY, cid integer NOT NULL, usn integer NOT NULL,
+        ease integer NOT NULL, ivl integer NOT NULL, lastIvl integer NOT NULL,
+        factor integer NOT NULL, time integer NOT NULL, type integer NOT NULL
+    )",
+    "CREATE TABLE graves (usn integer NOT NULL, oid integer NOT NULL, type integer NOT NULL)",
+    "CREATE INDEX ix_notes_usn ON notes (usn)",
+    "CREATE INDEX ix_cards_usn ON cards (usn)",
+    "CREATE INDEX ix_revlog_usn ON revlog (usn)",
+    "CREATE INDEX ix_cards_nid ON cards (nid)",
+    "CREATE INDEX ix_cards_sched ON cards (did, queue, due)",
+    "CREATE INDEX ix_revlog_cid ON revlog (cid)",
+    "CREATE INDEX ix_notes_csum ON notes (csum)",
+];
+
+#[cfg(test)]
+mod tests {
+    use sha2::{Digest, Sha256};
+
+    use super::*;
+    use crate::service::tests::{
+        create_file_cell_with_cell_type_and_content, get_db, get_random_file_path,
+    };
+
+    const MODELS: &str = r#"{
+        "1": {"name": "Basic", "type": 0, "flds": [{"name": "Front"}, {"name": "Back"}]},
+        "2": {"name": "Cloze", "type": 1, "flds": [{"name": "Text"}, {"name": "Back Extra"}]},
+        "3": {"name": "Vocabulary", "type": 0, "flds": [{"name": "Word"}, {"name": "Meaning"}, {"name": "Example"}]}
+    }"#;
+    const DECKS: &str = r#"{
+        "1": {"name": "Default"},
+        "10": {"name": "Languages"},
+        "11": {"name": "Languages::Spanish"}
+    }"#;
+    const CREATION_TIME: i64 = 1_700_000_000;
+
+    /// Writes a package with the given notes as `(id, note type id, fields)`
+    /// and cards as `(note id, deck id, ord, type, due, ivl, factor)`.
+    async fn create_package(
+        collection_name: &str,
+        notes: &[(i64, i64, &str)],
+        cards: &[(i64, i64, i32, i32, i64, i32, i32)],
+        media: &[(&str, &[u8])],
+    ) -> PathBuf {
+        let collection_path = get_random_file_path("anki2");
+        let collection =
+            Database::connect(format!("sqlite:{}?mode=rwc", collection_path.display()))
+                .await
+                .unwrap();
+        let mut statements = vec![
+            "CREATE TABLE col (id integer PRIMARY KEY, crt integer, models text, decks text)"
+                .to_string(),
+            "CREATE TABLE notes (id integer PRIMARY KEY, mid integer, flds text, tags text NOT NULL DEFAULT '')"
+                .to_string(),
+            "CREATE TABLE cards (id integer PRIMARY KEY, nid integer, did integer, ord integer,
+            type integer, due integer, ivl integer, factor integer, reps integer, lapses integer)"
+                .to_string(),
+            format!("INSERT INTO col VALUES (1, {CREATION_TIME}, '{MODELS}', '{DECKS}')"),
+        ];
+        for (id, mid, fields) in notes {
+            statements.push(format!(
+                "INSERT INTO notes (id, mid, flds) VALUES ({id}, {mid}, '{}')",
+                fields.replace('|', "\u{1f}")
+            ));
+        }
+        for (nid, did, ord, card_type, due, ivl, factor) in cards {
+            statements.push(format!(
+                "INSERT INTO cards (nid, did, ord, type, due, ivl, factor, reps, lapses)
+                VALUES ({nid}, {did}, {ord}, {card_type}, {due}, {ivl}, {factor}, 5, 1)"
+            ));
+        }
+        for statement in statements {
+            collection.execute_unprepared(&statement).await.unwrap();
+        }
+        collection.close().await.unwrap();
+
+        let package_path = get_random_file_path("apkg");
+        let mut zip = ZipWriter::new(File::create(&package_path).unwrap());
+        zip.start_file(collection_name, SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(&fs::read(&collection_path).unwrap()).unwrap();
+        let media_names: HashMap<String, &str> = media
+            .iter()
+            .enumerate()
+            .map(|(i, (name, _))| (i.to_string(), *name))
+            .collect();
+        zip.start_file("media", SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(serde_json::to_string(&media_names).unwrap().as_bytes())
+            .unwrap();
+        for (i, (_, bytes)) in media.iter().enumerate() {
+            zip.start_file(i.to_string(), SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(bytes).unwrap();
+        }
+        zip.finish().unwrap();
+
+        package_path
+    }
+
+    #[tokio::test]
+    async fn import_anki_package_basic_and_cloze_notes_imported_decks_as_files() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let folder_id = file_service::create_folder(&db_conn, "anki".into())
+            .await
+            .unwrap();
+        let package_path = create_package(
+            "collection.anki2",
+            &[
+                (100, 1, r#"perro <img src="dog.png">|dog"#),
+                (
+                    101,
+                    2,
+                    "{{c1::Madrid}} is the capital of {{c2::Spain::country}}|Extra",
+                ),
+            ],
+            &[(100, 11, 0, 0, 1, 0, 0), (101, 10, 0, 0, 2, 0, 0)],
+            &[("dog.png", b"image")],
+        )
+        .await;
+
+        // Act
+
+        let media_directory = get_random_file_path("media");
+        let actual = import_anki_package(
+            &db_conn,
+            package_path.to_str().unwrap().into(),
+            folder_id,
+            false,
+            &media_directory,
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        assert_eq!(actual.imported_notes_count, 2);
+        assert!(actual.unmapped_note_types.is_empty());
+
+        let files = file_service::list_folder_children_recursively(&db_conn, folder_id)
+            .await
+            .unwrap();
+        let spanish_file = files
+            .iter()
+            .find(|file| file.path == "anki/Languages/Spanish" && !file.is_folder)
+            .unwrap();
+        let languages_file = files
+            .iter()
+            .find(|file| file.path == "anki/Languages/Languages" && !file.is_folder)
+            .unwrap();
+
+        let spanish_cells =
+            cell_service::get_file_cells_ordered_by_index(&db_conn, spanish_file.id)
+                .await
+                .unwrap();
+        assert_eq!(spanish_cells.len(), 1);
+        assert_eq!(spanish_cells[0].cell_type, CellType::FlashCard);
+        let flash_card: FlashCard = serde_json::from_str(&spanish_cells[0].content).unwrap();
+        let media_name = format!("{:x}.png", Sha256::digest(b"image"));
+        assert_eq!(
+            flash_card.question,
+            format!(r#"perro <img src="{}">"#, get_media_reference(&media_name))
+        );
+        assert_eq!(
+            fs::read(media_directory.join(media_name)).unwrap(),
+            b"image"
+        );
+        assert_eq!(flash_card.answer, "dog");
+
+        let languages_cells =
+            cell_service::get_file_cells_ordered_by_index(&db_conn, languages_file.id)
+                .await
+                .unwrap();
+        assert_eq!(languages_cells.len(), 1);
+        assert_eq!(languages_cells[0].cell_type, CellType::Cloze);
+        assert_eq!(
+            languages_cells[0].content,
+            r#"<cloze index="1">Madrid</cloze> is the capital of <cloze index="2">Spain</cloze><br>Extra"#
+        );
+        let repetitions = repetition_service::get_file_repetitions(&db_conn, languages_file.id)
+            .await
+            .unwrap();
+        assert_eq!(repetitions.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn import_anki_package_unmapped_note_type_reported_and_skipped() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let package_path = create_package(
+            "collection.anki21",
+            &[
+                (100, 3, "hablar|to speak|Yo hablo"),
+                (101, 3, "comer|to eat|Yo como"),
+                (102, 1, "front|back"),
+            ],
+            &[
+                (100, 1, 0, 0, 1, 0, 0),
+                (101, 1, 0, 0, 2, 0, 0),
+                (102, 1, 0, 0, 3, 0, 0),
+            ],
+            &[],
+        )
+        .await;
+
+        // Act
+
+        let actual = import_anki_package(
+            &db_conn,
+            package_path.to_str().unwrap().into(),
+            0,
+            false,
+            &get_random_file_path("media"),
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        assert_eq!(actual.imported_notes_count, 1);
+        assert_eq!(
+            actual.unmapped_note_types,
+            vec![UnmappedNoteType {
+                name: "Vocabulary".into(),
+                notes_count: 2,
+            }]
+        );
+        let files = file_service::get_files(&db_conn).await.unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "Default");
+    }
+
+    #[tokio::test]
+    async fn import_anki_package_with_scheduling_imported_review_state() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let package_path = create_package(
+            "collection.anki2",
+            &[(100, 1, "front|back"), (101, 1, "new front|new back")],
+            &[(100, 1, 0, 2, 400, 20, 2500), (101, 1, 0, 0, 1, 0, 0)],
+            &[],
+        )
+        .await;
+
+        // Act
+
+        import_anki_package(
+            &db_conn,
+            package_path.to_str().unwrap().into(),
+            0,
+            true,
+            &get_random_file_path("media"),
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        let file_id = file_service::get_files(&db_conn).await.unwrap()[0].id;
+        let cells = cell_service::get_file_cells_ordered_by_index(&db_conn, file_id)
+            .await
+            .unwrap();
+        let repetitions = repetition_service::get_file_repetitions(&db_conn, file_id)
+            .await
+            .unwrap();
+        let review = repetitions
+            .iter()
+            .find(|repetition| repetition.cell_id == cells[0].id)
+            .unwrap();
+        assert_eq!(review.state, State::Review);
+        assert_eq!(
+            review.due.timestamp(),
+            CREATION_TIME + 400 * SECONDS_PER_DAY
+        );
+        assert_eq!(review.scheduled_days, 20);
+        assert_eq!(review.stability, 20.0);
+        assert_eq!(review.difficulty, 4.0);
+        assert_eq!(review.reps, 5);
+        assert_eq!(review.lapses, 1);
+        let new = repetitions
+            .iter()
+            .find(|repetition| repetition.cell_id == cells[1].id)
+            .unwrap();
+        assert_eq!(new.state, State::New);
+    }
+
+    #[tokio::test]
+    async fn import_anki_package_newest_format_returned_error() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let package_path = create_package("collection.anki21b", &[], &[], &[]).await;
+
+        // Act
+
+        let actual = import_anki_package(
+            &db_conn,
+            package_path.to_str().unwrap().into(),
+            0,
+            false,
+            &get_random_file_path("media"),
+        )
+        .await;
+
+        // Assert
+
+        assert_eq!(
+            actual,
+            Err(
+                "The package uses the newest Anki format, export it again with \"Support older Anki versions\" checked!"
+                    .into()
+            )
+        );
+        assert!(file_service::get_files(&db_conn).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn export_anki_package_folder_exported_decks_notes_and_cards() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let folder_id = file_service::create_folder(&db_conn, "School/Languages".into())
+            .await
+            .unwrap();
+        let (spanish_file_id, _) = create_file_cell_with_cell_type_and_content(
+            &db_conn,
+            "School/Languages/Spanish",
+            CellType::FlashCard,
+            &serde_json::to_string(&FlashCard {
+                question: "perro".into(),
+                answer: "dog".into(),
+                ..Default::default()
+            })
+            .unwrap(),
+        )
+        .await;
+        cell_service::create_cell(
+            &db_conn,
+            spanish_file_id,
+            &serde_json::to_string(&TrueFalse {
+                question: "gato means dog".into(),
+                is_true: false,
+            })
+            .unwrap(),
+            CellType::TrueFalse,
+            1,
+        )
+        .await
+        .unwrap();
+        cell_service::create_cell(&db_conn, spanish_file_id, "a note", CellType::Note, 2)
+            .await
+            .unwrap();
+        create_file_cell_with_cell_type_and_content(
+            &db_conn,
+            "School/Languages/Geography/Capitals",
+            CellType::Cloze,
+            r#"<cloze index="1">Madrid</cloze> is in <cloze index="3">Spain</cloze>"#,
+        )
+        .await;
+        let export_path = get_random_file_path("apkg");
+
+        // Act
+
+        export_anki_package(
+            &db_conn,
+            folder_id,
+            export_path.to_str().unwrap().into(),
+            None,
+            &get_random_file_path("media"),
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        let mut archive = ZipArchive::new(File::open(&export_path).unwrap()).unwrap();
+        let collection = read_collection(&mut archive).await.unwrap();
+
+        let mut deck_names: Vec<&str> = collection
+            .decks
+            .values()
+            .map(|deck| deck.name.as_str())
+            .collect();
+        deck_names.sort();
+        assert_eq!(
+            deck_names,
+            vec![
+                "Default",
+                "Languages",
+                "Languages::Geography",
+                "Languages::Geography::Capitals",
+                "Languages::Spanish",
+            ]
+        );
+
+        assert_eq!(collection.notes.len(), 3);
+        let note_type_names: Vec<&str> = collection
+            .notes
+            .iter()
+            .map(|note| collection.note_types[&note.mid.to_string()].name.as_str())
+            .collect();
+        assert_eq!(note_type_names, vec!["Cloze", "Basic", "Basic"]);
+        assert_eq!(
+            collection.notes[0].flds,
+            "{{c1::Madrid}} is in {{c3::Spain}}\u{1f}"
+        );
+        assert_eq!(collection.notes[1].flds, "perro\u{1f}dog");
+        assert_eq!(collection.notes[2].flds, "gato means dog\u{1f}False");
+
+        let cloze_deck_id = collection
+            .decks
+            .iter()
+            .find(|(_, deck)| deck.name == "Languages::Geography::Capitals")
+            .unwrap()
+            .0;
+        let cloze_cards: Vec<&AnkiCard> = collection
+            .cards
+            .iter()
+            .filter(|card| card.nid == collection.notes[0].id)
+            .collect();
+        assert_eq!(cloze_cards.len(), 2);
+        assert_eq!(cloze_cards[0].ord, 0);
+        assert_eq!(cloze_cards[1].ord, 2);
+        assert_eq!(cloze_cards[0].did.to_string(), *cloze_deck_id);
+        assert_eq!(collection.cards.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn export_anki_package_with_note_type_name_exported_note_cells() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (file_id, _) = create_file_cell_with_cell_type_and_content(
+            &db_conn,
+            "folder/file",
+            CellType::Note,
+            "<p>a note</p>",
+        )
+        .await;
+        let export_path = get_random_file_path("apkg");
+
+        // Act
+
+        export_anki_package(
+            &db_conn,
+            file_id,
+            export_path.to_str().unwrap().into(),
+            Some("Brainy Note".into()),
+            &get_random_file_path("media"),
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        let mut archive = ZipArchive::new(File::open(&export_path).unwrap()).unwrap();
+        let collection = read_collection(&mut archive).await.unwrap();
+        assert_eq!(collection.notes.len(), 1);
+        assert_eq!(collection.notes[0].flds, "<p>a note</p>");
+        let note_type = &collection.note_types[&collection.notes[0].mid.to_string()];
+        assert_eq!(note_type.name, "Brainy Note");
+        assert_eq!(note_type.flds.len(), 1);
+        assert_eq!(collection.cards.len(), 1);
+        assert!(
+            collection
+                .decks
+                .values()
+                .any(|deck| deck.name == "file" && collection.cards[0].did.to_string() != "1")
+        );
+    }
+
+    #[tokio::test]
+    async fn export_anki_package_stored_media_added_to_package() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let media_directory = get_random_file_path("media");
+        let media_file =
+            media_service::add_media_from_bytes(&media_directory, b"image", "png").unwrap();
+        let (file_id, _) = create_file_cell_with_cell_type_and_content(
+            &db_conn,
+            "deck",
+            CellType::FlashCard,
+            &serde_json::to_string(&FlashCard {
+                question: format!("<p><img src=\"{}\"></p>", media_file.url),
+                answer: "image".into(),
+                ..Default::default()
+            })
+            .unwrap(),
+        )
+        .await;
+        let export_path = get_random_file_path("apkg");
+
+        // Act
+
+        export_anki_package(
+            &db_conn,
+            file_id,
+            export_path.to_str().unwrap().into(),
+            None,
+            &media_directory,
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        let mut archive = ZipArchive::new(File::open(&export_path).unwrap()).unwrap();
+        let media_names: HashMap<String, String> =
+            serde_json::from_slice(&read_archive_file(&mut archive, "media").unwrap()).unwrap();
+        assert_eq!(
+            media_names,
+            HashMap::from([("0".into(), media_file.name.clone())])
+        );
+        assert_eq!(read_archive_file(&mut archive, "0").unwrap(), b"image");
+        let collection = read_collection(&mut archive).await.unwrap();
+        assert_eq!(
+            collection.notes[0].flds,
+            format!("<p><img src=\"{}\"></p>\u{1f}image", media_file.name)
+        );
+    }
+
+    #[tokio::test]
+    async fn export_anki_package_then_import_returned_same_cells() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let cloze_content = r#"<cloze index="1">Madrid</cloze> is in Spain"#;
+        let (file_id, cell_id) = create_file_cell_with_cell_type_and_content(
+            &db_conn,
+            "deck",
+            CellType::Cloze,
+            cloze_content,
+        )
+        .await;
+        tag_service::add_tag(&db_conn, vec![cell_id], "geography::europe")
+            .await
+            .unwrap();
+        let export_path = get_random_file_path("apkg");
+        export_anki_package(
+            &db_conn,
+            file_id,
+            export_path.to_str().unwrap().into(),
+            None,
+            &get_random_file_path("media"),
+        )
+        .await
+        .unwrap();
+        let import_folder_id = file_service::create_folder(&db_conn, "imported".into())
+            .await
+            .unwrap();
+
+        // Act
+
+        let actual = import_anki_package(
+            &db_conn,
+            export_path.to_str().unwrap().into(),
+            import_folder_id,
+            false,
+            &get_random_file_path("media"),
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        assert_eq!(actual.imported_notes_count, 1);
+        let imported_file = file_service::list_folder_children(&db_conn, import_folder_id)
+            .await
+            .unwrap()
+            .remove(0);
+        assert_eq!(imported_file.path, "imported/deck");
+        let cells = cell_service::get_file_cells_ordered_by_index(&db_conn, imported_file.id)
+            .await
+            .unwrap();
+        assert_eq!(cells[0].content, cloze_content);
+        let cell_tags = tag_service::get_tags_for_cells(&db_conn, vec![cells[0].id])
+            .await
+            .unwrap();
+        assert_eq!(cell_tags[&cells[0].id], vec!["geography::europe"]);
+    }
+
+    #[tokio::test]
+    async fn export_anki_package_reversible_flash_card_then_import_returned_reversible_flash_card()
+    {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let content = serde_json::to_string(&FlashCard {
+            question: "perro".into(),
+            answer: "dog".into(),
+            is_reversible: true,
+        })
+        .unwrap();
+        let (file_id, _) = create_file_cell_with_cell_type_and_content(
+            &db_conn,
+            "deck",
+            CellType::FlashCard,
+            &content,
+        )
+        .await;
+        let export_path = get_random_file_path("apkg");
+        export_anki_package(
+            &db_conn,
+            file_id,
+            export_path.to_str().unwrap().into(),
+            None,
+            &get_random_file_path("media"),
+        )
+        .await
+        .unwrap();
+        let import_folder_id = file_service::create_folder(&db_conn, "imported".into())
+            .await
+            .unwrap();
+
+        // Act
+
+        import_anki_package(
+            &db_conn,
+            export_path.to_str().unwrap().into(),
+            import_folder_id,
+            false,
+            &get_random_file_path("media"),
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        let mut archive = ZipArchive::new(File::open(&export_path).unwrap()).unwrap();
+        let collection = read_collection(&mut archive).await.unwrap();
+        let note_type = &collection.note_types[&collection.notes[0].mid.to_string()];
+        assert_eq!(note_type.name, "Basic (and reversed card)");
+        let card_ords: Vec<i32> = collection.cards.iter().map(|card| card.ord).collect();
+        assert_eq!(card_ords, vec![0, 1]);
+
+        let imported_file = file_service::list_folder_children(&db_conn, import_folder_id)
+            .await
+            .unwrap()
+            .remove(0);
+        let cells = cell_service::get_file_cells_ordered_by_index(&db_conn, imported_file.id)
+            .await
+            .unwrap();
+        assert_eq!(cells[0].content, content);
+        let repetitions = repetition_service::get_file_repetitions(&db_conn, imported_file.id)
+            .await
+            .unwrap();
+        assert_eq!(repetitions.len(), 2);
+    }
+}
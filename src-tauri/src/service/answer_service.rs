@@ -0,0 +1,435 @@
+use sea_orm::DbConn;
+use unicode_normalization::{UnicodeNormalization, char::is_combining_mark};
+
+use crate::{
+    dto::{
+        answer_comparison::{AnswerComparison, DiffKind, DiffSegment},
+        ordering_grade::OrderingGrade,
+    },
+    entity::cell::CellType,
+    model::{ordering::Ordering, rating::Rating, type_answer::TypeAnswer},
+};
+
+use super::cell_service;
+
+/// Compares the typed answer with the answers of the typed answer cell.
+pub async fn check_typed_answer(
+    db_conn: &DbConn,
+    cell_id: i32,
+    typed_answer: &str,
+) -> Result<AnswerComparison, String> {
+    let cell = cell_service::get_cell_by_id(db_conn, cell_id).await?;
+    if cell.cell_type != CellType::TypeAnswer {
+        return Err("Only the answers of typed answer cells can be checked!".into());
+    }
+    let type_answer: TypeAnswer = match serde_json::from_str(&cell.content) {
+        Ok(type_answer) => type_answer,
+        Err(err) => return Err(err.to_string()),
+    };
+    Ok(compare_answer(&type_answer, typed_answer))
+}
+
+/// Diffs the typed answer with the closest of the accepted answers, the
+/// answer is correct when all of their characters are equal.
+pub fn compare_answer(type_answer: &TypeAnswer, typed_answer: &str) -> AnswerComparison {
+    let typed = prepare(typed_answer, type_answer);
+    let mut closest: Option<(usize, &String, Vec<DiffSegment>)> = None;
+    for answer in [&type_answer.answer]
+        .into_iter()
+        .chain(&type_answer.alternative_answers)
+    {
+        let expected = prepare(answer, type_answer);
+        let (distance, diff) = get_diff(&typed, &expected, type_answer);
+        if closest
+            .as_ref()
+            .is_none_or(|(closest_distance, _, _)| distance < *closest_distance)
+        {
+            closest = Some((distance, answer, diff));
+        }
+    }
+
+    let (distance, expected_answer, diff) = closest.expect("There is always an answer");
+    let is_correct = distance == 0;
+    AnswerComparison {
+        is_correct,
+        expected_answer: expected_answer.clone(),
+        diff,
+        suggested_rating: if is_correct {
+            Rating::Good
+        } else {
+            Rating::Again
+        },
+    }
+}
+
+/// Composes the accents with their letters so that every letter is a single
+/// character, and collapses the whitespace unless it is compared as is.
+fn prepare(text: &str, type_answer: &TypeAnswer) -> Vec<char> {
+    let text: String = text.nfc().collect();
+    if type_answer.whitespace_sensitive {
+        text.chars().collect()
+    } else {
+        text.split_whitespace()
+            .collect::<Vec<&str>>()
+            .join(" ")
+            .chars()
+            .collect()
+    }
+}
+
+/// Returns the form of the character that is compared.
+fn get_key(c: char, type_answer: &TypeAnswer) -> String {
+    let key: String = if type_answer.accent_sensitive {
+        c.to_string()
+    } else {
+        c.to_string()
+            .nfd()
+            .filter(|c| !is_combining_mark(*c))
+            .collect()
+    };
+    if type_answer.case_sensitive {
+        key
+    } else {
+        key.to_lowercase()
+    }
+}
+
+/// Returns the count of the extra and missing characters, and the diff based
+/// on the longest common subsequence of the typed and expected characters.
+fn get_diff(
+    typed: &[char],
+    expected: &[char],
+    type_answer: &TypeAnswer,
+) -> (usize, Vec<DiffSegment>) {
+    let typed_keys: Vec<String> = typed.iter().map(|c| get_key(*c, type_answer)).collect();
+    let expected_keys: Vec<String> = expected.iter().map(|c| get_key(*c, type_answer)).collect();
+
+    // The length of the longest common subsequence of the suffixes.
+    let mut lengths = vec![vec![0usize; expected.len() + 1]; typed.len() + 1];
+    for i in (0..typed.len()).rev() {
+        for j in (0..expected.len()).rev() {
+            lengths[i][j] = if typed_keys[i] == expected_keys[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff: Vec<DiffSegment> = vec![];
+    let mut push = |kind: DiffKind, c: char| match diff.last_mut() {
+        Some(segment) if segment.kind == kind => segment.text.push(c),
+        _ => diff.push(DiffSegment {
+            kind,
+            text: c.to_string(),
+        }),
+    };
+    let (mut i, mut j) = (0, 0);
+    while i < typed.len() || j < expected.len() {
+        if i < typed.len() && j < expected.len() && typed_keys[i] == expected_keys[j] {
+            push(DiffKind::Equal, typed[i]);
+            i += 1;
+            j += 1;
+        } else if j == expected.len() || (i < typed.len() && lengths[i + 1][j] >= lengths[i][j + 1])
+        {
+            push(DiffKind::Extra, typed[i]);
+            i += 1;
+        } else {
+            push(DiffKind::Missing, expected[j]);
+            j += 1;
+        }
+    }
+
+    let distance = typed.len() + expected.len() - 2 * lengths[0][0];
+    (distance, diff)
+}
+
+/// Grades the order in which the items of the ordering cell were put, given
+/// as the indices of the items in their correct order.
+pub async fn grade_ordering(
+    db_conn: &DbConn,
+    cell_id: i32,
+    submitted_order: &[usize],
+) -> Result<OrderingGrade, String> {
+    let cell = cell_service::get_cell_by_id(db_conn, cell_id).await?;
+    if cell.cell_type != CellType::Ordering {
+        return Err("Only the items of ordering cells can be graded!".into());
+    }
+    let ordering: Ordering = match serde_json::from_str(&cell.content) {
+        Ok(ordering) => ordering,
+        Err(err) => return Err(err.to_string()),
+    };
+    grade_order(ordering.items.len(), submitted_order)
+}
+
+pub fn grade_order(items_count: usize, submitted_order: &[usize]) -> Result<OrderingGrade, String> {
+    let mut is_submitted = vec![false; items_count];
+    for index in submitted_order {
+        match is_submitted.get_mut(*index) {
+            Some(is_submitted) if !*is_submitted => *is_submitted = true,
+            _ => return Err("The submitted order must have every item once!".into()),
+        }
+    }
+    if submitted_order.len() != items_count {
+        return Err("The submitted order must have every item once!".into());
+    }
+
+    let in_place: Vec<bool> = submitted_order
+        .iter()
+        .enumerate()
+        .map(|(position, index)| position == *index)
+        .collect();
+    let items_in_place_count = in_place.iter().filter(|in_place| **in_place).count();
+    let longest_ordered_count = get_longest_increasing_subsequence_length(submitted_order);
+    let suggested_rating = if longest_ordered_count == items_count {
+        Rating::Good
+    } else if longest_ordered_count + 1 == items_count {
+        Rating::Hard
+    } else {
+        Rating::Again
+    };
+
+    Ok(OrderingGrade {
+        is_correct: items_in_place_count == items_count,
+        in_place,
+        items_in_place_count,
+        longest_ordered_count,
+        suggested_rating,
+    })
+}
+
+/// Patience sorting, the smallest last item of the increasing subsequences of
+/// every length is kept.
+fn get_longest_increasing_subsequence_length(values: &[usize]) -> usize {
+    let mut smallest_last_values: Vec<usize> = vec![];
+    for value in values {
+        let length = smallest_last_values.partition_point(|last_value| last_value < value);
+        if length == smallest_last_values.len() {
+            smallest_last_values.push(*value);
+        } else {
+            smallest_last_values[length] = *value;
+        }
+    }
+    smallest_last_values.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::service::tests::{create_file_cell_with_cell_type_and_content, get_db};
+
+    use super::*;
+
+    fn segment(kind: DiffKind, text: &str) -> DiffSegment {
+        DiffSegment {
+            kind,
+            text: text.into(),
+        }
+    }
+
+    #[test]
+    fn compare_answer_case_accents_and_whitespace_ignored_correct() {
+        // Arrange
+
+        let type_answer = TypeAnswer {
+            question: "Coffee with milk?".into(),
+            answer: "Café au lait".into(),
+            ..Default::default()
+        };
+
+        // Act
+
+        let actual = compare_answer(&type_answer, "  cafe AU   lait ");
+
+        // Assert
+
+        assert_eq!(
+            actual,
+            AnswerComparison {
+                is_correct: true,
+                expected_answer: "Café au lait".into(),
+                diff: vec![segment(DiffKind::Equal, "cafe AU lait")],
+                suggested_rating: Rating::Good,
+            }
+        );
+    }
+
+    #[test]
+    fn compare_answer_accent_sensitive_returned_diff() {
+        // Arrange
+
+        let type_answer = TypeAnswer {
+            question: "Coffee?".into(),
+            answer: "café".into(),
+            accent_sensitive: true,
+            ..Default::default()
+        };
+
+        // Act
+
+        let actual = compare_answer(&type_answer, "cafe");
+
+        // Assert
+
+        assert!(!actual.is_correct);
+        assert_eq!(actual.suggested_rating, Rating::Again);
+        assert_eq!(
+            actual.diff,
+            vec![
+                segment(DiffKind::Equal, "caf"),
+                segment(DiffKind::Extra, "e"),
+                segment(DiffKind::Missing, "é"),
+            ]
+        );
+    }
+
+    #[test]
+    fn compare_answer_typo_in_alternative_answer_returned_closest_diff() {
+        // Arrange
+
+        let type_answer = TypeAnswer {
+            question: "Which color is the sky?".into(),
+            answer: "blue".into(),
+            alternative_answers: vec!["light blue".into()],
+            ..Default::default()
+        };
+
+        // Act
+
+        let actual = compare_answer(&type_answer, "ligt blue");
+
+        // Assert
+
+        assert!(!actual.is_correct);
+        assert_eq!(actual.expected_answer, "light blue");
+        assert_eq!(
+            actual.diff,
+            vec![
+                segment(DiffKind::Equal, "lig"),
+                segment(DiffKind::Missing, "h"),
+                segment(DiffKind::Equal, "t blue"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn check_typed_answer_alternative_answer_correct() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let content = serde_json::to_string(&TypeAnswer {
+            question: "Which word names hues like red and blue?".into(),
+            answer: "color".into(),
+            alternative_answers: vec!["colour".into()],
+            ..Default::default()
+        })
+        .unwrap();
+        let (_, cell_id) = create_file_cell_with_cell_type_and_content(
+            &db_conn,
+            "file",
+            CellType::TypeAnswer,
+            &content,
+        )
+        .await;
+
+        // Act
+
+        let actual = check_typed_answer(&db_conn, cell_id, "Colour")
+            .await
+            .unwrap();
+
+        // Assert
+
+        assert!(actual.is_correct);
+        assert_eq!(actual.expected_answer, "colour");
+    }
+
+    #[test]
+    fn grade_order_one_item_moved_returned_hard() {
+        // Arrange
+
+        // The last item was put first.
+        let submitted_order = [3, 0, 1, 2];
+
+        // Act
+
+        let actual = grade_order(4, &submitted_order).unwrap();
+
+        // Assert
+
+        assert_eq!(
+            actual,
+            OrderingGrade {
+                is_correct: false,
+                in_place: vec![false; 4],
+                items_in_place_count: 0,
+                longest_ordered_count: 3,
+                suggested_rating: Rating::Hard,
+            }
+        );
+    }
+
+    #[test]
+    fn grade_order_swapped_pairs_returned_again() {
+        // Arrange
+
+        let submitted_order = [1, 0, 3, 2, 4];
+
+        // Act
+
+        let actual = grade_order(5, &submitted_order).unwrap();
+
+        // Assert
+
+        assert!(!actual.is_correct);
+        assert_eq!(actual.in_place, vec![false, false, false, false, true]);
+        assert_eq!(actual.longest_ordered_count, 3);
+        assert_eq!(actual.suggested_rating, Rating::Again);
+    }
+
+    #[test]
+    fn grade_order_repeated_item_returned_error() {
+        // Arrange
+
+        let submitted_order = [0, 0, 1];
+
+        // Act
+
+        let actual = grade_order(3, &submitted_order);
+
+        // Assert
+
+        assert_eq!(
+            actual,
+            Err("The submitted order must have every item once!".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn grade_ordering_correct_order_returned_good() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let content = serde_json::to_string(&Ordering {
+            question: "Order the planets by their distance to the sun".into(),
+            items: vec!["Mercury".into(), "Venus".into(), "Earth".into()],
+        })
+        .unwrap();
+        let (_, cell_id) = create_file_cell_with_cell_type_and_content(
+            &db_conn,
+            "file",
+            CellType::Ordering,
+            &content,
+        )
+        .await;
+
+        // Act
+
+        let actual = grade_ordering(&db_conn, cell_id, &[0, 1, 2]).await.unwrap();
+
+        // Assert
+
+        assert!(actual.is_correct);
+        assert_eq!(actual.items_in_place_count, 3);
+        assert_eq!(actual.suggested_rating, Rating::Good);
+    }
+}
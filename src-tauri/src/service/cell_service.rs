@@ -1,7 +1,10 @@
 use crate::{
     dto::update_cell_request::UpdateCellRequest,
     entity::cell::{self, CellType},
-    model::{flash_card::FlashCard, true_false::TrueFalse},
+    model::{
+        flash_card::FlashCard, image_occlusion::ImageOcclusion, multiple_choice::MultipleChoice,
+        ordering::Ordering, true_false::TrueFalse, type_answer::TypeAnswer,
+    },
 };
 
 use prelude::Expr;
@@ -98,6 +101,45 @@ fn get_searchable_content(content: &str, cell_type: &CellType) -> String {
                 .replace_all(&true_false.question, "")
                 .to_string()
         }
+        CellType::MultipleChoice => {
+            let multiple_choice: MultipleChoice =
+                serde_json::from_str(content).expect("Cannot parse multiple choice JSON!");
+            let mut texts = vec![multiple_choice.question];
+            texts.extend(multiple_choice.options);
+            texts.extend(multiple_choice.explanation);
+            remove_html_regex
+                .replace_all(&texts.join(" "), "")
+                .to_string()
+        }
+        CellType::TypeAnswer => {
+            let type_answer: TypeAnswer =
+                serde_json::from_str(content).expect("Cannot parse type answer JSON!");
+            let mut texts = vec![type_answer.question, type_answer.answer];
+            texts.extend(type_answer.alternative_answers);
+            remove_html_regex
+                .replace_all(&texts.join(" "), "")
+                .to_string()
+        }
+        CellType::Ordering => {
+            let ordering: Ordering =
+                serde_json::from_str(content).expect("Cannot parse ordering JSON!");
+            let mut texts = vec![ordering.question];
+            texts.extend(ordering.items);
+            remove_html_regex
+                .replace_all(&texts.join(" "), "")
+                .to_string()
+        }
+        CellType::ImageOcclusion => {
+            let image_occlusion: ImageOcclusion =
+                serde_json::from_str(content).expect("Cannot parse image occlusion JSON!");
+            image_occlusion
+                .masks
+                .into_iter()
+                .map(|mask| mask.label)
+                .filter(|label| !label.is_empty())
+                .collect::<Vec<String>>()
+                .join(" ")
+        }
     };
 
     searchable_content.to_lowercase()
@@ -118,6 +160,29 @@ pub async fn delete_cell(db_conn: &DbConn, cell_id: i32) -> Result<(), String> {
     Ok(())
 }
 
+/// Deletes the cells, which are sorted by file and index, from the last to the
+/// first so that the indices of the remaining cells stay consecutive.
+pub async fn delete_cells(db_conn: &DbConn, cells: &[cell::Model]) -> Result<(), String> {
+    let txn = match db_conn.begin().await {
+        Ok(txn) => txn,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    for cell in cells.iter().rev() {
+        let result = cell::Entity::delete_by_id(cell.id).exec(&txn).await;
+        if let Err(err) = result {
+            return Err(err.to_string());
+        }
+        increase_cells_indices_starting_from(&txn, cell.file_id, cell.index, -1).await?;
+    }
+
+    let result = txn.commit().await;
+    match result {
+        Ok(_) => Ok(()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
 pub async fn move_cell(db_conn: &DbConn, cell_id: i32, new_index: i32) -> Result<(), String> {
     let cell = get_cell_by_id(db_conn, cell_id).await?;
     let new_index = if new_index > cell.index {
@@ -150,7 +215,7 @@ pub async fn move_cell(db_conn: &DbConn, cell_id: i32, new_index: i32) -> Result
     }
 }
 
-async fn increase_cells_indices_starting_from(
+pub async fn increase_cells_indices_starting_from(
     db_conn: &impl ConnectionTrait,
     file_id: i32,
     start_index: i32,
@@ -286,6 +351,7 @@ mod tests {
         let content = serde_json::to_string(&FlashCard {
             question: "question".into(),
             answer: "<bold>Answer</bold>".into(),
+            ..Default::default()
         })
         .unwrap();
 
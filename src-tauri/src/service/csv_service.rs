@@ -0,0 +1,634 @@
+use csv::{ReaderBuilder, StringRecord, WriterBuilder};
+use sea_orm::{DbConn, Iterable, TransactionTrait};
+
+use crate::dto::csv_import_options::CsvImportOptions;
+use crate::dto::csv_import_result::{CsvImportResult, CsvRowError};
+use crate::entity::cell::{self, CellType};
+use crate::model::search_query::parse_variant;
+use crate::model::{
+    flash_card::FlashCard, image_occlusion::ImageOcclusion, multiple_choice::MultipleChoice,
+    ordering::Ordering, true_false::TrueFalse, type_answer::TypeAnswer,
+};
+
+use super::{anki_service, cell_service, export_import_service, file_service};
+
+const HEADER: [&str; 4] = ["question", "answer", "type", "file"];
+/// Separates the options of a multiple choice in the answer column, the
+/// correct options start with `CORRECT_OPTION_MARKER`, like `*Paris|Lyon`.
+/// The alternative answers of a typed answer follow the answer the same way.
+const OPTION_SEPARATOR: char = '|';
+const CORRECT_OPTION_MARKER: char = '*';
+/// Follows the type of a flash card that is also reviewed from its answer to
+/// its question, like `FlashCard reversed`.
+const REVERSED_MARKER: &str = "reversed";
+
+/// Imports the rows of a CSV or TSV file as cells at the end of the file.
+/// Invalid rows are skipped and reported with their line numbers.
+pub async fn import_csv(
+    db_conn: &DbConn,
+    import_path: String,
+    file_id: i32,
+    options: &CsvImportOptions,
+) -> Result<CsvImportResult, String> {
+    if file_service::get_by_id(db_conn, file_id).await?.is_folder {
+        return Err("Rows can only be imported into a file!".into());
+    }
+    let result = ReaderBuilder::new()
+        .delimiter(get_delimiter(&import_path, options.delimiter)?)
+        .has_headers(options.has_header)
+        .flexible(true)
+        .from_path(&import_path);
+    let mut reader = match result {
+        Ok(reader) => reader,
+        Err(err) => return Err(err.to_string()),
+    };
+    let mut index = cell_service::get_file_cells_ordered_by_index(db_conn, file_id)
+        .await?
+        .len() as i32;
+
+    let txn = match db_conn.begin().await {
+        Ok(txn) => txn,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let mut import_result = CsvImportResult::default();
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(err) => {
+                import_result.row_errors.push(CsvRowError {
+                    line: err.position().map(|position| position.line()).unwrap_or(0),
+                    message: err.to_string(),
+                });
+                continue;
+            }
+        };
+        let line = record
+            .position()
+            .map(|position| position.line())
+            .unwrap_or(0);
+        match get_cell(&record, options) {
+            Ok((cell_type, content)) => {
+                cell_service::create_cell_no_transaction(
+                    &txn,
+                    file_id,
+                    &export_import_service::purify_html(&content),
+                    &cell_type,
+                    index,
+                )
+                .await?;
+                index += 1;
+                import_result.imported_cells_count += 1;
+            }
+            Err(message) => import_result.row_errors.push(CsvRowError { line, message }),
+        }
+    }
+
+    let result = txn.commit().await;
+    match result {
+        Ok(_) => Ok(import_result),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Writes the cells of the file or folder as rows of question, answer, type
+/// and file path, which `import_csv` can read back.
+pub async fn export_csv(db_conn: &DbConn, item_id: i32, export_path: String) -> Result<(), String> {
+    let item = file_service::get_by_id(db_conn, item_id).await?;
+    let skip_prefix_length = match item.path.rfind('/') {
+        Some(index) => index + 1,
+        None => 0,
+    };
+    let mut files = if item.is_folder {
+        file_service::list_folder_children_recursively(db_conn, item_id)
+            .await?
+            .into_iter()
+            .filter(|file| !file.is_folder)
+            .collect()
+    } else {
+        vec![item]
+    };
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let result = WriterBuilder::new()
+        .delimiter(get_delimiter(&export_path, None)?)
+        .from_path(&export_path);
+    let mut writer = match result {
+        Ok(writer) => writer,
+        Err(err) => return Err(err.to_string()),
+    };
+    if let Err(err) = writer.write_record(HEADER) {
+        return Err(err.to_string());
+    }
+
+    for file in files {
+        for cell in cell_service::get_file_cells_ordered_by_index(db_conn, file.id).await? {
+            let (question, answer) = get_question_and_answer(&cell)?;
+            let cell_type = get_type(&cell)?;
+            let result = writer.write_record([
+                question.as_str(),
+                answer.as_str(),
+                cell_type.as_str(),
+                &file.path[skip_prefix_length..],
+            ]);
+            if let Err(err) = result {
+                return Err(err.to_string());
+            }
+        }
+    }
+
+    match writer.flush() {
+        Ok(_) => Ok(()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+fn get_delimiter(path: &str, delimiter: Option<char>) -> Result<u8, String> {
+    match delimiter {
+        Some(delimiter) if delimiter.is_ascii() => Ok(delimiter as u8),
+        Some(_) => Err("The delimiter must be an ASCII character!".into()),
+        None if path.to_lowercase().ends_with(".tsv") => Ok(b'\t'),
+        None => Ok(b','),
+    }
+}
+
+fn get_cell(
+    record: &StringRecord,
+    options: &CsvImportOptions,
+) -> Result<(CellType, String), String> {
+    let type_value = options
+        .type_column
+        .and_then(|column| record.get(column))
+        .unwrap_or_default()
+        .trim();
+    let (type_name, argument) = type_value
+        .split_once(char::is_whitespace)
+        .map(|(type_name, argument)| (type_name, argument.trim()))
+        .unwrap_or((type_value, ""));
+    let cell_type = match type_name {
+        "" => CellType::FlashCard,
+        type_name => parse_variant(CellType::iter(), type_name, "type")?,
+    };
+    let is_reversible = match argument {
+        "" => false,
+        argument
+            if argument.eq_ignore_ascii_case(REVERSED_MARKER)
+                && cell_type == CellType::FlashCard =>
+        {
+            true
+        }
+        _ => {
+            return Err(format!(
+                "Invalid type \"{type_value}\", only a flash card can be {REVERSED_MARKER}!"
+            ));
+        }
+    };
+    let question = get_column(record, options.question_column, "question")?;
+
+    let content = match cell_type {
+        CellType::FlashCard => serde_json::to_string(&FlashCard {
+            question,
+            answer: get_column(record, options.answer_column, "answer")?,
+            is_reversible,
+        })
+        .unwrap(),
+        CellType::TrueFalse => {
+            let answer = get_column(record, options.answer_column, "answer")?;
+            serde_json::to_string(&TrueFalse {
+                question,
+                is_true: parse_bool(&answer)?,
+            })
+            .unwrap()
+        }
+        CellType::MultipleChoice => {
+            let answer = get_column(record, options.answer_column, "answer")?;
+            let multiple_choice = parse_multiple_choice(question, &answer);
+            multiple_choice.validate()?;
+            serde_json::to_string(&multiple_choice).unwrap()
+        }
+        CellType::TypeAnswer => {
+            let answer = get_column(record, options.answer_column, "answer")?;
+            let mut answers = answer
+                .split(OPTION_SEPARATOR)
+                .map(|answer| answer.trim().to_string());
+            let type_answer = TypeAnswer {
+                question,
+                answer: answers.next().unwrap_or_default(),
+                alternative_answers: answers.collect(),
+                ..Default::default()
+            };
+            type_answer.validate()?;
+            serde_json::to_string(&type_answer).unwrap()
+        }
+        CellType::Ordering => {
+            let answer = get_column(record, options.answer_column, "answer")?;
+            let ordering = Ordering {
+                question,
+                items: answer
+                    .split(OPTION_SEPARATOR)
+                    .map(|item| item.trim().to_string())
+                    .collect(),
+            };
+            ordering.validate()?;
+            serde_json::to_string(&ordering).unwrap()
+        }
+        CellType::ImageOcclusion => {
+            return Err("Image occlusions cannot be imported from CSV files!".into());
+        }
+        CellType::Note => question,
+        CellType::Cloze => {
+            let content = anki_service::convert_clozes(&question);
+            if !content.contains("<cloze") {
+                return Err("The cloze has no clozes, mark them like {{c1::text}}!".into());
+            }
+            content
+        }
+    };
+
+    Ok((cell_type, content))
+}
+
+fn get_column(record: &StringRecord, column: usize, name: &str) -> Result<String, String> {
+    match record.get(column).map(str::trim) {
+        Some(value) if !value.is_empty() => Ok(value.to_string()),
+        _ => Err(format!("The {name} in column {} is empty!", column + 1)),
+    }
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value.to_lowercase().as_str() {
+        "true" | "t" | "yes" | "y" | "1" => Ok(true),
+        "false" | "f" | "no" | "n" | "0" => Ok(false),
+        _ => Err(format!(
+            "Invalid answer \"{value}\", expected true or false!"
+        )),
+    }
+}
+
+fn parse_multiple_choice(question: String, answer: &str) -> MultipleChoice {
+    let mut multiple_choice = MultipleChoice {
+        question,
+        ..Default::default()
+    };
+    for (i, option) in answer.split(OPTION_SEPARATOR).map(str::trim).enumerate() {
+        let option = match option.strip_prefix(CORRECT_OPTION_MARKER) {
+            Some(option) => {
+                multiple_choice.correct_indices.push(i);
+                option.trim_start()
+            }
+            None => option,
+        };
+        multiple_choice.options.push(option.to_string());
+    }
+    multiple_choice
+}
+
+/// Returns the value of the type column, which marks reversible flash cards.
+fn get_type(cell: &cell::Model) -> Result<String, String> {
+    let cell_type = format!("{:?}", cell.cell_type);
+    if cell.cell_type != CellType::FlashCard {
+        return Ok(cell_type);
+    }
+    match serde_json::from_str::<FlashCard>(&cell.content) {
+        Ok(flash_card) if flash_card.is_reversible => Ok(format!("{cell_type} {REVERSED_MARKER}")),
+        Ok(_) => Ok(cell_type),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+fn get_question_and_answer(cell: &cell::Model) -> Result<(String, String), String> {
+    match cell.cell_type {
+        CellType::FlashCard => match serde_json::from_str::<FlashCard>(&cell.content) {
+            Ok(flash_card) => Ok((flash_card.question, flash_card.answer)),
+            Err(err) => Err(err.to_string()),
+        },
+        CellType::TrueFalse => match serde_json::from_str::<TrueFalse>(&cell.content) {
+            Ok(true_false) => Ok((true_false.question, true_false.is_true.to_string())),
+            Err(err) => Err(err.to_string()),
+        },
+        CellType::MultipleChoice => match serde_json::from_str::<MultipleChoice>(&cell.content) {
+            Ok(multiple_choice) => {
+                let options: Vec<String> = multiple_choice
+                    .options
+                    .iter()
+                    .enumerate()
+                    .map(|(i, option)| {
+                        if multiple_choice.correct_indices.contains(&i) {
+                            format!("{CORRECT_OPTION_MARKER}{option}")
+                        } else {
+                            option.clone()
+                        }
+                    })
+                    .collect();
+                Ok((
+                    multiple_choice.question,
+                    options.join(&OPTION_SEPARATOR.to_string()),
+                ))
+            }
+            Err(err) => Err(err.to_string()),
+        },
+        CellType::TypeAnswer => match serde_json::from_str::<TypeAnswer>(&cell.content) {
+            Ok(type_answer) => {
+                let mut answers = vec![type_answer.answer];
+                answers.extend(type_answer.alternative_answers);
+                Ok((
+                    type_answer.question,
+                    answers.join(&OPTION_SEPARATOR.to_string()),
+                ))
+            }
+            Err(err) => Err(err.to_string()),
+        },
+        CellType::Ordering => match serde_json::from_str::<Ordering>(&cell.content) {
+            Ok(ordering) => Ok((
+                ordering.question,
+                ordering.items.join(&OPTION_SEPARATOR.to_string()),
+            )),
+            Err(err) => Err(err.to_string()),
+        },
+        CellType::ImageOcclusion => match serde_json::from_str::<ImageOcclusion>(&cell.content) {
+            Ok(image_occlusion) => {
+                let labels: Vec<String> = image_occlusion
+                    .masks
+                    .into_iter()
+                    .map(|mask| mask.label)
+                    .collect();
+                Ok((
+                    format!(
+                        "<img src=\"{}\">",
+                        image_occlusion.image.replace('"', "&quot;")
+                    ),
+                    labels.join(&OPTION_SEPARATOR.to_string()),
+                ))
+            }
+            Err(err) => Err(err.to_string()),
+        },
+        CellType::Note | CellType::Cloze => Ok((cell.content.clone(), "".into())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::tests::{
+        create_file, create_file_cell_with_cell_type_and_content, get_db, get_random_file_path,
+    };
+
+    #[tokio::test]
+    async fn import_csv_quoted_values_imported_cells() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let file_id = create_file(&db_conn, "vocabulary").await;
+        let import_path = get_random_file_path("csv");
+        std::fs::write(
+            &import_path,
+            "Word,Meaning\n\"perro, el\",\"the \"\"dog\"\"\"\n\"multi\nline\",answer\n",
+        )
+        .unwrap();
+
+        // Act
+
+        let actual = import_csv(
+            &db_conn,
+            import_path.to_str().unwrap().into(),
+            file_id,
+            &CsvImportOptions {
+                question_column: 0,
+                answer_column: 1,
+                has_header: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        assert_eq!(actual.imported_cells_count, 2);
+        assert!(actual.row_errors.is_empty());
+        let cells = cell_service::get_file_cells_ordered_by_index(&db_conn, file_id)
+            .await
+            .unwrap();
+        assert_eq!(cells.len(), 2);
+        assert_eq!(
+            serde_json::from_str::<FlashCard>(&cells[0].content).unwrap(),
+            FlashCard {
+                question: "perro, el".into(),
+                answer: "the \"dog\"".into(),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            serde_json::from_str::<FlashCard>(&cells[1].content)
+                .unwrap()
+                .question,
+            "multi\nline"
+        );
+    }
+
+    #[tokio::test]
+    async fn import_csv_invalid_rows_reported_with_line_numbers() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (file_id, _) =
+            create_file_cell_with_cell_type_and_content(&db_conn, "file", CellType::Note, "").await;
+        let import_path = get_random_file_path("tsv");
+        std::fs::write(
+            &import_path,
+            "type\tquestion\tanswer\n\
+            true_false\tThe earth is flat\tno\n\
+            flash_card\tno answer\n\
+            cloze\t{{c1::Paris}} is in France\n\
+            true_false\tmaybe\tperhaps\n\
+            quiz\tquestion\tanswer\n\
+            note reversed\tquestion\n",
+        )
+        .unwrap();
+
+        // Act
+
+        let actual = import_csv(
+            &db_conn,
+            import_path.to_str().unwrap().into(),
+            file_id,
+            &CsvImportOptions {
+                question_column: 1,
+                answer_column: 2,
+                type_column: Some(0),
+                has_header: true,
+                delimiter: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        assert_eq!(actual.imported_cells_count, 2);
+        assert_eq!(
+            actual.row_errors,
+            vec![
+                CsvRowError {
+                    line: 3,
+                    message: "The answer in column 3 is empty!".into(),
+                },
+                CsvRowError {
+                    line: 5,
+                    message: "Invalid answer \"perhaps\", expected true or false!".into(),
+                },
+                CsvRowError {
+                    line: 6,
+                    message:
+                        "Invalid type \"quiz\", expected one of: flashcard, note, cloze, truefalse, multiplechoice, typeanswer, imageocclusion, ordering!"
+                            .into(),
+                },
+                CsvRowError {
+                    line: 7,
+                    message: "Invalid type \"note reversed\", only a flash card can be reversed!"
+                        .into(),
+                },
+            ]
+        );
+        let cells = cell_service::get_file_cells_ordered_by_index(&db_conn, file_id)
+            .await
+            .unwrap();
+        assert_eq!(cells.len(), 3);
+        assert_eq!(cells[1].cell_type, CellType::TrueFalse);
+        assert_eq!(cells[1].index, 1);
+        assert_eq!(cells[2].cell_type, CellType::Cloze);
+        assert_eq!(
+            cells[2].content,
+            "<cloze index=\"1\">Paris</cloze> is in France"
+        );
+    }
+
+    #[tokio::test]
+    async fn import_csv_multiple_choice_marked_options_correct() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let file_id = create_file(&db_conn, "file").await;
+        let import_path = get_random_file_path("csv");
+        std::fs::write(
+            &import_path,
+            "multiple_choice,Which cities are in France?,*Paris | Madrid | * Lyon\n\
+            multiple_choice,No correct option,Paris | Madrid\n",
+        )
+        .unwrap();
+
+        // Act
+
+        let actual = import_csv(
+            &db_conn,
+            import_path.to_str().unwrap().into(),
+            file_id,
+            &CsvImportOptions {
+                question_column: 1,
+                answer_column: 2,
+                type_column: Some(0),
+                has_header: false,
+                delimiter: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        assert_eq!(actual.imported_cells_count, 1);
+        assert_eq!(
+            actual.row_errors,
+            vec![CsvRowError {
+                line: 2,
+                message: "A multiple choice must have at least one correct option!".into(),
+            }]
+        );
+        let cells = cell_service::get_file_cells_ordered_by_index(&db_conn, file_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            serde_json::from_str::<MultipleChoice>(&cells[0].content).unwrap(),
+            MultipleChoice {
+                question: "Which cities are in France?".into(),
+                options: vec!["Paris".into(), "Madrid".into(), "Lyon".into()],
+                correct_indices: vec![0, 2],
+                ..Default::default()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn export_csv_folder_then_import_csv_returned_same_cells() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let folder_id = file_service::create_folder(&db_conn, "folder".into())
+            .await
+            .unwrap();
+        let flash_card_content = serde_json::to_string(&FlashCard {
+            question: "a, \"quoted\" question".into(),
+            answer: "answer".into(),
+            is_reversible: true,
+        })
+        .unwrap();
+        create_file_cell_with_cell_type_and_content(
+            &db_conn,
+            "folder/file 1",
+            CellType::FlashCard,
+            &flash_card_content,
+        )
+        .await;
+        let true_false_content = serde_json::to_string(&TrueFalse {
+            question: "true?".into(),
+            is_true: true,
+        })
+        .unwrap();
+        create_file_cell_with_cell_type_and_content(
+            &db_conn,
+            "folder/sub/file 2",
+            CellType::TrueFalse,
+            &true_false_content,
+        )
+        .await;
+        let export_path = get_random_file_path("csv");
+        let import_file_id = create_file(&db_conn, "imported").await;
+
+        // Act
+
+        export_csv(&db_conn, folder_id, export_path.to_str().unwrap().into())
+            .await
+            .unwrap();
+        let actual = import_csv(
+            &db_conn,
+            export_path.to_str().unwrap().into(),
+            import_file_id,
+            &CsvImportOptions {
+                question_column: 0,
+                answer_column: 1,
+                type_column: Some(2),
+                has_header: true,
+                delimiter: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        assert_eq!(
+            std::fs::read_to_string(&export_path)
+                .unwrap()
+                .lines()
+                .map(|line| line.rsplit(',').next().unwrap())
+                .collect::<Vec<_>>(),
+            vec!["file", "folder/file 1", "folder/sub/file 2"]
+        );
+        assert_eq!(actual.imported_cells_count, 2);
+        let cells = cell_service::get_file_cells_ordered_by_index(&db_conn, import_file_id)
+            .await
+            .unwrap();
+        assert_eq!(cells[0].content, flash_card_content);
+        assert_eq!(cells[1].content, true_false_content);
+    }
+}
@@ -0,0 +1,433 @@
+use std::collections::{HashMap, HashSet};
+
+use sea_orm::{DbConn, entity::*, query::*};
+
+use crate::{
+    dto::duplicate_group::{DuplicateCell, DuplicateGroup},
+    entity::{cell, file, repetition},
+};
+
+/// Groups the cells of the same type whose searchable contents are equal after
+/// normalization, or whose trigrams have a Jaccard similarity of at least
+/// `similarity_threshold`. Groups are sorted from the most similar.
+pub async fn find_duplicates(
+    db_conn: &DbConn,
+    similarity_threshold: f32,
+) -> Result<Vec<DuplicateGroup>, String> {
+    if !(similarity_threshold > 0.0 && similarity_threshold <= 1.0) {
+        return Err("The similarity threshold must be between 0 and 1!".into());
+    }
+
+    let cells = match cell::Entity::find()
+        .order_by_asc(cell::Column::Id)
+        .all(db_conn)
+        .await
+    {
+        Ok(cells) => cells,
+        Err(err) => return Err(err.to_string()),
+    };
+    let file_paths: HashMap<i32, String> = match file::Entity::find().all(db_conn).await {
+        Ok(files) => files.into_iter().map(|file| (file.id, file.path)).collect(),
+        Err(err) => return Err(err.to_string()),
+    };
+    let reviews_counts = get_reviews_counts(db_conn).await?;
+
+    let contents: Vec<(String, String)> = cells
+        .iter()
+        .map(|cell| {
+            (
+                format!("{:?}", cell.cell_type),
+                normalize(&cell.searchable_content),
+            )
+        })
+        .collect();
+    let mut groups = DisjointSet::new(cells.len());
+    let mut similar_pairs = vec![];
+
+    // Only the first cell of each set of equal contents is compared by
+    // similarity, the others join its group anyway.
+    let mut first_indices: HashMap<&(String, String), usize> = HashMap::new();
+    let mut distinct_indices = vec![];
+    for (i, content) in contents.iter().enumerate() {
+        if content.1.is_empty() {
+            continue;
+        }
+        match first_indices.get(content) {
+            Some(&first_index) => {
+                groups.union(first_index, i);
+                similar_pairs.push((first_index, i, 1.0));
+            }
+            None => {
+                first_indices.insert(content, i);
+                distinct_indices.push(i);
+            }
+        }
+    }
+    if similarity_threshold < 1.0 {
+        for (i, j, similarity) in
+            get_similar_pairs(&contents, &distinct_indices, similarity_threshold)
+        {
+            groups.union(i, j);
+            similar_pairs.push((i, j, similarity));
+        }
+    }
+
+    let mut similarities: HashMap<usize, f32> = HashMap::new();
+    for (i, _, similarity) in similar_pairs {
+        let root = groups.find(i);
+        let group_similarity = similarities.entry(root).or_insert(1.0);
+        *group_similarity = group_similarity.min(similarity);
+    }
+    let mut grouped_cells: HashMap<usize, Vec<DuplicateCell>> = HashMap::new();
+    for (i, cell) in cells.into_iter().enumerate() {
+        let root = groups.find(i);
+        if !similarities.contains_key(&root) {
+            continue;
+        }
+        grouped_cells.entry(root).or_default().push(DuplicateCell {
+            file_path: file_paths.get(&cell.file_id).cloned().unwrap_or_default(),
+            reviews_count: reviews_counts.get(&cell.id).copied().unwrap_or_default(),
+            cell,
+        });
+    }
+
+    let mut duplicate_groups: Vec<DuplicateGroup> = grouped_cells
+        .into_iter()
+        .map(|(root, mut cells)| {
+            cells.sort_by(|a, b| {
+                b.reviews_count
+                    .cmp(&a.reviews_count)
+                    .then_with(|| a.file_path.cmp(&b.file_path))
+                    .then_with(|| a.cell.index.cmp(&b.cell.index))
+            });
+            DuplicateGroup {
+                similarity: similarities[&root],
+                cells,
+            }
+        })
+        .collect();
+    duplicate_groups.sort_by(|a, b| {
+        b.similarity
+            .total_cmp(&a.similarity)
+            .then_with(|| a.cells[0].file_path.cmp(&b.cells[0].file_path))
+            .then_with(|| a.cells[0].cell.index.cmp(&b.cells[0].cell.index))
+    });
+    Ok(duplicate_groups)
+}
+
+/// Checks that the cells are distinct, exist and have the same type, as only
+/// such cells can be duplicates of each other.
+pub async fn check_mergeable(db_conn: &DbConn, cell_ids: &[i32]) -> Result<(), String> {
+    if cell_ids.len() < 2 {
+        return Err("At least two cells are needed to merge!".into());
+    }
+    let distinct_cell_ids: HashSet<i32> = cell_ids.iter().copied().collect();
+    if distinct_cell_ids.len() != cell_ids.len() {
+        return Err("A cell cannot be merged with itself!".into());
+    }
+
+    let result = cell::Entity::find()
+        .select_only()
+        .column(cell::Column::CellType)
+        .filter(cell::Column::Id.is_in(distinct_cell_ids))
+        .into_tuple::<cell::CellType>()
+        .all(db_conn)
+        .await;
+    let cell_types = match result {
+        Ok(cell_types) => cell_types,
+        Err(err) => return Err(err.to_string()),
+    };
+    if cell_types.len() != cell_ids.len() {
+        return Err("Cell not found!".into());
+    }
+    if cell_types
+        .iter()
+        .any(|cell_type| *cell_type != cell_types[0])
+    {
+        return Err("Only cells of the same type can be merged!".into());
+    }
+    Ok(())
+}
+
+/// Returns the id of the cell whose repetitions were reviewed the most times,
+/// the oldest cell on a tie.
+pub async fn get_most_reviewed_cell_id(db_conn: &DbConn, cell_ids: &[i32]) -> Result<i32, String> {
+    let result = repetition::Entity::find()
+        .filter(repetition::Column::CellId.is_in(cell_ids.to_vec()))
+        .all(db_conn)
+        .await;
+    let repetitions = match result {
+        Ok(repetitions) => repetitions,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let mut reviews_counts: HashMap<i32, i32> = HashMap::new();
+    for repetition in repetitions {
+        *reviews_counts.entry(repetition.cell_id).or_default() += repetition.reps;
+    }
+    let most_reviewed_cell_id = cell_ids.iter().copied().max_by(|a, b| {
+        let a_count = reviews_counts.get(a).copied().unwrap_or_default();
+        let b_count = reviews_counts.get(b).copied().unwrap_or_default();
+        a_count.cmp(&b_count).then_with(|| b.cmp(a))
+    });
+    match most_reviewed_cell_id {
+        Some(cell_id) => Ok(cell_id),
+        None => Err("No cells to merge!".into()),
+    }
+}
+
+async fn get_reviews_counts(db_conn: &DbConn) -> Result<HashMap<i32, i32>, String> {
+    let result = repetition::Entity::find()
+        .select_only()
+        .column(repetition::Column::CellId)
+        .column_as(repetition::Column::Reps.sum(), "reviews_count")
+        .group_by(repetition::Column::CellId)
+        .into_tuple::<(i32, i32)>()
+        .all(db_conn)
+        .await;
+    match result {
+        Ok(rows) => Ok(rows.into_iter().collect()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Keeps only letters, digits and single spaces, the searchable content is
+/// lowercase already.
+fn normalize(searchable_content: &str) -> String {
+    searchable_content
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// Compares the contents of the same type sharing at least one trigram, and
+/// returns the pairs whose similarity reaches the threshold.
+fn get_similar_pairs(
+    contents: &[(String, String)],
+    indices: &[usize],
+    similarity_threshold: f32,
+) -> Vec<(usize, usize, f32)> {
+    let trigrams: HashMap<usize, HashSet<String>> = indices
+        .iter()
+        .map(|&i| (i, get_trigrams(&contents[i].1)))
+        .collect();
+    let mut postings: HashMap<(&str, &str), Vec<usize>> = HashMap::new();
+    let mut similar_pairs = vec![];
+
+    for &i in indices {
+        let cell_type = contents[i].0.as_str();
+        let mut shared_counts: HashMap<usize, usize> = HashMap::new();
+        for trigram in &trigrams[&i] {
+            if let Some(previous_indices) = postings.get(&(cell_type, trigram.as_str())) {
+                for &j in previous_indices {
+                    *shared_counts.entry(j).or_default() += 1;
+                }
+            }
+        }
+
+        let mut candidates: Vec<(usize, usize)> = shared_counts.into_iter().collect();
+        candidates.sort_unstable();
+        for (j, shared_count) in candidates {
+            let union_count = trigrams[&i].len() + trigrams[&j].len() - shared_count;
+            let similarity = shared_count as f32 / union_count as f32;
+            if similarity >= similarity_threshold {
+                similar_pairs.push((j, i, similarity));
+            }
+        }
+
+        for trigram in &trigrams[&i] {
+            postings
+                .entry((cell_type, trigram.as_str()))
+                .or_default()
+                .push(i);
+        }
+    }
+    similar_pairs
+}
+
+/// Returns the character trigrams of the text, padded with spaces so that
+/// short words have trigrams as well.
+fn get_trigrams(text: &str) -> HashSet<String> {
+    let characters: Vec<char> = format!("  {text} ").chars().collect();
+    characters
+        .windows(3)
+        .map(|window| window.iter().collect())
+        .collect()
+}
+
+/// Tracks which cells belong to the same group.
+struct DisjointSet {
+    parents: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(size: usize) -> Self {
+        Self {
+            parents: (0..size).collect(),
+        }
+    }
+
+    fn find(&mut self, i: usize) -> usize {
+        let mut root = i;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        let mut current = i;
+        while self.parents[current] != root {
+            let next = self.parents[current];
+            self.parents[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    fn union(&mut self, i: usize, j: usize) {
+        let i_root = self.find(i);
+        let j_root = self.find(j);
+        if i_root != j_root {
+            self.parents[j_root.max(i_root)] = i_root.min(j_root);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        entity::cell::CellType,
+        model::rating::Rating,
+        service::{
+            cell_service, repetition_service,
+            tests::{create_file, get_db},
+        },
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn find_duplicates_equal_and_similar_cells_grouped() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let file_1_id = create_file(&db_conn, "file 1").await;
+        let file_2_id = create_file(&db_conn, "file 2").await;
+        let contents = [
+            "The capital of France is Paris.",
+            "the capital of france is paris",
+            "The capital of France is Paris!!",
+            "Something else entirely",
+        ];
+        for (i, content) in contents.iter().enumerate() {
+            cell_service::create_cell(&db_conn, file_1_id, content, CellType::Note, i as i32)
+                .await
+                .unwrap();
+        }
+        cell_service::create_cell(
+            &db_conn,
+            file_2_id,
+            "The capital of France is Paris, isn't it",
+            CellType::Note,
+            0,
+        )
+        .await
+        .unwrap();
+        cell_service::create_cell(
+            &db_conn,
+            file_2_id,
+            "The capital of France is Paris.",
+            CellType::Cloze,
+            1,
+        )
+        .await
+        .unwrap();
+
+        // Act
+
+        let exact = find_duplicates(&db_conn, 1.0).await.unwrap();
+        let similar = find_duplicates(&db_conn, 0.7).await.unwrap();
+
+        // Assert
+
+        assert_eq!(exact.len(), 1);
+        assert_eq!(exact[0].similarity, 1.0);
+        assert_eq!(exact[0].cells.len(), 3);
+        assert_eq!(similar.len(), 1);
+        assert!(similar[0].similarity >= 0.7 && similar[0].similarity < 1.0);
+        let paths: Vec<&str> = similar[0]
+            .cells
+            .iter()
+            .map(|cell| cell.file_path.as_str())
+            .collect();
+        assert_eq!(paths, vec!["file 1", "file 1", "file 1", "file 2"]);
+    }
+
+    #[tokio::test]
+    async fn check_mergeable_repeated_ids_and_mixed_types_returned_error() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let file_id = create_file(&db_conn, "file").await;
+        let note_id = cell_service::create_cell(&db_conn, file_id, "note", CellType::Note, 0)
+            .await
+            .unwrap();
+        let flash_card_id = cell_service::create_cell(
+            &db_conn,
+            file_id,
+            r#"{"question":"note","answer":""}"#,
+            CellType::FlashCard,
+            1,
+        )
+        .await
+        .unwrap();
+
+        // Act
+
+        let repeated = check_mergeable(&db_conn, &[note_id, note_id]).await;
+        let mixed = check_mergeable(&db_conn, &[note_id, flash_card_id]).await;
+
+        // Assert
+
+        assert_eq!(repeated, Err("A cell cannot be merged with itself!".into()));
+        assert_eq!(
+            mixed,
+            Err("Only cells of the same type can be merged!".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn get_most_reviewed_cell_id_reviewed_cell_returned() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let file_id = create_file(&db_conn, "file").await;
+        let content = r#"{"question":"question","answer":"answer"}"#;
+        let mut cell_ids = vec![];
+        for i in 0..2 {
+            cell_ids.push(
+                cell_service::create_cell(&db_conn, file_id, content, CellType::FlashCard, i)
+                    .await
+                    .unwrap(),
+            );
+        }
+        let repetitions = repetition_service::get_file_repetitions(&db_conn, file_id)
+            .await
+            .unwrap();
+        let reviewed = repetitions
+            .iter()
+            .find(|repetition| repetition.cell_id == cell_ids[1])
+            .unwrap();
+        repetition_service::review_repetition(&db_conn, reviewed.id, Rating::Good, None)
+            .await
+            .unwrap();
+
+        // Act
+
+        let actual = get_most_reviewed_cell_id(&db_conn, &cell_ids)
+            .await
+            .unwrap();
+
+        // Assert
+
+        assert_eq!(actual, cell_ids[1]);
+    }
+}
@@ -1,14 +1,135 @@
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::Path;
+
+use chrono::Utc;
+use sea_orm::{DbConn, entity::*, query::*};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+use crate::dto::export_envelope::ExportEnvelope;
+use crate::dto::exported_item::{ExportedCell, ExportedItem, ExportedItemType, ExportedRepetition};
+use crate::dto::import_options::{ImportMode, ImportOptions};
+use crate::dto::import_result::{ImportAction, ImportActionKind, ImportResult};
+use crate::entity::cell::{self, CellType};
+use crate::entity::repetition;
+use crate::model::{
+    flash_card::FlashCard, image_occlusion::ImageOcclusion, multiple_choice::MultipleChoice,
+    ordering::Ordering, true_false::TrueFalse, type_answer::TypeAnswer,
+};
+use crate::util::media_util::{
+    get_media_reference, get_media_store_path, is_local_file_url, is_valid_media_name,
+};
+
+use super::{cell_service, file_service, media_service, repetition_service, tag_service};
+use lol_html::html_content::Element;
+use lol_html::{RewriteStrSettings, element, rewrite_str};
+use zip::{ZipArchive, ZipWriter, write::SimpleFileOptions};
 
-use sea_orm::{DbConn, query::*};
+const FORMAT_VERSION: u32 = 1;
 
-use crate::dto::exported_item::{ExportedCell, ExportedItem, ExportedItemType};
+const BUNDLE_ITEM_FILE_NAME: &str = "export.json";
+const BUNDLE_MEDIA_DIRECTORY: &str = "media/";
 
-use super::{cell_service, file_service};
-use lol_html::html_content::Element;
-use lol_html::{RewriteStrSettings, element, rewrite_str};
+/// Upgrades the file from the format version at the index to the next one.
+const UPGRADERS: [fn(Value) -> Value; FORMAT_VERSION as usize] = [upgrade_from_version_0];
+
+/// Exports the file or folder as JSON, with the review progress of every cell
+/// when `include_progress` is set.
+pub async fn export(
+    db_conn: &DbConn,
+    item_id: i32,
+    export_path: String,
+    include_progress: bool,
+) -> Result<(), String> {
+    let exported_item = get_root_exported_item(db_conn, item_id, include_progress).await?;
+    let envelope = create_envelope(exported_item);
+    let result = fs::write(export_path, serde_json::to_string(&envelope).unwrap());
+
+    if let Err(err) = result {
+        return Err(err.to_string());
+    }
+
+    Ok(())
+}
+
+/// Exports the file or folder like `export` into a zip bundle, together with
+/// the media store files referenced by the cells.
+/// The media files are streamed into the bundle.
+pub async fn export_bundle(
+    db_conn: &DbConn,
+    item_id: i32,
+    export_path: String,
+    include_progress: bool,
+    media_directory: &Path,
+) -> Result<(), String> {
+    let mut exported_item = get_root_exported_item(db_conn, item_id, include_progress).await?;
+
+    let mut media_paths = HashSet::new();
+    map_item_sources(&mut exported_item, &mut |src| {
+        if let Some(path) = get_media_store_path(src, media_directory).filter(|path| path.is_file())
+        {
+            media_paths.insert(path);
+        }
+        None
+    });
+    let mut media_names = HashMap::new();
+    for path in media_paths {
+        let name = media_service::get_media_name(&path)?;
+        media_names.insert(path, name);
+    }
+    map_item_sources(&mut exported_item, &mut |src| {
+        get_media_store_path(src, media_directory)
+            .and_then(|path| media_names.get(&path))
+            .map(|name| format!("{BUNDLE_MEDIA_DIRECTORY}{name}"))
+    });
+    let envelope = create_envelope(exported_item);
+
+    let bundle_file = match File::create(export_path) {
+        Ok(file) => file,
+        Err(err) => return Err(err.to_string()),
+    };
+    let mut zip = ZipWriter::new(bundle_file);
+    if let Err(err) = zip.start_file(BUNDLE_ITEM_FILE_NAME, SimpleFileOptions::default()) {
+        return Err(err.to_string());
+    }
+    if let Err(err) = serde_json::to_writer(&mut zip, &envelope) {
+        return Err(err.to_string());
+    }
+    let mut written_names = HashSet::new();
+    for (path, name) in &media_names {
+        // Identical files share a name, only the first one is written.
+        if !written_names.insert(name) {
+            continue;
+        }
+        let result = zip.start_file(
+            format!("{BUNDLE_MEDIA_DIRECTORY}{name}"),
+            SimpleFileOptions::default(),
+        );
+        if let Err(err) = result {
+            return Err(err.to_string());
+        }
+        let mut media_file = match File::open(path) {
+            Ok(file) => file,
+            Err(err) => return Err(err.to_string()),
+        };
+        if let Err(err) = io::copy(&mut media_file, &mut zip) {
+            return Err(err.to_string());
+        }
+    }
+
+    match zip.finish() {
+        Ok(_) => Ok(()),
+        Err(err) => Err(err.to_string()),
+    }
+}
 
-pub async fn export(db_conn: &DbConn, item_id: i32, export_path: String) -> Result<(), String> {
+async fn get_root_exported_item(
+    db_conn: &DbConn,
+    item_id: i32,
+    include_progress: bool,
+) -> Result<ExportedItem, String> {
     let item = file_service::get_by_id(db_conn, item_id).await?;
     let slash_index = item.path.rfind('/');
     let skip_prefix_length = if let Some(index) = slash_index {
@@ -16,32 +137,65 @@ pub async fn export(db_conn: &DbConn, item_id: i32, export_path: String) -> Resu
     } else {
         0
     };
-    let exported_item = get_exported_item(db_conn, item_id, skip_prefix_length).await?;
-    let result = fs::write(export_path, serde_json::to_string(&exported_item).unwrap());
+    get_exported_item(db_conn, item_id, skip_prefix_length, include_progress).await
+}
 
-    if let Err(err) = result {
-        return Err(err.to_string());
+fn create_envelope(exported_item: ExportedItem) -> ExportEnvelope {
+    // The checksum is calculated from the parsed JSON like when importing, so
+    // that numbers are formatted the same way.
+    let item_value: Value =
+        serde_json::from_str(&serde_json::to_string(&exported_item).unwrap()).unwrap();
+    ExportEnvelope {
+        format_version: FORMAT_VERSION,
+        app_version: Some(env!("CARGO_PKG_VERSION").into()),
+        created_at: Some(Utc::now()),
+        checksum: get_checksum(&item_value),
+        item: exported_item,
     }
-
-    Ok(())
 }
 
 async fn get_exported_item(
     db_conn: &DbConn,
     item_id: i32,
     skip_prefix_length: usize,
+    include_progress: bool,
 ) -> Result<ExportedItem, String> {
     let item = file_service::get_by_id(db_conn, item_id).await?;
     let cells: Option<Vec<ExportedCell>> = if item.is_folder {
         None
     } else {
-        Some(
-            cell_service::get_file_cells_ordered_by_index(db_conn, item_id)
-                .await?
-                .into_iter()
-                .map(ExportedCell::from)
-                .collect(),
+        let mut repetitions = if include_progress {
+            repetition_service::get_file_repetitions(db_conn, item_id).await?
+        } else {
+            vec![]
+        };
+        repetitions.sort_by(|a, b| a.additional_content.cmp(&b.additional_content));
+
+        let file_cells = cell_service::get_file_cells_ordered_by_index(db_conn, item_id).await?;
+        let mut cell_tags = tag_service::get_tags_for_cells(
+            db_conn,
+            file_cells.iter().map(|cell| cell.id).collect(),
         )
+        .await?;
+
+        let mut cells = vec![];
+        for cell in file_cells {
+            let cell_id = cell.id;
+            let mut exported_cell = ExportedCell::from(cell);
+            exported_cell.tags = cell_tags.remove(&cell_id).unwrap_or_default();
+            if include_progress {
+                exported_cell.repetitions = Some(
+                    repetitions
+                        .iter()
+                        .filter(|repetition| repetition.cell_id == cell_id)
+                        .cloned()
+                        .map(ExportedRepetition::from)
+                        .collect(),
+                );
+            }
+            cells.push(exported_cell);
+        }
+        Some(cells)
     };
     let mut children: Option<Vec<ExportedItem>> = None;
 
@@ -54,6 +208,7 @@ async fn get_exported_item(
                     db_conn,
                     folder_child.id,
                     skip_prefix_length,
+                    include_progress,
                 ))
                 .await?,
             );
@@ -75,21 +230,104 @@ async fn get_exported_item(
     Ok(exported_item)
 }
 
+/// Imports a JSON export into the folder, `options` decide what happens to
+/// files which exist already.
 pub async fn import(
     db_conn: &DbConn,
     import_item_path: String,
     import_into_folder_id: i32,
-) -> Result<(), String> {
+    options: &ImportOptions,
+) -> Result<ImportResult, String> {
     let import_file = match File::open(import_item_path) {
         Err(err) => return Err(err.to_string()),
         Ok(file) => file,
     };
 
-    let exported_item: ExportedItem = match serde_json::from_reader(import_file) {
+    let exported_item = read_envelope(import_file)?.item;
+
+    import_into_folder(db_conn, &exported_item, import_into_folder_id, options).await
+}
+
+/// Imports a bundle written by `export_bundle`. The media files are added to
+/// the media store and the cells are changed to reference them.
+pub async fn import_bundle(
+    db_conn: &DbConn,
+    bundle_path: String,
+    import_into_folder_id: i32,
+    media_directory: &Path,
+    options: &ImportOptions,
+) -> Result<ImportResult, String> {
+    let bundle_file = match File::open(bundle_path) {
+        Ok(file) => file,
+        Err(err) => return Err(err.to_string()),
+    };
+    let mut archive = match ZipArchive::new(bundle_file) {
+        Ok(archive) => archive,
         Err(err) => return Err(err.to_string()),
-        Ok(exported_item) => exported_item,
     };
+    let mut exported_item = match archive.by_name(BUNDLE_ITEM_FILE_NAME) {
+        Ok(file) => read_envelope(file)?.item,
+        Err(_) => return Err(format!("The bundle has no {BUNDLE_ITEM_FILE_NAME}!")),
+    };
+
+    // The bundle names are mapped to the stored names, which are calculated
+    // again in case the bundle was edited. Dry runs roll the cells back, so
+    // their sources are left as they are and nothing is stored.
+    let mut media_names = HashMap::new();
+    let media_count = if options.dry_run { 0 } else { archive.len() };
+    for i in 0..media_count {
+        let file = match archive.by_index(i) {
+            Ok(file) => file,
+            Err(err) => return Err(err.to_string()),
+        };
+        let name = match file.name().strip_prefix(BUNDLE_MEDIA_DIRECTORY) {
+            Some(name) if is_valid_media_name(name) => name.to_string(),
+            _ => continue,
+        };
+        let extension = name
+            .split_once('.')
+            .map(|(_, extension)| extension.to_string());
+        let stored_name = media_service::store_media(media_directory, file, extension.as_deref())?;
+        media_names.insert(name, stored_name);
+    }
 
+    map_item_sources(&mut exported_item, &mut |src| {
+        src.strip_prefix(BUNDLE_MEDIA_DIRECTORY)
+            .and_then(|name| media_names.get(name))
+            .map(|name| get_media_reference(name))
+    });
+
+    import_into_folder(db_conn, &exported_item, import_into_folder_id, options).await
+}
+
+/// The state of an import. The paths of the exported items start with the
+/// name of the imported item and are relative to the folder imported into.
+struct ImportContext<'a> {
+    options: &'a ImportOptions,
+    parent_folder_path: String,
+    root_path: String,
+    /// Differs from the root path when the imported item was renamed.
+    root_target_path: String,
+    actions: Vec<ImportAction>,
+}
+
+impl ImportContext<'_> {
+    fn get_target_path(&self, item_path: &str) -> String {
+        let path = match item_path.strip_prefix(&self.root_path) {
+            Some(rest) => format!("{}{rest}", self.root_target_path),
+            None => item_path.to_string(),
+        };
+        join_path(&self.parent_folder_path, &path)
+    }
+}
+
+/// Imports the item in a transaction, which is rolled back for dry runs.
+async fn import_into_folder(
+    db_conn: &DbConn,
+    exported_item: &ExportedItem,
+    import_into_folder_id: i32,
+    options: &ImportOptions,
+) -> Result<ImportResult, String> {
     let import_into_folder_path = if import_into_folder_id == 0 {
         "".into()
     } else {
@@ -103,29 +341,352 @@ pub async fn import(
         Err(err) => return Err(err.to_string()),
     };
 
-    import_exported_item(&txn, &exported_item, &import_into_folder_path).await?;
+    let root_target_path = if options.mode == ImportMode::Rename {
+        get_free_name(
+            &txn,
+            &import_into_folder_path,
+            &exported_item.path,
+            exported_item.item_type == ExportedItemType::Folder,
+        )
+        .await?
+    } else {
+        exported_item.path.clone()
+    };
+    let mut context = ImportContext {
+        options,
+        parent_folder_path: import_into_folder_path,
+        root_path: exported_item.path.clone(),
+        root_target_path,
+        actions: vec![],
+    };
+    import_exported_item(&txn, exported_item, &mut context).await?;
 
-    let result = txn.commit().await;
+    let result = if options.dry_run {
+        txn.rollback().await
+    } else {
+        txn.commit().await
+    };
     match result {
-        Ok(_) => Ok(()),
+        Ok(_) => Ok(ImportResult {
+            actions: context.actions,
+        }),
         Err(err) => Err(err.to_string()),
     }
 }
 
+/// Returns the name, or the name with the lowest number like `Deck (2)`, which
+/// no item in the folder has.
+async fn get_free_name(
+    db_conn: &impl ConnectionTrait,
+    parent_folder_path: &str,
+    name: &str,
+    is_folder: bool,
+) -> Result<String, String> {
+    let mut free_name = name.to_string();
+    let mut number = 2;
+    while file_service::get_by_path(
+        db_conn,
+        &join_path(parent_folder_path, &free_name),
+        is_folder,
+    )
+    .await?
+    .is_some()
+    {
+        free_name = format!("{name} ({number})");
+        number += 1;
+    }
+    Ok(free_name)
+}
+
+fn join_path(parent_folder_path: &str, path: &str) -> String {
+    if parent_folder_path.is_empty() {
+        path.to_string()
+    } else {
+        format!("{parent_folder_path}/{path}")
+    }
+}
+
+/// Calls the function with the sources of the media of every cell, like the
+/// `src` attributes in the HTML and the images of image occlusions, and
+/// replaces the sources for which it returns a new value.
+fn map_item_sources(item: &mut ExportedItem, g: &mut impl FnMut(&str) -> Option<String>) {
+    for cell in item.cells.iter_mut().flatten() {
+        let mut f = |html: &str| rewrite_sources(html, &mut *g);
+        cell.content = match cell.cell_type {
+            CellType::FlashCard => match serde_json::from_str::<FlashCard>(&cell.content) {
+                Ok(flash_card) => serde_json::to_string(&FlashCard {
+                    question: f(&flash_card.question),
+                    answer: f(&flash_card.answer),
+                    ..flash_card
+                })
+                .unwrap(),
+                Err(_) => continue,
+            },
+            CellType::TrueFalse => match serde_json::from_str::<TrueFalse>(&cell.content) {
+                Ok(true_false) => serde_json::to_string(&TrueFalse {
+                    question: f(&true_false.question),
+                    is_true: true_false.is_true,
+                })
+                .unwrap(),
+                Err(_) => continue,
+            },
+            CellType::MultipleChoice => {
+                match serde_json::from_str::<MultipleChoice>(&cell.content) {
+                    Ok(multiple_choice) => serde_json::to_string(&MultipleChoice {
+                        question: f(&multiple_choice.question),
+                        options: multiple_choice
+                            .options
+                            .iter()
+                            .map(|option| f(option))
+                            .collect(),
+                        explanation: multiple_choice.explanation.as_deref().map(&mut f),
+                        ..multiple_choice
+                    })
+                    .unwrap(),
+                    Err(_) => continue,
+                }
+            }
+            CellType::TypeAnswer => match serde_json::from_str::<TypeAnswer>(&cell.content) {
+                Ok(type_answer) => serde_json::to_string(&TypeAnswer {
+                    question: f(&type_answer.question),
+                    ..type_answer
+                })
+                .unwrap(),
+                Err(_) => continue,
+            },
+            CellType::Ordering => match serde_json::from_str::<Ordering>(&cell.content) {
+                Ok(ordering) => serde_json::to_string(&Ordering {
+                    question: f(&ordering.question),
+                    items: ordering.items.iter().map(|item| f(item)).collect(),
+                })
+                .unwrap(),
+                Err(_) => continue,
+            },
+            CellType::ImageOcclusion => {
+                match serde_json::from_str::<ImageOcclusion>(&cell.content) {
+                    Ok(image_occlusion) => serde_json::to_string(&ImageOcclusion {
+                        image: g(&image_occlusion.image).unwrap_or(image_occlusion.image),
+                        ..image_occlusion
+                    })
+                    .unwrap(),
+                    Err(_) => continue,
+                }
+            }
+            CellType::Note | CellType::Cloze => f(&cell.content),
+        };
+    }
+    for child in item.children.iter_mut().flatten() {
+        map_item_sources(child, g);
+    }
+}
+
+/// Replaces the `src` attributes for which the function returns a new value.
+pub fn rewrite_sources(html: &str, mut f: impl FnMut(&str) -> Option<String>) -> String {
+    let handler = |el: &mut Element| {
+        if let Some(src) = el.get_attribute("src").and_then(|src| f(&src)) {
+            el.set_attribute("src", &src)?;
+        }
+
+        Ok(())
+    };
+
+    rewrite_str(
+        html,
+        RewriteStrSettings {
+            element_content_handlers: vec![element!("[src]", handler)],
+            ..RewriteStrSettings::default()
+        },
+    )
+    .unwrap()
+}
+
+/// Reads the export file, upgrading older formats, and validates it before
+/// anything is imported.
+fn read_envelope(import_file: impl Read) -> Result<ExportEnvelope, String> {
+    let mut value: Value = match serde_json::from_reader(import_file) {
+        Ok(value) => value,
+        Err(err) => return Err(format!("The file is not valid JSON: {err}")),
+    };
+
+    // Files exported before the format was versioned only contain the item.
+    let mut format_version = match value.get("formatVersion") {
+        None => 0,
+        Some(format_version) => match format_version.as_u64() {
+            Some(format_version) => format_version as u32,
+            None => return Err(format!("Invalid format version {format_version}!")),
+        },
+    };
+    if format_version > FORMAT_VERSION {
+        return Err(format!(
+            "The file has format version {format_version}, but only versions up to \
+            {FORMAT_VERSION} are supported. Update the app to import it!"
+        ));
+    }
+    while format_version < FORMAT_VERSION {
+        value = UPGRADERS[format_version as usize](value);
+        format_version += 1;
+    }
+
+    if value.get("checksum").and_then(Value::as_str) != Some(&get_checksum(&value["item"])) {
+        return Err("The checksum does not match, the file is damaged or was edited!".into());
+    }
+    let mut errors = vec![];
+    validate_item(&value["item"], "", &mut errors);
+    if !errors.is_empty() {
+        return Err(format!("The file is invalid:\n{}", errors.join("\n")));
+    }
+
+    match serde_json::from_value(value) {
+        Ok(envelope) => Ok(envelope),
+        Err(err) => Err(format!("The file is invalid: {err}")),
+    }
+}
+
+fn upgrade_from_version_0(item: Value) -> Value {
+    json!({
+        "formatVersion": 1,
+        "appVersion": null,
+        "createdAt": null,
+        "checksum": get_checksum(&item),
+        "item": item,
+    })
+}
+
+fn get_checksum(item: &Value) -> String {
+    format!("{:x}", Sha256::digest(item.to_string().as_bytes()))
+}
+
+fn validate_item(item: &Value, parent_folder_path: &str, errors: &mut Vec<String>) {
+    let path = match item.get("path").and_then(Value::as_str) {
+        Some(path) if !path.trim().is_empty() => path,
+        _ => {
+            errors.push(format!(
+                "An item in the folder \"{parent_folder_path}\" has no path!"
+            ));
+            return;
+        }
+    };
+
+    match item.get("itemType").and_then(Value::as_str) {
+        Some("File") => {
+            let cells = item.get("cells").and_then(Value::as_array);
+            for (i, cell) in cells.into_iter().flatten().enumerate() {
+                if let Err(err) = validate_cell(cell) {
+                    errors.push(format!("File \"{path}\", cell {}: {err}", i + 1));
+                }
+            }
+        }
+        Some("Folder") => {
+            let children = item.get("children").and_then(Value::as_array);
+            for child in children.into_iter().flatten() {
+                validate_item(child, path, errors);
+            }
+        }
+        _ => errors.push(format!(
+            "Item \"{path}\" has an invalid type, expected File or Folder!"
+        )),
+    }
+}
+
+fn validate_cell(cell: &Value) -> Result<(), String> {
+    let cell: ExportedCell = match serde_json::from_value(cell.clone()) {
+        Ok(cell) => cell,
+        Err(err) => return Err(err.to_string()),
+    };
+    let result = match cell.cell_type {
+        CellType::FlashCard => serde_json::from_str::<FlashCard>(&cell.content).map(|_| ()),
+        CellType::TrueFalse => serde_json::from_str::<TrueFalse>(&cell.content).map(|_| ()),
+        CellType::MultipleChoice => match serde_json::from_str::<MultipleChoice>(&cell.content) {
+            Ok(multiple_choice) => {
+                if let Err(err) = multiple_choice.validate() {
+                    return Err(format!("Invalid {:?} content: {err}", cell.cell_type));
+                }
+                Ok(())
+            }
+            Err(err) => Err(err),
+        },
+        CellType::TypeAnswer => match serde_json::from_str::<TypeAnswer>(&cell.content) {
+            Ok(type_answer) => {
+                if let Err(err) = type_answer.validate() {
+                    return Err(format!("Invalid {:?} content: {err}", cell.cell_type));
+                }
+                Ok(())
+            }
+            Err(err) => Err(err),
+        },
+        CellType::Ordering => match serde_json::from_str::<Ordering>(&cell.content) {
+            Ok(ordering) => {
+                if let Err(err) = ordering.validate() {
+                    return Err(format!("Invalid {:?} content: {err}", cell.cell_type));
+                }
+                Ok(())
+            }
+            Err(err) => Err(err),
+        },
+        CellType::ImageOcclusion => match serde_json::from_str::<ImageOcclusion>(&cell.content) {
+            Ok(image_occlusion) => {
+                if let Err(err) = image_occlusion.validate() {
+                    return Err(format!("Invalid {:?} content: {err}", cell.cell_type));
+                }
+                Ok(())
+            }
+            Err(err) => Err(err),
+        },
+        CellType::Note | CellType::Cloze => Ok(()),
+    };
+    if let Err(err) = result {
+        return Err(format!("Invalid {:?} content: {err}", cell.cell_type));
+    }
+    for tag in &cell.tags {
+        tag_service::normalize_tag_name(tag)?;
+    }
+
+    for (i, repetition) in cell.repetitions.iter().flatten().enumerate() {
+        let error = if !repetition.stability.is_finite() || repetition.stability < 0.0 {
+            Some("the stability must be a positive number")
+        } else if !repetition.difficulty.is_finite() || repetition.difficulty < 0.0 {
+            Some("the difficulty must be a positive number")
+        } else if repetition.reps < 0 || repetition.lapses < 0 {
+            Some("the reps and lapses cannot be negative")
+        } else if cell.cell_type == CellType::Cloze
+            && repetition
+                .additional_content
+                .as_ref()
+                .is_none_or(|index| index.parse::<u32>().is_err())
+        {
+            Some("the cloze index must be a number")
+        } else if cell.cell_type == CellType::ImageOcclusion
+            && repetition
+                .additional_content
+                .as_ref()
+                .is_none_or(|mask_id| mask_id.parse::<u32>().is_err())
+        {
+            Some("the mask id must be a number")
+        } else {
+            None
+        };
+        if let Some(error) = error {
+            return Err(format!("Repetition {}: {error}!", i + 1));
+        }
+    }
+
+    Ok(())
+}
+
 async fn import_exported_item(
     db_conn: &impl ConnectionTrait,
     exported_item: &ExportedItem,
-    parent_folder_path: &String,
+    context: &mut ImportContext<'_>,
 ) -> Result<(), String> {
     match exported_item.item_type {
         ExportedItemType::File => {
-            import_file_from_exported_item(db_conn, exported_item, parent_folder_path).await
+            import_file_from_exported_item(db_conn, exported_item, context).await
         }
         ExportedItemType::Folder => {
             Box::pin(import_folder_from_exported_item(
                 db_conn,
                 exported_item,
-                parent_folder_path,
+                context,
             ))
             .await
         }
@@ -135,22 +696,133 @@ async fn import_exported_item(
 async fn import_file_from_exported_item(
     db_conn: &impl ConnectionTrait,
     exported_item: &ExportedItem,
-    parent_folder_path: &String,
+    context: &mut ImportContext<'_>,
 ) -> Result<(), String> {
-    let file_id = file_service::create_file(
-        db_conn,
-        format!("{parent_folder_path}/{}", exported_item.path),
-    )
-    .await?;
+    let mut path = context.get_target_path(&exported_item.path);
+    let mut existing_cells = vec![];
+    let (kind, file_id) = match file_service::get_by_path(db_conn, &path, false).await? {
+        None => (
+            ImportActionKind::CreateFile,
+            file_service::create_file(db_conn, path.clone()).await?,
+        ),
+        Some(file) => match context.options.mode {
+            ImportMode::Rename => {
+                let (folder_path, name) = path.rsplit_once('/').unwrap_or(("", &path));
+                path = join_path(
+                    folder_path,
+                    &get_free_name(db_conn, folder_path, name, false).await?,
+                );
+                (
+                    ImportActionKind::CreateFile,
+                    file_service::create_file(db_conn, path.clone()).await?,
+                )
+            }
+            ImportMode::Merge | ImportMode::SkipDuplicates => {
+                let result = cell::Entity::find()
+                    .filter(cell::Column::FileId.eq(file.id))
+                    .all(db_conn)
+                    .await;
+                existing_cells = match result {
+                    Ok(cells) => cells,
+                    Err(err) => return Err(err.to_string()),
+                };
+                (ImportActionKind::MergeFile, file.id)
+            }
+            ImportMode::Overwrite => {
+                let result = cell::Entity::delete_many()
+                    .filter(cell::Column::FileId.eq(file.id))
+                    .exec(db_conn)
+                    .await;
+                if let Err(err) = result {
+                    return Err(err.to_string());
+                }
+                (ImportActionKind::OverwriteFile, file.id)
+            }
+        },
+    };
 
-    if let Some(cells) = exported_item.cells.as_ref() {
-        for (i, cell) in cells.iter().enumerate() {
-            cell_service::create_cell_no_transaction(
+    let mut cell_hashes: HashSet<String> = existing_cells
+        .iter()
+        .map(|cell| get_cell_hash(&cell.cell_type, &cell.content))
+        .collect();
+    let mut index = existing_cells.len() as i32;
+    let mut action = ImportAction {
+        path,
+        kind,
+        added_cells_count: 0,
+        skipped_cells_count: 0,
+    };
+    for cell in exported_item.cells.iter().flatten() {
+        let content = purify_html(&cell.content);
+        if context.options.mode == ImportMode::SkipDuplicates
+            && !cell_hashes.insert(get_cell_hash(&cell.cell_type, &content))
+        {
+            action.skipped_cells_count += 1;
+            continue;
+        }
+
+        let cell_id = cell_service::create_cell_no_transaction(
+            db_conn,
+            file_id,
+            &content,
+            &cell.cell_type,
+            index,
+        )
+        .await?;
+        if let Some(repetitions) = cell.repetitions.as_ref() {
+            import_repetitions(db_conn, cell_id, repetitions).await?;
+        }
+        tag_service::add_tags_no_transaction(db_conn, cell_id, &cell.tags).await?;
+        index += 1;
+        action.added_cells_count += 1;
+    }
+    context.actions.push(action);
+
+    Ok(())
+}
+
+fn get_cell_hash(cell_type: &CellType, content: &str) -> String {
+    let digest = Sha256::digest(format!("{cell_type:?}\n{content}").as_bytes());
+    format!("{digest:x}")
+}
+
+/// Restores the progress of the new repetitions of the cell, cloze
+/// repetitions are matched by their cloze index and mask repetitions by their
+/// mask id.
+async fn import_repetitions(
+    db_conn: &impl ConnectionTrait,
+    cell_id: i32,
+    exported_repetitions: &[ExportedRepetition],
+) -> Result<(), String> {
+    let result = repetition::Entity::find()
+        .filter(repetition::Column::CellId.eq(cell_id))
+        .all(db_conn)
+        .await;
+    let repetitions = match result {
+        Ok(repetitions) => repetitions,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    for repetition in repetitions {
+        let exported_repetition = exported_repetitions.iter().find(|exported_repetition| {
+            get_repetition_key(exported_repetition.additional_content.as_deref())
+                == get_repetition_key(repetition.additional_content.as_deref())
+        });
+        if let Some(exported_repetition) = exported_repetition {
+            repetition_service::save_repetition(
                 db_conn,
-                file_id,
-                &purify_html(&cell.content),
-                &cell.cell_type,
-                i as i32,
+                repetition::Model {
+                    due: exported_repetition.due,
+                    stability: exported_repetition.stability,
+                    difficulty: exported_repetition.difficulty,
+                    elapsed_days: exported_repetition.elapsed_days,
+                    scheduled_days: exported_repetition.scheduled_days,
+                    reps: exported_repetition.reps,
+                    lapses: exported_repetition.lapses,
+                    state: exported_repetition.state.clone(),
+                    last_review: exported_repetition.last_review,
+                    ..repetition
+                },
             )
             .await?;
         }
@@ -159,7 +831,16 @@ async fn import_file_from_exported_item(
     Ok(())
 }
 
-fn purify_html(html: &str) -> String {
+/// The front repetition of a flash card has no additional content, but is
+/// named in some exports.
+fn get_repetition_key(additional_content: Option<&str>) -> Option<&str> {
+    match additional_content {
+        Some(FlashCard::FRONT) => None,
+        additional_content => additional_content,
+    }
+}
+
+pub fn purify_html(html: &str) -> String {
     let handler = |el: &mut Element| {
         if el.tag_name().to_lowercase() == "script"
             || el
@@ -168,6 +849,11 @@ fn purify_html(html: &str) -> String {
                 .any(|attr| attr.name().to_lowercase().starts_with("on"))
         {
             el.remove();
+        } else if el
+            .get_attribute("src")
+            .is_some_and(|src| is_local_file_url(&src))
+        {
+            el.remove_attribute("src");
         }
 
         Ok(())
@@ -186,17 +872,28 @@ fn purify_html(html: &str) -> String {
 async fn import_folder_from_exported_item(
     db_conn: &impl ConnectionTrait,
     exported_item: &ExportedItem,
-    parent_folder_path: &String,
+    context: &mut ImportContext<'_>,
 ) -> Result<(), String> {
-    file_service::create_folder(
-        db_conn,
-        format!("{parent_folder_path}/{}", exported_item.path),
-    )
-    .await?;
+    let path = context.get_target_path(&exported_item.path);
+    let kind = if file_service::get_by_path(db_conn, &path, true)
+        .await?
+        .is_some()
+    {
+        ImportActionKind::MergeFolder
+    } else {
+        file_service::create_folder(db_conn, path.clone()).await?;
+        ImportActionKind::CreateFolder
+    };
+    context.actions.push(ImportAction {
+        path,
+        kind,
+        added_cells_count: 0,
+        skipped_cells_count: 0,
+    });
 
     if let Some(children) = exported_item.children.as_ref() {
         for child in children {
-            import_exported_item(db_conn, child, parent_folder_path).await?
+            import_exported_item(db_conn, child, context).await?
         }
     }
 
@@ -210,30 +907,16 @@ mod tests {
     use super::*;
     use crate::{
         entity::cell::CellType,
-        model::{flash_card::FlashCard, true_false::TrueFalse},
+        model::{
+            flash_card::FlashCard,
+            image_occlusion::{MaskShape, OcclusionMask},
+            true_false::TrueFalse,
+        },
         service::{
             repetition_service,
-            tests::{create_file_cell_with_cell_type_and_content, get_db},
+            tests::{create_file_cell_with_cell_type_and_content, get_db, get_random_file_path},
         },
     };
-    use rand::prelude::*;
-
-    fn get_random_file_path() -> PathBuf {
-        let temp_dir = std::env::current_dir().unwrap().join("temp");
-
-        if !temp_dir.exists() {
-            std::fs::create_dir(&temp_dir).unwrap();
-        }
-
-        let mut rng = rand::rng();
-        let mut file_name = String::new();
-
-        for _ in 1..6 {
-            file_name = format!("{}{}", file_name, rng.random::<char>());
-        }
-
-        temp_dir.join(format!("{file_name}.json"))
-    }
 
     #[tokio::test]
     async fn export_file_exported_file_correctly() {
@@ -252,18 +935,25 @@ mod tests {
             &file_content,
         )
         .await;
-        let export_path = get_random_file_path();
+        let export_path = get_random_file_path("json");
 
         // Act
 
-        export(&db_conn, file_id, export_path.to_str().unwrap().into())
-            .await
-            .unwrap();
+        export(
+            &db_conn,
+            file_id,
+            export_path.to_str().unwrap().into(),
+            false,
+        )
+        .await
+        .unwrap();
 
         // Assert
 
         let file = File::open(export_path.clone()).unwrap();
-        let exported_item: ExportedItem = serde_json::from_reader(file).unwrap();
+        let exported_item = serde_json::from_reader::<_, ExportEnvelope>(file)
+            .unwrap()
+            .item;
 
         assert_eq!(exported_item.item_type, ExportedItemType::File);
         assert_eq!(exported_item.path, "file 1".to_string());
@@ -310,18 +1000,25 @@ mod tests {
             "file content",
         )
         .await;
-        let export_path = get_random_file_path();
+        let export_path = get_random_file_path("json");
 
         // Act
 
-        export(&db_conn, folder_id, export_path.to_str().unwrap().into())
-            .await
-            .unwrap();
+        export(
+            &db_conn,
+            folder_id,
+            export_path.to_str().unwrap().into(),
+            false,
+        )
+        .await
+        .unwrap();
 
         // Assert
 
         let file = File::open(export_path.clone()).unwrap();
-        let exported_item: ExportedItem = serde_json::from_reader(file).unwrap();
+        let exported_item = serde_json::from_reader::<_, ExportEnvelope>(file)
+            .unwrap()
+            .item;
 
         assert_eq!(exported_item.item_type, ExportedItemType::Folder);
         assert_eq!(exported_item.path, "folder 2".to_string());
@@ -395,10 +1092,15 @@ mod tests {
             .await
             .unwrap();
 
-        let export_path = get_random_file_path();
-        export(&db_conn, folder2_id, export_path.to_str().unwrap().into())
-            .await
-            .unwrap();
+        let export_path = get_random_file_path("json");
+        export(
+            &db_conn,
+            folder2_id,
+            export_path.to_str().unwrap().into(),
+            false,
+        )
+        .await
+        .unwrap();
 
         // Act
 
@@ -406,6 +1108,7 @@ mod tests {
             &db_conn,
             export_path.to_str().unwrap().into(),
             import_folder_id,
+            &ImportOptions::default(),
         )
         .await
         .unwrap();
@@ -490,10 +1193,15 @@ mod tests {
             .await
             .unwrap();
 
-        let export_path = get_random_file_path();
-        export(&db_conn, folder_id, export_path.to_str().unwrap().into())
-            .await
-            .unwrap();
+        let export_path = get_random_file_path("json");
+        export(
+            &db_conn,
+            folder_id,
+            export_path.to_str().unwrap().into(),
+            false,
+        )
+        .await
+        .unwrap();
 
         // Act
 
@@ -501,6 +1209,7 @@ mod tests {
             &db_conn,
             export_path.to_str().unwrap().into(),
             import_folder_id,
+            &ImportOptions::default(),
         )
         .await
         .unwrap();
@@ -520,4 +1229,686 @@ mod tests {
             .any(|c| c.content.contains("script") || c.content.contains("onLoad"));
         assert!(!is_javascript_existing);
     }
+
+    #[tokio::test]
+    async fn import_named_front_repetition_restored_front_repetition() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let repetition = |additional_content: &str, reps| ExportedRepetition {
+            due: chrono::Utc::now(),
+            stability: 1.0,
+            difficulty: 5.0,
+            elapsed_days: 0,
+            scheduled_days: 1,
+            reps,
+            lapses: 0,
+            state: repetition::State::Review,
+            last_review: chrono::Utc::now(),
+            additional_content: Some(additional_content.into()),
+        };
+        let exported_item = ExportedItem::new(
+            "deck".into(),
+            ExportedItemType::File,
+            Some(vec![ExportedCell {
+                content: serde_json::to_string(&FlashCard {
+                    question: "perro".into(),
+                    answer: "dog".into(),
+                    is_reversible: true,
+                })
+                .unwrap(),
+                cell_type: CellType::FlashCard,
+                repetitions: Some(vec![
+                    repetition(FlashCard::FRONT, 3),
+                    repetition(FlashCard::BACK, 5),
+                ]),
+                tags: vec![],
+            }]),
+            None,
+        );
+        let import_path = get_random_file_path("json");
+        fs::write(&import_path, serde_json::to_string(&exported_item).unwrap()).unwrap();
+
+        // Act
+
+        import(
+            &db_conn,
+            import_path.to_str().unwrap().into(),
+            0,
+            &ImportOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        let file_id = file_service::get_files(&db_conn).await.unwrap()[0].id;
+        let actual: Vec<(Option<String>, i32)> =
+            repetition_service::get_file_repetitions(&db_conn, file_id)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|repetition| (repetition.additional_content, repetition.reps))
+                .collect();
+        assert_eq!(actual, vec![(None, 3), (Some(FlashCard::BACK.into()), 5)]);
+    }
+
+    #[tokio::test]
+    async fn import_exported_progress_restored_repetitions() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let folder_id = file_service::create_folder(&db_conn, "folder".into())
+            .await
+            .unwrap();
+        let (file_id, _) = create_file_cell_with_cell_type_and_content(
+            &db_conn,
+            "folder/file",
+            CellType::Cloze,
+            "<cloze index=\"1\">Madrid</cloze> is in <cloze index=\"2\">Spain</cloze>",
+        )
+        .await;
+        let due = chrono::Utc::now() + chrono::Duration::days(30);
+        for repetition in repetition_service::get_file_repetitions(&db_conn, file_id)
+            .await
+            .unwrap()
+        {
+            if repetition.additional_content == Some("2".into()) {
+                repetition_service::save_repetition(
+                    &db_conn,
+                    repetition::Model {
+                        due,
+                        stability: 12.5,
+                        difficulty: 4.0,
+                        reps: 7,
+                        lapses: 2,
+                        state: repetition::State::Review,
+                        ..repetition
+                    },
+                )
+                .await
+                .unwrap();
+            }
+        }
+        let import_folder_id = file_service::create_folder(&db_conn, "import folder".into())
+            .await
+            .unwrap();
+        let export_path = get_random_file_path("json");
+        export(
+            &db_conn,
+            folder_id,
+            export_path.to_str().unwrap().into(),
+            true,
+        )
+        .await
+        .unwrap();
+
+        // Act
+
+        import(
+            &db_conn,
+            export_path.to_str().unwrap().into(),
+            import_folder_id,
+            &ImportOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        let imported_file_id =
+            file_service::list_folder_children_recursively(&db_conn, import_folder_id)
+                .await
+                .unwrap()
+                .into_iter()
+                .find(|file| !file.is_folder)
+                .unwrap()
+                .id;
+        let mut repetitions = repetition_service::get_file_repetitions(&db_conn, imported_file_id)
+            .await
+            .unwrap();
+        repetitions.sort_by(|a, b| a.additional_content.cmp(&b.additional_content));
+        assert_eq!(repetitions.len(), 2);
+        assert_eq!(repetitions[0].state, repetition::State::New);
+        assert_eq!(repetitions[0].reps, 0);
+        assert_eq!(repetitions[1].state, repetition::State::Review);
+        assert_eq!(repetitions[1].due, due);
+        assert_eq!(repetitions[1].stability, 12.5);
+        assert_eq!(repetitions[1].difficulty, 4.0);
+        assert_eq!(repetitions[1].reps, 7);
+        assert_eq!(repetitions[1].lapses, 2);
+    }
+
+    #[tokio::test]
+    async fn import_exported_tags_restored_tags() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (file_id, cell_id) =
+            create_file_cell_with_cell_type_and_content(&db_conn, "file", CellType::Note, "note")
+                .await;
+        tag_service::add_tag(&db_conn, vec![cell_id], "lang::spanish")
+            .await
+            .unwrap();
+        let import_folder_id = file_service::create_folder(&db_conn, "import folder".into())
+            .await
+            .unwrap();
+        let export_path = get_random_file_path("json");
+        export(
+            &db_conn,
+            file_id,
+            export_path.to_str().unwrap().into(),
+            false,
+        )
+        .await
+        .unwrap();
+
+        // Act
+
+        import(
+            &db_conn,
+            export_path.to_str().unwrap().into(),
+            import_folder_id,
+            &ImportOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        let imported_file = file_service::get_by_path(&db_conn, "import folder/file", false)
+            .await
+            .unwrap()
+            .unwrap();
+        let imported_cells =
+            cell_service::get_file_cells_ordered_by_index(&db_conn, imported_file.id)
+                .await
+                .unwrap();
+        let cell_tags = tag_service::get_tags_for_cells(&db_conn, vec![imported_cells[0].id])
+            .await
+            .unwrap();
+        assert_eq!(cell_tags[&imported_cells[0].id], vec!["lang::spanish"]);
+    }
+
+    #[tokio::test]
+    async fn import_unversioned_file_imported_item() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let import_path = get_random_file_path("json");
+        fs::write(
+            &import_path,
+            r#"{"path":"file","itemType":"File","cells":[{"content":"note","cellType":"Note"}],"children":null}"#,
+        )
+        .unwrap();
+
+        // Act
+
+        import(
+            &db_conn,
+            import_path.to_str().unwrap().into(),
+            0,
+            &ImportOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        let files = file_service::get_files(&db_conn).await.unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "file");
+        let cells = cell_service::get_file_cells_ordered_by_index(&db_conn, files[0].id)
+            .await
+            .unwrap();
+        assert_eq!(cells[0].content, "note");
+    }
+
+    #[tokio::test]
+    async fn import_edited_file_returned_checksum_error() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (file_id, _) =
+            create_file_cell_with_cell_type_and_content(&db_conn, "file", CellType::Note, "old")
+                .await;
+        let export_path = get_random_file_path("json");
+        export(
+            &db_conn,
+            file_id,
+            export_path.to_str().unwrap().into(),
+            false,
+        )
+        .await
+        .unwrap();
+        let exported = fs::read_to_string(&export_path).unwrap();
+        fs::write(&export_path, exported.replace("old", "new")).unwrap();
+
+        // Act
+
+        let actual = import(
+            &db_conn,
+            export_path.to_str().unwrap().into(),
+            0,
+            &ImportOptions::default(),
+        )
+        .await;
+
+        // Assert
+
+        assert_eq!(
+            actual,
+            Err("The checksum does not match, the file is damaged or was edited!".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn import_invalid_cells_returned_errors_and_imported_nothing() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let import_path = get_random_file_path("json");
+        fs::write(
+            &import_path,
+            r#"{"path":"folder","itemType":"Folder","cells":null,"children":[
+                {"path":"folder/file","itemType":"File","children":null,"cells":[
+                    {"content":"note","cellType":"Note"},
+                    {"content":"{}","cellType":"TrueFalse"},
+                    {"content":"<cloze index=\"1\">x</cloze>","cellType":"Cloze","repetitions":[
+                        {"due":"2025-01-01T00:00:00Z","stability":-1.0,"difficulty":5.0,
+                        "elapsedDays":0,"scheduledDays":0,"reps":1,"lapses":0,"state":"Review",
+                        "lastReview":"2025-01-01T00:00:00Z","additionalContent":"1"}
+                    ]},
+                    {"content":"{\"question\":\"q\",\"options\":[\"a\",\"b\"],\"correctIndices\":[2]}","cellType":"MultipleChoice"},
+                    {"content":"{\"image\":\"image.png\",\"masks\":[]}","cellType":"ImageOcclusion"}
+                ]},
+                {"path":"folder/other","itemType":"Deck"}
+            ]}"#,
+        )
+        .unwrap();
+
+        // Act
+
+        let actual = import(
+            &db_conn,
+            import_path.to_str().unwrap().into(),
+            0,
+            &ImportOptions::default(),
+        )
+        .await;
+
+        // Assert
+
+        assert_eq!(
+            actual,
+            Err("The file is invalid:\n\
+                File \"folder/file\", cell 2: Invalid TrueFalse content: missing field `question` at line 1 column 2\n\
+                File \"folder/file\", cell 3: Repetition 1: the stability must be a positive number!\n\
+                File \"folder/file\", cell 4: Invalid MultipleChoice content: The correct option 2 does not exist!\n\
+                File \"folder/file\", cell 5: Invalid ImageOcclusion content: An image occlusion must have at least one mask!\n\
+                Item \"folder/other\" has an invalid type, expected File or Folder!"
+                .into())
+        );
+        assert!(file_service::get_files(&db_conn).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn import_newer_format_version_returned_error() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let import_path = get_random_file_path("json");
+        fs::write(&import_path, r#"{"formatVersion":99}"#).unwrap();
+
+        // Act
+
+        let actual = import(
+            &db_conn,
+            import_path.to_str().unwrap().into(),
+            0,
+            &ImportOptions::default(),
+        )
+        .await;
+
+        // Assert
+
+        assert_eq!(
+            actual,
+            Err(
+                "The file has format version 99, but only versions up to 1 are supported. \
+                Update the app to import it!"
+                    .into()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn import_bundle_exported_bundle_copied_store_media_and_rewrote_sources() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let media_source_directory = get_random_file_path("media");
+        fs::create_dir_all(&media_source_directory).unwrap();
+        // Local files outside of the store are neither exported nor imported.
+        let image_path = media_source_directory.join("secret.PNG");
+        fs::write(&image_path, b"secret").unwrap();
+        let store_directory = media_source_directory.join("store");
+        let stored_copy =
+            media_service::add_media_from_bytes(&store_directory, b"image", "png").unwrap();
+        let flash_card = serde_json::to_string(&FlashCard {
+            question: format!("<img src=\"file://{}\">", image_path.to_str().unwrap()),
+            answer: "<img src=\"https://example.com/image.png\">".into(),
+            ..Default::default()
+        })
+        .unwrap();
+        let folder_id = file_service::create_folder(&db_conn, "folder".into())
+            .await
+            .unwrap();
+        let (file_id, _) = create_file_cell_with_cell_type_and_content(
+            &db_conn,
+            "folder/file",
+            CellType::FlashCard,
+            &flash_card,
+        )
+        .await;
+        cell_service::create_cell(
+            &db_conn,
+            file_id,
+            &format!("<p><img src=\"{}\"></p>", stored_copy.url),
+            CellType::Note,
+            1,
+        )
+        .await
+        .unwrap();
+        let image_occlusion = ImageOcclusion {
+            image: stored_copy.url.clone(),
+            masks: vec![OcclusionMask {
+                id: 1,
+                label: "label".into(),
+                shape: MaskShape::Rectangle {
+                    x: 0.0,
+                    y: 0.0,
+                    width: 0.5,
+                    height: 0.5,
+                },
+            }],
+        };
+        cell_service::create_cell(
+            &db_conn,
+            file_id,
+            &serde_json::to_string(&image_occlusion).unwrap(),
+            CellType::ImageOcclusion,
+            2,
+        )
+        .await
+        .unwrap();
+        let bundle_path = get_random_file_path("brainy");
+        let media_directory = media_source_directory.join("media");
+        let import_folder_id = file_service::create_folder(&db_conn, "import folder".into())
+            .await
+            .unwrap();
+
+        // Act
+
+        export_bundle(
+            &db_conn,
+            folder_id,
+            bundle_path.to_str().unwrap().into(),
+            false,
+            &store_directory,
+        )
+        .await
+        .unwrap();
+        import_bundle(
+            &db_conn,
+            bundle_path.to_str().unwrap().into(),
+            import_folder_id,
+            &media_directory,
+            &ImportOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        let media_name = format!("{:x}.png", Sha256::digest(b"image"));
+        let archive = ZipArchive::new(File::open(&bundle_path).unwrap()).unwrap();
+        let mut names: Vec<&str> = archive.file_names().collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                BUNDLE_ITEM_FILE_NAME.to_string(),
+                format!("{BUNDLE_MEDIA_DIRECTORY}{media_name}")
+            ]
+        );
+        let imported_media_path = media_directory.join(&media_name);
+        assert_eq!(fs::read(&imported_media_path).unwrap(), b"image");
+
+        let imported_file_id =
+            file_service::list_folder_children_recursively(&db_conn, import_folder_id)
+                .await
+                .unwrap()
+                .into_iter()
+                .find(|file| !file.is_folder)
+                .unwrap()
+                .id;
+        let cells = cell_service::get_file_cells_ordered_by_index(&db_conn, imported_file_id)
+            .await
+            .unwrap();
+        let media_url = get_media_reference(&media_name);
+        assert_eq!(
+            serde_json::from_str::<FlashCard>(&cells[0].content).unwrap(),
+            FlashCard {
+                question: "<img>".into(),
+                answer: "<img src=\"https://example.com/image.png\">".into(),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            cells[1].content,
+            format!("<p><img src=\"{media_url}\"></p>")
+        );
+        assert_eq!(
+            serde_json::from_str::<ImageOcclusion>(&cells[2].content).unwrap(),
+            ImageOcclusion {
+                image: media_url,
+                ..image_occlusion
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn import_bundle_dry_run_stored_no_media() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let store_directory = get_random_file_path("media");
+        let stored_image =
+            media_service::add_media_from_bytes(&store_directory, b"image", "png").unwrap();
+        let (file_id, _) = create_file_cell_with_cell_type_and_content(
+            &db_conn,
+            "deck",
+            CellType::Note,
+            &format!("<img src=\"{}\">", stored_image.url),
+        )
+        .await;
+        let bundle_path = get_random_file_path("brainy");
+        export_bundle(
+            &db_conn,
+            file_id,
+            bundle_path.to_str().unwrap().into(),
+            false,
+            &store_directory,
+        )
+        .await
+        .unwrap();
+        let media_directory = get_random_file_path("media");
+        let options = ImportOptions {
+            mode: ImportMode::Rename,
+            dry_run: true,
+        };
+
+        // Act
+
+        let actual = import_bundle(
+            &db_conn,
+            bundle_path.to_str().unwrap().into(),
+            0,
+            &media_directory,
+            &options,
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        assert_eq!(actual.actions.len(), 1);
+        assert!(!media_directory.exists());
+        assert_eq!(file_service::get_files(&db_conn).await.unwrap().len(), 1);
+    }
+
+    fn write_deck_export() -> PathBuf {
+        let import_path = get_random_file_path("json");
+        fs::write(
+            &import_path,
+            r#"{"path":"deck","itemType":"File","cells":[{"content":"a","cellType":"Note"},{"content":"b","cellType":"Note"}],"children":null}"#,
+        )
+        .unwrap();
+        import_path
+    }
+
+    async fn get_cell_contents(db_conn: &DbConn, file_id: i32) -> Vec<String> {
+        cell_service::get_file_cells_ordered_by_index(db_conn, file_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|cell| cell.content)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn import_existing_file_in_rename_mode_imported_renamed_file() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (file_id, _) =
+            create_file_cell_with_cell_type_and_content(&db_conn, "deck", CellType::Note, "a")
+                .await;
+        let import_path = write_deck_export();
+
+        // Act
+
+        let actual = import(
+            &db_conn,
+            import_path.to_str().unwrap().into(),
+            0,
+            &ImportOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        assert_eq!(
+            actual.actions,
+            vec![ImportAction {
+                path: "deck (2)".into(),
+                kind: ImportActionKind::CreateFile,
+                added_cells_count: 2,
+                skipped_cells_count: 0,
+            }]
+        );
+        assert_eq!(get_cell_contents(&db_conn, file_id).await, vec!["a"]);
+        let renamed_file = file_service::get_by_path(&db_conn, "deck (2)", false)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            get_cell_contents(&db_conn, renamed_file.id).await,
+            vec!["a", "b"]
+        );
+    }
+
+    #[tokio::test]
+    async fn import_existing_file_in_skip_duplicates_mode_added_only_new_cells() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (file_id, _) =
+            create_file_cell_with_cell_type_and_content(&db_conn, "deck", CellType::Note, "a")
+                .await;
+        let import_path = write_deck_export();
+        let options = ImportOptions {
+            mode: ImportMode::SkipDuplicates,
+            dry_run: false,
+        };
+
+        // Act
+
+        let actual = import(&db_conn, import_path.to_str().unwrap().into(), 0, &options)
+            .await
+            .unwrap();
+
+        // Assert
+
+        assert_eq!(actual.actions[0].kind, ImportActionKind::MergeFile);
+        assert_eq!(actual.actions[0].added_cells_count, 1);
+        assert_eq!(actual.actions[0].skipped_cells_count, 1);
+        assert_eq!(get_cell_contents(&db_conn, file_id).await, vec!["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn import_existing_file_in_overwrite_mode_replaced_cells() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (file_id, _) =
+            create_file_cell_with_cell_type_and_content(&db_conn, "deck", CellType::Note, "old")
+                .await;
+        let import_path = write_deck_export();
+        let options = ImportOptions {
+            mode: ImportMode::Overwrite,
+            dry_run: false,
+        };
+
+        // Act
+
+        let actual = import(&db_conn, import_path.to_str().unwrap().into(), 0, &options)
+            .await
+            .unwrap();
+
+        // Assert
+
+        assert_eq!(actual.actions[0].kind, ImportActionKind::OverwriteFile);
+        assert_eq!(get_cell_contents(&db_conn, file_id).await, vec!["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn import_dry_run_returned_actions_and_changed_nothing() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (file_id, _) =
+            create_file_cell_with_cell_type_and_content(&db_conn, "deck", CellType::Note, "a")
+                .await;
+        let import_path = write_deck_export();
+        let options = ImportOptions {
+            mode: ImportMode::Merge,
+            dry_run: true,
+        };
+
+        // Act
+
+        let actual = import(&db_conn, import_path.to_str().unwrap().into(), 0, &options)
+            .await
+            .unwrap();
+
+        // Assert
+
+        assert_eq!(actual.actions[0].kind, ImportActionKind::MergeFile);
+        assert_eq!(actual.actions[0].added_cells_count, 2);
+        assert_eq!(get_cell_contents(&db_conn, file_id).await, vec!["a"]);
+        assert_eq!(file_service::get_files(&db_conn).await.unwrap().len(), 1);
+    }
 }
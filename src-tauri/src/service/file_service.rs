@@ -1,9 +1,9 @@
 use prelude::Expr;
-use sea_orm::{DbConn, entity::*, query::*};
+use sea_orm::{DbConn, Value, entity::*, query::*, sea_query::SimpleExpr};
 
 use crate::{dto::file_with_repetitions_count::FileWithRepetitionsCount, entity::file};
 
-use super::repetition_service;
+use super::{repetition_service, trash_service};
 
 pub async fn get_files(db_conn: &DbConn) -> Result<Vec<FileWithRepetitionsCount>, String> {
     let result = file::Entity::find().all(db_conn).await;
@@ -67,47 +67,19 @@ pub async fn create_folder(db_conn: &impl ConnectionTrait, path: String) -> Resu
     create_folder_recursively(db_conn, &path).await
 }
 
-pub async fn delete_file(db_conn: &DbConn, file_id: i32) -> Result<(), String> {
-    let result = file::Entity::delete_many()
-        .filter(file::Column::Id.eq(file_id))
-        .exec(db_conn)
-        .await;
-
-    match result {
-        Ok(_) => Ok(()),
-        Err(err) => Err(err.to_string()),
-    }
+/// Moves the file to the trash and returns the id of the trash item.
+pub async fn delete_file(db_conn: &DbConn, file_id: i32) -> Result<i32, String> {
+    let file = get_by_id(db_conn, file_id).await?;
+    trash_service::move_to_trash(db_conn, vec![file]).await
 }
 
-pub async fn delete_folder(db_conn: &DbConn, folder_id: i32) -> Result<(), String> {
+/// Moves the folder with all its descendants to the trash and returns the id
+/// of the trash item.
+pub async fn delete_folder(db_conn: &DbConn, folder_id: i32) -> Result<i32, String> {
     let folder = get_by_id(db_conn, folder_id).await?;
-
-    let txn = match db_conn.begin().await {
-        Ok(txn) => txn,
-        Err(err) => return Err(err.to_string()),
-    };
-
-    let result = file::Entity::delete_many()
-        .filter(file::Column::Path.starts_with(folder.path + "/"))
-        .exec(&txn)
-        .await;
-    if let Err(err) = result {
-        return Err(err.to_string());
-    }
-
-    let result = file::Entity::delete_many()
-        .filter(file::Column::Id.eq(folder_id))
-        .exec(&txn)
-        .await;
-    if let Err(err) = result {
-        return Err(err.to_string());
-    }
-
-    let result = txn.commit().await;
-    match result {
-        Ok(_) => Ok(()),
-        Err(err) => Err(err.to_string()),
-    }
+    let mut files = vec![folder];
+    files.append(&mut list_folder_children_recursively(db_conn, folder_id).await?);
+    trash_service::move_to_trash(db_conn, files).await
 }
 
 pub async fn move_file(
@@ -275,6 +247,20 @@ pub async fn list_folder_children_recursively(
     }
 }
 
+/// Matches the files and folders inside the folder, at any depth. The prefix
+/// is compared exactly, as `LIKE` ignores the case and treats `_` and `%` as
+/// wildcards.
+pub fn get_folder_descendants_condition(folder_path: &str) -> SimpleExpr {
+    let prefix = format!("{folder_path}/");
+    Expr::cust_with_values(
+        r#"substr("file"."path", 1, ?) = ?"#,
+        [
+            Value::from(prefix.chars().count() as i64),
+            Value::from(prefix),
+        ],
+    )
+}
+
 pub async fn list_folder_children(db_conn: &DbConn, id: i32) -> Result<Vec<file::Model>, String> {
     let folder = get_by_id(db_conn, id).await?;
     let folder_children = list_folder_children_recursively(db_conn, id).await?;
@@ -305,7 +291,24 @@ pub async fn get_by_id(db_conn: &DbConn, id: i32) -> Result<file::Model, String>
     }
 }
 
-async fn create_folder_recursively(
+/// Returns the file or folder with the path, if it exists.
+pub async fn get_by_path(
+    db_conn: &impl ConnectionTrait,
+    path: &str,
+    is_folder: bool,
+) -> Result<Option<file::Model>, String> {
+    let result = file::Entity::find()
+        .filter(file::Column::Path.eq(path))
+        .filter(file::Column::IsFolder.eq(is_folder))
+        .one(db_conn)
+        .await;
+    match result {
+        Ok(result) => Ok(result),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+pub async fn create_folder_recursively(
     db_conn: &impl ConnectionTrait,
     path: &str,
 ) -> Result<i32, String> {
@@ -0,0 +1,1006 @@
+//! Exports folders as directories of Markdown files and imports them back.
+//!
+//! Every cell is a fenced block named after its type, the answer of a flash
+//! card follows a `::: answer` line, true or false cells put the answer after
+//! the type, the options of a multiple choice follow `::: option` or
+//! `::: correct` lines, every accepted answer of a typed answer follows a
+//! `::: answer` line, every mask of an image occlusion follows a
+//! `::: mask` line and the items of an ordering follow `::: item` lines in
+//! their correct order:
+//!
+//! ```markdown
+//! ::: flashcard
+//! What is the capital of **France**?
+//! ::: answer
+//! Paris
+//! :::
+//!
+//! ::: flashcard reversed
+//! perro
+//! ::: answer
+//! dog
+//! :::
+//!
+//! ::: truefalse true
+//! Paris is in France.
+//! :::
+//!
+//! ::: multiplechoice
+//! Which cities are in France?
+//! ::: correct
+//! Paris
+//! ::: option
+//! Madrid
+//! ::: correct
+//! Lyon
+//! ::: explanation
+//! Madrid is in Spain.
+//! :::
+//!
+//! ::: typeanswer case,accents
+//! How do you say coffee with milk in French?
+//! ::: answer
+//! Café au lait
+//! ::: answer
+//! Café crème
+//! :::
+//!
+//! ::: imageocclusion
+//! ![](data:image/png;base64,iVBORw0KGgo=)
+//! ::: mask 1
+//! rectangle 0.1 0.2 0.3 0.1
+//! Heart
+//! ::: mask 2
+//! polygon 0.5,0.5 0.7,0.5 0.6,0.8
+//! Liver
+//! :::
+//!
+//! ::: ordering
+//! Order the planets by their distance to the sun.
+//! ::: item
+//! Mercury
+//! ::: item
+//! Venus
+//! :::
+//! ```
+//!
+//! A `reversed` flash card is also reviewed from its answer to its question.
+//! The argument of a typed answer lists what is compared as is, among `case`,
+//! `accents` and `whitespace`. The `::: mask` line has the id of the mask,
+//! which keeps its repetition. The first line of a mask is its shape, with
+//! coordinates that are fractions of the width and height of the image, and
+//! the next lines are its label.
+//!
+//! Text outside of the blocks is imported as note cells, so that plain
+//! Markdown files can be imported as well.
+
+use std::fs;
+use std::path::Path;
+
+use regex::Regex;
+use sea_orm::{ConnectionTrait, DbConn, Iterable, TransactionTrait};
+
+use crate::entity::cell::{self, CellType};
+use crate::model::search_query::parse_variant;
+use crate::model::{
+    flash_card::FlashCard,
+    image_occlusion::{ImageOcclusion, MaskShape, OcclusionMask},
+    multiple_choice::MultipleChoice,
+    ordering::Ordering,
+    true_false::TrueFalse,
+    type_answer::TypeAnswer,
+};
+use crate::util::markdown_util::{html_to_markdown, markdown_to_html};
+
+use super::{cell_service, export_import_service, file_service};
+
+const EXTENSION: &str = ".md";
+/// The lines that start a new section inside a block, like `::: answer`.
+const SECTION_NAMES: [&str; 6] = ["answer", "option", "correct", "explanation", "mask", "item"];
+
+/// Writes the file as `<name>.md` or the folder as a directory tree into the
+/// export directory.
+pub async fn export_markdown(
+    db_conn: &DbConn,
+    item_id: i32,
+    export_directory: String,
+) -> Result<(), String> {
+    let item = file_service::get_by_id(db_conn, item_id).await?;
+    let skip_prefix_length = match item.path.rfind('/') {
+        Some(index) => index + 1,
+        None => 0,
+    };
+    let mut items = vec![];
+    if item.is_folder {
+        items.append(&mut file_service::list_folder_children_recursively(db_conn, item_id).await?);
+    }
+    items.insert(0, item);
+
+    for item in items {
+        let path = Path::new(&export_directory).join(&item.path[skip_prefix_length..]);
+        if item.is_folder {
+            if let Err(err) = fs::create_dir_all(&path) {
+                return Err(err.to_string());
+            }
+            continue;
+        }
+
+        let cells = cell_service::get_file_cells_ordered_by_index(db_conn, item.id).await?;
+        let markdown = get_markdown(&cells)?;
+        if let Some(parent) = path.parent()
+            && let Err(err) = fs::create_dir_all(parent)
+        {
+            return Err(err.to_string());
+        }
+        if let Err(err) = fs::write(format!("{}{EXTENSION}", path.display()), markdown) {
+            return Err(err.to_string());
+        }
+    }
+
+    Ok(())
+}
+
+/// Imports a Markdown file or a directory, subdirectories become folders and
+/// `.md` files become files. Hidden entries like `.git` are skipped.
+pub async fn import_markdown(
+    db_conn: &DbConn,
+    import_path: String,
+    import_into_folder_id: i32,
+) -> Result<(), String> {
+    let import_path = Path::new(&import_path);
+    if !import_path.is_dir() && !is_markdown_file(import_path) {
+        return Err("Only Markdown files and directories can be imported!".into());
+    }
+
+    let import_into_folder_path = if import_into_folder_id == 0 {
+        "".into()
+    } else {
+        file_service::get_by_id(db_conn, import_into_folder_id)
+            .await?
+            .path
+    };
+
+    let txn = match db_conn.begin().await {
+        Ok(txn) => txn,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    import_entry(&txn, import_path, &import_into_folder_path).await?;
+
+    let result = txn.commit().await;
+    match result {
+        Ok(_) => Ok(()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+async fn import_entry(
+    db_conn: &impl ConnectionTrait,
+    path: &Path,
+    parent_folder_path: &str,
+) -> Result<(), String> {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    if path.is_dir() {
+        let folder_path = format!("{parent_folder_path}/{name}");
+        file_service::create_folder(db_conn, folder_path.clone()).await?;
+
+        let entries = match fs::read_dir(path) {
+            Ok(entries) => entries,
+            Err(err) => return Err(err.to_string()),
+        };
+        let mut children = vec![];
+        for entry in entries {
+            match entry {
+                Ok(entry) if !entry.file_name().to_string_lossy().starts_with('.') => {
+                    children.push(entry.path())
+                }
+                Ok(_) => {}
+                Err(err) => return Err(err.to_string()),
+            }
+        }
+        children.sort();
+
+        for child in children {
+            Box::pin(import_entry(db_conn, &child, &folder_path)).await?;
+        }
+    } else if is_markdown_file(path) {
+        let markdown = match fs::read_to_string(path) {
+            Ok(markdown) => markdown,
+            Err(err) => return Err(err.to_string()),
+        };
+        let cells = match parse_cells(&markdown) {
+            Ok(cells) => cells,
+            Err(err) => return Err(format!("{}: {err}", path.display())),
+        };
+        let file_id = file_service::create_file(
+            db_conn,
+            format!(
+                "{parent_folder_path}/{}",
+                &name[..name.len() - EXTENSION.len()]
+            ),
+        )
+        .await?;
+
+        for (i, (cell_type, content)) in cells.iter().enumerate() {
+            cell_service::create_cell_no_transaction(
+                db_conn,
+                file_id,
+                &export_import_service::purify_html(content),
+                cell_type,
+                i as i32,
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+fn is_markdown_file(path: &Path) -> bool {
+    path.is_file() && path.to_string_lossy().to_lowercase().ends_with(EXTENSION)
+}
+
+fn get_markdown(cells: &[cell::Model]) -> Result<String, String> {
+    let mut blocks = vec![];
+    for cell in cells {
+        let cell_type = format!("{:?}", cell.cell_type).to_lowercase();
+        let (argument, sections) = match cell.cell_type {
+            CellType::FlashCard => {
+                let flash_card: FlashCard = parse_content(&cell.content)?;
+                let argument = if flash_card.is_reversible {
+                    " reversed"
+                } else {
+                    ""
+                };
+                (
+                    argument.into(),
+                    vec![
+                        ("".into(), html_to_markdown(&flash_card.question)),
+                        ("answer".into(), html_to_markdown(&flash_card.answer)),
+                    ],
+                )
+            }
+            CellType::TrueFalse => {
+                let true_false: TrueFalse = parse_content(&cell.content)?;
+                (
+                    format!(" {}", true_false.is_true),
+                    vec![("".into(), html_to_markdown(&true_false.question))],
+                )
+            }
+            CellType::MultipleChoice => {
+                let multiple_choice: MultipleChoice = parse_content(&cell.content)?;
+                let argument = if multiple_choice.shuffle_options {
+                    " shuffle"
+                } else {
+                    ""
+                };
+                let mut sections = vec![("".into(), html_to_markdown(&multiple_choice.question))];
+                for (i, option) in multiple_choice.options.iter().enumerate() {
+                    let name = if multiple_choice.correct_indices.contains(&i) {
+                        "correct"
+                    } else {
+                        "option"
+                    };
+                    sections.push((name.into(), html_to_markdown(option)));
+                }
+                if let Some(explanation) = &multiple_choice.explanation {
+                    sections.push(("explanation".into(), html_to_markdown(explanation)));
+                }
+                (argument.into(), sections)
+            }
+            CellType::TypeAnswer => {
+                let type_answer: TypeAnswer = parse_content(&cell.content)?;
+                let sensitivities: Vec<&str> = [
+                    (type_answer.case_sensitive, "case"),
+                    (type_answer.accent_sensitive, "accents"),
+                    (type_answer.whitespace_sensitive, "whitespace"),
+                ]
+                .into_iter()
+                .filter_map(|(is_sensitive, name)| is_sensitive.then_some(name))
+                .collect();
+                let mut sections = vec![("".into(), html_to_markdown(&type_answer.question))];
+                sections.push(("answer".into(), type_answer.answer));
+                for answer in type_answer.alternative_answers {
+                    sections.push(("answer".into(), answer));
+                }
+                let argument = if sensitivities.is_empty() {
+                    "".into()
+                } else {
+                    format!(" {}", sensitivities.join(","))
+                };
+                (argument, sections)
+            }
+            CellType::Ordering => {
+                let ordering: Ordering = parse_content(&cell.content)?;
+                let mut sections = vec![("".into(), html_to_markdown(&ordering.question))];
+                for item in &ordering.items {
+                    sections.push(("item".into(), html_to_markdown(item)));
+                }
+                ("".into(), sections)
+            }
+            CellType::ImageOcclusion => {
+                let image_occlusion: ImageOcclusion = parse_content(&cell.content)?;
+                let mut sections = vec![("".into(), format!("![]({})", image_occlusion.image))];
+                for mask in &image_occlusion.masks {
+                    sections.push((
+                        format!("mask {}", mask.id),
+                        format!("{}\n{}", get_mask_shape_line(&mask.shape), mask.label),
+                    ));
+                }
+                ("".into(), sections)
+            }
+            CellType::Note | CellType::Cloze => (
+                "".into(),
+                vec![("".into(), html_to_markdown(&cell.content))],
+            ),
+        };
+
+        let fence = get_fence(&sections);
+        let mut block = format!("{fence} {cell_type}{argument}\n{}\n", sections[0].1);
+        for (name, section) in &sections[1..] {
+            block.push_str(&format!("{fence} {name}\n{section}\n"));
+        }
+        block.push_str(&fence);
+        blocks.push(block);
+    }
+
+    Ok(blocks.join("\n\n") + "\n")
+}
+
+fn parse_content<T: serde::de::DeserializeOwned>(content: &str) -> Result<T, String> {
+    match serde_json::from_str(content) {
+        Ok(value) => Ok(value),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Returns a fence longer than any line of colons inside the sections, so that
+/// the content cannot close the block.
+fn get_fence(sections: &[(String, String)]) -> String {
+    let longest = sections
+        .iter()
+        .flat_map(|(_, section)| section.lines())
+        .map(|line| line.chars().take_while(|c| *c == ':').count())
+        .max()
+        .unwrap_or(0);
+    ":".repeat(3.max(longest + 1))
+}
+
+/// Parses the blocks of a Markdown file into cell types and contents.
+fn parse_cells(markdown: &str) -> Result<Vec<(CellType, String)>, String> {
+    let opening_regex = Regex::new(r"^(:{3,})\s*([A-Za-z_-]+)\s*(\S*)\s*$").expect("Invalid regex");
+    let mut cells = vec![];
+    let mut lines = markdown.lines().enumerate();
+    let mut text = String::new();
+
+    while let Some((i, line)) = lines.next() {
+        let Some(captures) = opening_regex.captures(line) else {
+            text.push_str(line);
+            text.push('\n');
+            continue;
+        };
+        if !text.trim().is_empty() {
+            cells.push((CellType::Note, markdown_to_html(&text)));
+        }
+        text.clear();
+
+        let line_number = i + 1;
+        let fence = &captures[1];
+        let cell_type = match parse_variant(CellType::iter(), &captures[2], "cell type") {
+            Ok(cell_type) => cell_type,
+            Err(err) => return Err(format!("Line {line_number}: {err}")),
+        };
+        let mut sections = vec![("", String::new())];
+        let mut is_closed = false;
+        for (_, line) in lines.by_ref() {
+            let line = line.trim_end();
+            if line == fence {
+                is_closed = true;
+                break;
+            }
+            if let Some(name) = line.strip_prefix(fence).map(str::trim)
+                && is_section_name(name)
+            {
+                sections.push((name, String::new()));
+                continue;
+            }
+            let section = &mut sections.last_mut().unwrap().1;
+            section.push_str(line);
+            section.push('\n');
+        }
+        if !is_closed {
+            return Err(format!(
+                "Line {line_number}: The block is not closed by {fence}!"
+            ));
+        }
+
+        match get_content(&cell_type, &captures[3], &sections) {
+            Ok(content) => cells.push((cell_type, content)),
+            Err(err) => return Err(format!("Line {line_number}: {err}")),
+        }
+    }
+    if !text.trim().is_empty() {
+        cells.push((CellType::Note, markdown_to_html(&text)));
+    }
+
+    Ok(cells)
+}
+
+/// Whether the line after the fence starts a section, only a mask has an
+/// argument, its id.
+fn is_section_name(name: &str) -> bool {
+    let (name, argument) = name.split_once(char::is_whitespace).unwrap_or((name, ""));
+    SECTION_NAMES.contains(&name) && (argument.is_empty() || name == "mask")
+}
+
+fn get_content(
+    cell_type: &CellType,
+    argument: &str,
+    sections: &[(&str, String)],
+) -> Result<String, String> {
+    let expected_names: &[&str] = match cell_type {
+        CellType::FlashCard => &["", "answer"],
+        // The sections of the other types are checked while reading them.
+        CellType::MultipleChoice
+        | CellType::TypeAnswer
+        | CellType::ImageOcclusion
+        | CellType::Ordering => &[],
+        CellType::TrueFalse | CellType::Note | CellType::Cloze => &[""],
+    };
+    let names: Vec<&str> = sections.iter().map(|(name, _)| *name).collect();
+    if !expected_names.is_empty() && names != expected_names {
+        return Err(format!(
+            "A {cell_type:?} block must have {} answer line!",
+            if expected_names.len() == 2 {
+                "one"
+            } else {
+                "no"
+            }
+        ));
+    }
+
+    let content = match cell_type {
+        CellType::FlashCard => {
+            let is_reversible = match argument.to_lowercase().as_str() {
+                "reversed" => true,
+                "" => false,
+                _ => {
+                    return Err(format!(
+                        "Invalid argument \"{argument}\", expected reversed or nothing!"
+                    ));
+                }
+            };
+            serde_json::to_string(&FlashCard {
+                question: markdown_to_html(&sections[0].1),
+                answer: markdown_to_html(&sections[1].1),
+                is_reversible,
+            })
+            .unwrap()
+        }
+        CellType::TrueFalse => {
+            let is_true = match argument.to_lowercase().as_str() {
+                "true" => true,
+                "false" => false,
+                _ => {
+                    return Err(format!(
+                        "Invalid answer \"{argument}\", expected true or false!"
+                    ));
+                }
+            };
+            serde_json::to_string(&TrueFalse {
+                question: markdown_to_html(&sections[0].1),
+                is_true,
+            })
+            .unwrap()
+        }
+        CellType::Note => markdown_to_html(&sections[0].1),
+        CellType::Cloze => {
+            let content = markdown_to_html(&sections[0].1);
+            if !content.contains("<cloze") {
+                return Err("The cloze has no clozes, mark them like {{c1::text}}!".into());
+            }
+            content
+        }
+        CellType::MultipleChoice => get_multiple_choice_content(argument, sections)?,
+        CellType::TypeAnswer => get_type_answer_content(argument, sections)?,
+        CellType::ImageOcclusion => get_image_occlusion_content(sections)?,
+        CellType::Ordering => get_ordering_content(sections)?,
+    };
+
+    Ok(content)
+}
+
+fn get_multiple_choice_content(
+    argument: &str,
+    sections: &[(&str, String)],
+) -> Result<String, String> {
+    let shuffle_options = match argument.to_lowercase().as_str() {
+        "shuffle" => true,
+        "" => false,
+        _ => {
+            return Err(format!(
+                "Invalid argument \"{argument}\", expected shuffle or nothing!"
+            ));
+        }
+    };
+    let mut multiple_choice = MultipleChoice {
+        question: markdown_to_html(&sections[0].1),
+        shuffle_options,
+        ..Default::default()
+    };
+    for (name, section) in &sections[1..] {
+        if multiple_choice.explanation.is_some() {
+            return Err("The explanation must be the last section!".into());
+        }
+        match *name {
+            "correct" => {
+                multiple_choice
+                    .correct_indices
+                    .push(multiple_choice.options.len());
+                multiple_choice.options.push(markdown_to_html(section));
+            }
+            "option" => multiple_choice.options.push(markdown_to_html(section)),
+            "explanation" => multiple_choice.explanation = Some(markdown_to_html(section)),
+            _ => {
+                return Err(format!(
+                    "A MultipleChoice block cannot have an {name} line!"
+                ));
+            }
+        }
+    }
+    multiple_choice.validate()?;
+
+    Ok(serde_json::to_string(&multiple_choice).unwrap())
+}
+
+fn get_type_answer_content(argument: &str, sections: &[(&str, String)]) -> Result<String, String> {
+    let mut type_answer = TypeAnswer {
+        question: markdown_to_html(&sections[0].1),
+        ..Default::default()
+    };
+    for sensitivity in argument.split(',').filter(|name| !name.is_empty()) {
+        match sensitivity.to_lowercase().as_str() {
+            "case" => type_answer.case_sensitive = true,
+            "accents" => type_answer.accent_sensitive = true,
+            "whitespace" => type_answer.whitespace_sensitive = true,
+            _ => {
+                return Err(format!(
+                    "Invalid argument \"{sensitivity}\", expected case, accents or whitespace!"
+                ));
+            }
+        }
+    }
+    let mut answers = vec![];
+    for (name, section) in &sections[1..] {
+        if *name != "answer" {
+            return Err(format!("A TypeAnswer block cannot have an {name} line!"));
+        }
+        answers.push(section.trim().to_string());
+    }
+    if answers.is_empty() {
+        return Err("A TypeAnswer block must have at least one answer line!".into());
+    }
+    type_answer.answer = answers.remove(0);
+    type_answer.alternative_answers = answers;
+    type_answer.validate()?;
+
+    Ok(serde_json::to_string(&type_answer).unwrap())
+}
+
+fn get_image_occlusion_content(sections: &[(&str, String)]) -> Result<String, String> {
+    let image_regex = Regex::new(r"^!\[[^\]]*\]\((\S+)\)$").expect("Invalid regex");
+    let image = match image_regex.captures(sections[0].1.trim()) {
+        Some(captures) => captures[1].to_string(),
+        None => {
+            return Err(
+                "An ImageOcclusion block must start with an image like ![](image.png)!".into(),
+            );
+        }
+    };
+    let mut image_occlusion = ImageOcclusion {
+        image,
+        masks: vec![],
+    };
+    for (name, section) in &sections[1..] {
+        let Some(argument) = name.strip_prefix("mask") else {
+            return Err(format!(
+                "An ImageOcclusion block cannot have an {name} line!"
+            ));
+        };
+        // The masks without an id are numbered after the previous ones.
+        let id = match argument.trim() {
+            "" => {
+                image_occlusion
+                    .masks
+                    .iter()
+                    .map(|mask| mask.id)
+                    .max()
+                    .unwrap_or(0)
+                    + 1
+            }
+            argument => match argument.parse::<u32>() {
+                Ok(id) => id,
+                Err(_) => return Err(format!("Invalid mask id \"{argument}\"!")),
+            },
+        };
+        let (shape_line, label) = section
+            .trim()
+            .split_once('\n')
+            .unwrap_or((section.trim(), ""));
+        image_occlusion.masks.push(OcclusionMask {
+            id,
+            label: label.trim().to_string(),
+            shape: parse_mask_shape(shape_line)?,
+        });
+    }
+    image_occlusion.validate()?;
+
+    Ok(serde_json::to_string(&image_occlusion).unwrap())
+}
+
+fn get_ordering_content(sections: &[(&str, String)]) -> Result<String, String> {
+    let mut ordering = Ordering {
+        question: markdown_to_html(&sections[0].1),
+        items: vec![],
+    };
+    for (name, section) in &sections[1..] {
+        if *name != "item" {
+            return Err(format!("An Ordering block cannot have an {name} line!"));
+        }
+        ordering.items.push(markdown_to_html(section));
+    }
+    ordering.validate()?;
+
+    Ok(serde_json::to_string(&ordering).unwrap())
+}
+
+fn get_mask_shape_line(shape: &MaskShape) -> String {
+    match shape {
+        MaskShape::Rectangle {
+            x,
+            y,
+            width,
+            height,
+        } => format!("rectangle {x} {y} {width} {height}"),
+        MaskShape::Polygon { points } => {
+            let points: Vec<String> = points.iter().map(|(x, y)| format!("{x},{y}")).collect();
+            format!("polygon {}", points.join(" "))
+        }
+    }
+}
+
+/// Parses `rectangle x y width height` or `polygon x,y x,y x,y`.
+fn parse_mask_shape(line: &str) -> Result<MaskShape, String> {
+    let error = format!(
+        "Invalid mask \"{line}\", expected rectangle x y width height or polygon x,y x,y x,y!"
+    );
+    let mut words = line.split_whitespace();
+    let kind = words.next().unwrap_or_default().to_lowercase();
+    let mut numbers = vec![];
+    for word in words {
+        let coordinates: Vec<&str> = if kind == "polygon" {
+            match word.split_once(',') {
+                Some((x, y)) => vec![x, y],
+                None => return Err(error),
+            }
+        } else {
+            vec![word]
+        };
+        for coordinate in coordinates {
+            match coordinate.parse::<f64>() {
+                Ok(number) => numbers.push(number),
+                Err(_) => return Err(error),
+            }
+        }
+    }
+
+    match (kind.as_str(), numbers.as_slice()) {
+        ("rectangle", [x, y, width, height]) => Ok(MaskShape::Rectangle {
+            x: *x,
+            y: *y,
+            width: *width,
+            height: *height,
+        }),
+        ("polygon", numbers) => Ok(MaskShape::Polygon {
+            points: numbers
+                .chunks(2)
+                .map(|point| (point[0], point[1]))
+                .collect(),
+        }),
+        _ => Err(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::tests::{
+        create_file_cell_with_cell_type_and_content, get_db, get_random_file_path,
+    };
+
+    #[tokio::test]
+    async fn export_markdown_folder_tree_imported_same_cells() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let flash_card = serde_json::to_string(&FlashCard {
+            question: "<p>What is the capital of <strong>France</strong>?</p>".into(),
+            answer: "<p>Paris</p>".into(),
+            is_reversible: true,
+        })
+        .unwrap();
+        let true_false = serde_json::to_string(&TrueFalse {
+            question: "<p>Paris is in <em>Spain</em>.</p>".into(),
+            is_true: false,
+        })
+        .unwrap();
+        let multiple_choice = serde_json::to_string(&MultipleChoice {
+            question: "<p>Which cities are in France?</p>".into(),
+            options: vec![
+                "<p>Paris</p>".into(),
+                "<p>Madrid</p>".into(),
+                "<p>Lyon</p>".into(),
+            ],
+            correct_indices: vec![0, 2],
+            explanation: Some("<p>Madrid is in Spain.</p>".into()),
+            shuffle_options: true,
+        })
+        .unwrap();
+        let type_answer = serde_json::to_string(&TypeAnswer {
+            question: "<p>What is the capital of <em>Italy</em>?</p>".into(),
+            answer: "Rome".into(),
+            alternative_answers: vec!["Roma".into()],
+            case_sensitive: true,
+            whitespace_sensitive: true,
+            ..Default::default()
+        })
+        .unwrap();
+        let image_occlusion = serde_json::to_string(&ImageOcclusion {
+            image: "data:image/png;base64,iVBORw0KGgo=".into(),
+            masks: vec![
+                OcclusionMask {
+                    id: 4,
+                    label: "France".into(),
+                    shape: MaskShape::Rectangle {
+                        x: 0.25,
+                        y: 0.5,
+                        width: 0.125,
+                        height: 0.1,
+                    },
+                },
+                OcclusionMask {
+                    id: 7,
+                    label: "".into(),
+                    shape: MaskShape::Polygon {
+                        points: vec![(0.5, 0.5), (0.75, 0.5), (0.6, 0.8)],
+                    },
+                },
+            ],
+        })
+        .unwrap();
+        let ordering = serde_json::to_string(&Ordering {
+            question: "<p>Order the countries from north to south</p>".into(),
+            items: vec![
+                "<p>Norway</p>".into(),
+                "<p>Germany</p>".into(),
+                "<p>Italy</p>".into(),
+            ],
+        })
+        .unwrap();
+        let (file_id, _) = create_file_cell_with_cell_type_and_content(
+            &db_conn,
+            "geography/europe/capitals",
+            CellType::FlashCard,
+            &flash_card,
+        )
+        .await;
+        for (i, (cell_type, content)) in [
+            (CellType::TrueFalse, true_false.as_str()),
+            (CellType::MultipleChoice, multiple_choice.as_str()),
+            (CellType::TypeAnswer, type_answer.as_str()),
+            (CellType::ImageOcclusion, image_occlusion.as_str()),
+            (CellType::Ordering, ordering.as_str()),
+            (
+                CellType::Cloze,
+                "<p><cloze index=\"1\">Madrid</cloze> is the capital of Spain.</p>",
+            ),
+            (
+                CellType::Note,
+                "<h2>Notes</h2><ul><li>one</li><li>two</li></ul><pre><code>::: answer\n</code></pre>",
+            ),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            cell_service::create_cell(&db_conn, file_id, content, cell_type, i as i32 + 1)
+                .await
+                .unwrap();
+        }
+        file_service::create_folder(&db_conn, "geography/empty".into())
+            .await
+            .unwrap();
+        let folder = file_service::get_files(&db_conn)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|file| file.path == "geography")
+            .unwrap();
+        let export_directory = get_random_file_path("markdown");
+        let import_folder_id = file_service::create_folder(&db_conn, "imported".into())
+            .await
+            .unwrap();
+
+        // Act
+
+        export_markdown(
+            &db_conn,
+            folder.id,
+            export_directory.to_str().unwrap().into(),
+        )
+        .await
+        .unwrap();
+        import_markdown(
+            &db_conn,
+            export_directory.join("geography").to_str().unwrap().into(),
+            import_folder_id,
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        assert!(export_directory.join("geography/empty").is_dir());
+        let files = file_service::list_folder_children_recursively(&db_conn, import_folder_id)
+            .await
+            .unwrap();
+        let mut paths: Vec<&str> = files.iter().map(|file| file.path.as_str()).collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![
+                "imported/geography",
+                "imported/geography/empty",
+                "imported/geography/europe",
+                "imported/geography/europe/capitals",
+            ]
+        );
+        let imported_file = files
+            .iter()
+            .find(|file| file.path == "imported/geography/europe/capitals")
+            .unwrap();
+        let expected: Vec<(CellType, String)> =
+            cell_service::get_file_cells_ordered_by_index(&db_conn, file_id)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|cell| (cell.cell_type, cell.content))
+                .collect();
+        let actual: Vec<(CellType, String)> =
+            cell_service::get_file_cells_ordered_by_index(&db_conn, imported_file.id)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|cell| (cell.cell_type, cell.content))
+                .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn import_markdown_plain_text_around_blocks_imported_as_notes() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let import_directory = get_random_file_path("markdown");
+        fs::create_dir_all(&import_directory).unwrap();
+        let import_path = import_directory.join("vocabulary.md");
+        fs::write(
+            &import_path,
+            "# Vocabulary\n\n::: flashcard\nperro\n::: answer\ndog\n:::\n\nMore *notes*.\n",
+        )
+        .unwrap();
+
+        // Act
+
+        import_markdown(&db_conn, import_path.to_str().unwrap().into(), 0)
+            .await
+            .unwrap();
+
+        // Assert
+
+        let file = file_service::get_files(&db_conn)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|file| file.path == "vocabulary")
+            .unwrap();
+        let cells: Vec<(CellType, String)> =
+            cell_service::get_file_cells_ordered_by_index(&db_conn, file.id)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|cell| (cell.cell_type, cell.content))
+                .collect();
+        assert_eq!(
+            cells,
+            vec![
+                (CellType::Note, "<h1>Vocabulary</h1>".into()),
+                (
+                    CellType::FlashCard,
+                    r#"{"question":"<p>perro</p>","answer":"<p>dog</p>","isReversible":false}"#
+                        .into()
+                ),
+                (CellType::Note, "<p>More <em>notes</em>.</p>".into()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn import_markdown_masks_without_id_numbered_after_previous_ones() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let import_directory = get_random_file_path("markdown");
+        fs::create_dir_all(&import_directory).unwrap();
+        let import_path = import_directory.join("anatomy.md");
+        fs::write(
+            &import_path,
+            "::: imageocclusion\n![](body.png)\n::: mask 5\nrectangle 0.1 0.1 0.2 0.2\nHeart\n::: mask\nrectangle 0.5 0.5 0.2 0.2\nLiver\n:::\n",
+        )
+        .unwrap();
+
+        // Act
+
+        import_markdown(&db_conn, import_path.to_str().unwrap().into(), 0)
+            .await
+            .unwrap();
+
+        // Assert
+
+        let file = file_service::get_files(&db_conn)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|file| file.path == "anatomy")
+            .unwrap();
+        let cells = cell_service::get_file_cells_ordered_by_index(&db_conn, file.id)
+            .await
+            .unwrap();
+        let image_occlusion: ImageOcclusion = serde_json::from_str(&cells[0].content).unwrap();
+        let actual: Vec<(u32, &str)> = image_occlusion
+            .masks
+            .iter()
+            .map(|mask| (mask.id, mask.label.as_str()))
+            .collect();
+        assert_eq!(actual, vec![(5, "Heart"), (6, "Liver")]);
+    }
+
+    #[tokio::test]
+    async fn import_markdown_unclosed_block_returned_error_with_line() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let import_directory = get_random_file_path("markdown");
+        fs::create_dir_all(&import_directory).unwrap();
+        let import_path = import_directory.join("broken.md");
+        fs::write(&import_path, "Intro\n\n::: cloze\n{{c1::Paris}}\n").unwrap();
+
+        // Act
+
+        let actual = import_markdown(&db_conn, import_path.to_str().unwrap().into(), 0).await;
+
+        // Assert
+
+        assert_eq!(
+            actual,
+            Err(format!(
+                "{}: Line 3: The block is not closed by :::!",
+                import_path.display()
+            ))
+        );
+        assert!(file_service::get_files(&db_conn).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,327 @@
+//! The media store keeps images, audio and other files referenced by cells in
+//! a directory next to the database. Files are named after the SHA-256 of
+//! their content, so every file is stored once.
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use rand::Rng;
+use regex::Regex;
+use sea_orm::{DbConn, EntityTrait, QuerySelect};
+use sha2::{Digest, Sha256};
+
+use crate::dto::media_file::MediaFile;
+use crate::entity::{cell, trash};
+use crate::util::media_util::{
+    get_media_reference, get_media_type, get_requested_media_name, is_valid_media_name,
+};
+
+/// Copies the file into the media store.
+pub fn add_media_from_path(media_directory: &Path, path: &Path) -> Result<MediaFile, String> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) => return Err(err.to_string()),
+    };
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_string());
+    let name = store_media(media_directory, file, extension.as_deref())?;
+    get_media_file(media_directory, &name)
+}
+
+/// Writes the bytes into the media store, the extension is used for the name.
+pub fn add_media_from_bytes(
+    media_directory: &Path,
+    bytes: &[u8],
+    extension: &str,
+) -> Result<MediaFile, String> {
+    let extension = extension.trim_start_matches('.');
+    let extension = if extension.is_empty() {
+        None
+    } else {
+        Some(extension)
+    };
+    let name = store_media(media_directory, bytes, extension)?;
+    get_media_file(media_directory, &name)
+}
+
+/// Streams the content into the media store and returns its name. Nothing is
+/// written when the store has the same content already.
+pub fn store_media(
+    media_directory: &Path,
+    mut reader: impl Read,
+    extension: Option<&str>,
+) -> Result<String, String> {
+    if let Err(err) = fs::create_dir_all(media_directory) {
+        return Err(err.to_string());
+    }
+    // Hidden files are ignored when listing the store.
+    let partial_path = media_directory.join(format!(".{}.part", rand::rng().random::<u64>()));
+    let mut partial_file = match File::create(&partial_path) {
+        Ok(file) => file,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read_count = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read_count) => read_count,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => {
+                let _ = fs::remove_file(&partial_path);
+                return Err(err.to_string());
+            }
+        };
+        hasher.update(&buffer[..read_count]);
+        if let Err(err) = partial_file.write_all(&buffer[..read_count]) {
+            let _ = fs::remove_file(&partial_path);
+            return Err(err.to_string());
+        }
+    }
+    drop(partial_file);
+
+    let name = get_name(&format!("{:x}", hasher.finalize()), extension);
+    let media_path = media_directory.join(&name);
+    let result = if media_path.exists() {
+        fs::remove_file(&partial_path)
+    } else {
+        fs::rename(&partial_path, &media_path)
+    };
+    match result {
+        Ok(_) => Ok(name),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Returns the name the file has in the media store, without copying it.
+pub fn get_media_name(path: &Path) -> Result<String, String> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) => return Err(err.to_string()),
+    };
+    let mut hasher = Sha256::new();
+    if let Err(err) = io::copy(&mut file, &mut hasher) {
+        return Err(err.to_string());
+    }
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_string());
+    Ok(get_name(
+        &format!("{:x}", hasher.finalize()),
+        extension.as_deref(),
+    ))
+}
+
+/// Reads the file of the media store requested by the webview, returns its
+/// content and MIME type.
+pub fn read_media(media_directory: &Path, url: &str) -> Result<(Vec<u8>, &'static str), String> {
+    let name = match get_requested_media_name(url) {
+        Some(name) => name,
+        None => return Err(format!("Invalid media URL \"{url}\"!")),
+    };
+    match fs::read(media_directory.join(name)) {
+        Ok(bytes) => Ok((bytes, get_media_type(name))),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Lists the media files which are neither referenced by a cell nor by an
+/// item in the trash.
+pub async fn list_unused_media(
+    db_conn: &DbConn,
+    media_directory: &Path,
+) -> Result<Vec<MediaFile>, String> {
+    if !media_directory.is_dir() {
+        return Ok(vec![]);
+    }
+    let referenced_hashes = get_referenced_hashes(db_conn).await?;
+
+    let entries = match fs::read_dir(media_directory) {
+        Ok(entries) => entries,
+        Err(err) => return Err(err.to_string()),
+    };
+    let mut unused_media = vec![];
+    for entry in entries {
+        let name = match entry {
+            Ok(entry) => entry.file_name().to_string_lossy().to_string(),
+            Err(err) => return Err(err.to_string()),
+        };
+        if !is_valid_media_name(&name) {
+            continue;
+        }
+        let hash = name.split('.').next().unwrap_or_default();
+        if !referenced_hashes.contains(hash) {
+            unused_media.push(get_media_file(media_directory, &name)?);
+        }
+    }
+    unused_media.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(unused_media)
+}
+
+/// Deletes the unused media files and returns how many were deleted.
+pub async fn delete_unused_media(db_conn: &DbConn, media_directory: &Path) -> Result<u32, String> {
+    let unused_media = list_unused_media(db_conn, media_directory).await?;
+    for media_file in &unused_media {
+        if let Err(err) = fs::remove_file(media_directory.join(&media_file.name)) {
+            return Err(err.to_string());
+        }
+    }
+    Ok(unused_media.len() as u32)
+}
+
+fn get_name(hash: &str, extension: Option<&str>) -> String {
+    match extension {
+        Some(extension) if extension.chars().all(|c| c.is_ascii_alphanumeric()) => {
+            format!("{hash}.{}", extension.to_lowercase())
+        }
+        _ => hash.to_string(),
+    }
+}
+
+fn get_media_file(media_directory: &Path, name: &str) -> Result<MediaFile, String> {
+    let path = media_directory.join(name);
+    match fs::metadata(&path) {
+        Ok(metadata) => Ok(MediaFile {
+            name: name.to_string(),
+            url: get_media_reference(name),
+            size: metadata.len(),
+        }),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Returns the hashes in the contents of the cells and the trash. Looking for
+/// the hash alone finds the references however the URL is encoded.
+async fn get_referenced_hashes(db_conn: &DbConn) -> Result<HashSet<String>, String> {
+    let result = cell::Entity::find()
+        .select_only()
+        .column(cell::Column::Content)
+        .into_tuple::<String>()
+        .all(db_conn)
+        .await;
+    let mut contents = match result {
+        Ok(contents) => contents,
+        Err(err) => return Err(err.to_string()),
+    };
+    let result = trash::Entity::find()
+        .select_only()
+        .column(trash::Column::Items)
+        .into_tuple::<String>()
+        .all(db_conn)
+        .await;
+    match result {
+        Ok(mut items) => contents.append(&mut items),
+        Err(err) => return Err(err.to_string()),
+    }
+
+    let hash_regex = Regex::new("[0-9a-f]{64}").expect("Invalid regex");
+    Ok(contents
+        .iter()
+        .flat_map(|content| hash_regex.find_iter(content))
+        .map(|hash| hash.as_str().to_string())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        entity::cell::CellType,
+        service::{
+            cell_service, file_service,
+            tests::{create_file, get_db, get_random_file_path},
+        },
+    };
+
+    #[test]
+    fn add_media_from_bytes_same_content_stored_once() {
+        // Arrange
+
+        let media_directory = get_random_file_path("media");
+
+        // Act
+
+        let first = add_media_from_bytes(&media_directory, b"image", ".PNG").unwrap();
+        let second = add_media_from_bytes(&media_directory, b"image", "png").unwrap();
+
+        // Assert
+
+        assert_eq!(first, second);
+        assert_eq!(first.name, format!("{:x}.png", Sha256::digest(b"image")));
+        assert_eq!(first.size, 5);
+        assert_eq!(fs::read_dir(&media_directory).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn read_media_resolved_url_returned_file_and_rejected_other_paths() {
+        // Arrange
+
+        let media_directory = get_random_file_path("media");
+        let media_file = add_media_from_bytes(&media_directory, b"image", "png").unwrap();
+
+        // Act
+
+        let actual = read_media(
+            &media_directory,
+            &format!("http://brainy-media.localhost/{}", media_file.name),
+        );
+        let outside = read_media(&media_directory, "brainy-media://localhost/..%2Fsecret");
+
+        // Assert
+
+        assert_eq!(actual, Ok((b"image".to_vec(), "image/png")));
+        assert!(outside.is_err());
+    }
+
+    #[tokio::test]
+    async fn delete_unused_media_deleted_files_not_referenced_by_cells_or_trash() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let media_directory = get_random_file_path("media");
+        let used = add_media_from_bytes(&media_directory, b"used", "png").unwrap();
+        let trashed = add_media_from_bytes(&media_directory, b"trashed", "mp3").unwrap();
+        let unused = add_media_from_bytes(&media_directory, b"unused", "png").unwrap();
+        let file_id = create_file(&db_conn, "file").await;
+        cell_service::create_cell(
+            &db_conn,
+            file_id,
+            &format!("<img src=\"{}\">", used.url),
+            CellType::Note,
+            0,
+        )
+        .await
+        .unwrap();
+        let trashed_file_id = create_file(&db_conn, "trashed").await;
+        cell_service::create_cell(
+            &db_conn,
+            trashed_file_id,
+            &format!("<audio src=\"{}\"></audio>", trashed.url),
+            CellType::Note,
+            0,
+        )
+        .await
+        .unwrap();
+        file_service::delete_file(&db_conn, trashed_file_id)
+            .await
+            .unwrap();
+
+        // Act
+
+        let actual = delete_unused_media(&db_conn, &media_directory)
+            .await
+            .unwrap();
+
+        // Assert
+
+        assert_eq!(actual, 1);
+        assert!(media_directory.join(&used.name).exists());
+        assert!(media_directory.join(&trashed.name).exists());
+        assert!(!media_directory.join(&unused.name).exists());
+    }
+}
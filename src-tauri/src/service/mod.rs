@@ -1,12 +1,25 @@
+pub mod anki_service;
+pub mod answer_service;
 pub mod cell_service;
+pub mod csv_service;
+pub mod duplicate_service;
 pub mod export_import_service;
 pub mod file_service;
+pub mod markdown_service;
+pub mod media_service;
 pub mod repetition_service;
+pub mod scheduler_service;
 pub mod search_service;
 pub mod settings_service;
+pub mod tag_service;
+pub mod trash_service;
+pub mod undo_service;
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
+    use std::path::PathBuf;
+
+    use rand::Rng;
     use sea_orm::{Database, DatabaseConnection, DbConn};
 
     use crate::entity::cell::CellType;
@@ -19,6 +32,17 @@ mod tests {
         connection
     }
 
+    /// Returns a path that does not exist yet inside the `temp` directory.
+    pub fn get_random_file_path(extension: &str) -> PathBuf {
+        let temp_dir = std::env::current_dir().unwrap().join("temp");
+
+        if !temp_dir.exists() {
+            std::fs::create_dir(&temp_dir).unwrap();
+        }
+
+        temp_dir.join(format!("{}.{extension}", rand::rng().random::<u64>()))
+    }
+
     pub async fn create_file(db_conn: &DbConn, path: &str) -> i32 {
         file_service::create_file(db_conn, path.to_string())
             .await
@@ -6,13 +6,18 @@ use rand_chacha::ChaCha8Rng;
 use regex::Regex;
 use sea_orm::{DbConn, Set};
 
+use crate::dto::review_preview::ReviewPreview;
 use crate::entity::cell::CellType;
 use crate::entity::repetition::{self, State};
+use crate::entity::review_log;
 use crate::model::file_repetitions_count::FileRepetitionCounts;
+use crate::model::flash_card::FlashCard;
+use crate::model::image_occlusion::ImageOcclusion;
+use crate::model::rating::Rating;
 
 use sea_orm::{entity::*, query::*};
 
-use super::cell_service;
+use super::{cell_service, scheduler_service};
 
 const SEED: [u8; 32] = [42u8; 32];
 
@@ -29,7 +34,20 @@ pub async fn update_repetitions_for_cell(
 
     match cell_type {
         CellType::Note => (),
-        CellType::FlashCard | CellType::TrueFalse => {
+        CellType::FlashCard => {
+            update_repetitions_for_flash_card_cell(
+                content,
+                file_id,
+                cell_id,
+                &cell_repetitions,
+                &mut repetitions_to_insert,
+                &mut repetitions_to_remove,
+            );
+        }
+        CellType::TrueFalse
+        | CellType::MultipleChoice
+        | CellType::TypeAnswer
+        | CellType::Ordering => {
             if cell_repetitions.is_empty() {
                 repetitions_to_insert.push(repetition::ActiveModel {
                     file_id: Set(file_id),
@@ -48,6 +66,16 @@ pub async fn update_repetitions_for_cell(
                 &mut repetitions_to_remove,
             );
         }
+        CellType::ImageOcclusion => {
+            update_repetitions_for_image_occlusion_cell(
+                content,
+                file_id,
+                cell_id,
+                &cell_repetitions,
+                &mut repetitions_to_insert,
+                &mut repetitions_to_remove,
+            );
+        }
     }
 
     for active_model in repetitions_to_insert {
@@ -120,6 +148,96 @@ fn update_repetitions_for_cloze_cell(
     }
 }
 
+/// Keeps one repetition per direction of the flash card, the repetitions
+/// without additional content ask for the front like those of non reversible
+/// flash cards.
+fn update_repetitions_for_flash_card_cell(
+    content: &str,
+    file_id: i32,
+    cell_id: i32,
+    current_cell_repetitions: &[repetition::Model],
+    repetitions_to_insert: &mut Vec<repetition::ActiveModel>,
+    repetitions_to_remove: &mut Vec<i32>,
+) {
+    let is_reversible = match serde_json::from_str::<FlashCard>(content) {
+        Ok(flash_card) => flash_card.is_reversible,
+        Err(_) => false,
+    };
+    let is_back = |repetition: &repetition::Model| {
+        repetition.additional_content.as_deref() == Some(FlashCard::BACK)
+    };
+
+    let mut has_front = false;
+    let mut has_back = false;
+    for repetition in current_cell_repetitions {
+        if !is_back(repetition) && !has_front {
+            has_front = true;
+        } else if is_back(repetition) && is_reversible && !has_back {
+            has_back = true;
+        } else {
+            repetitions_to_remove.push(repetition.id);
+        }
+    }
+
+    // The front repetition has no additional content whether the card is
+    // reversible or not, so it is kept when that changes.
+    let mut missing_directions = vec![];
+    if !has_front {
+        missing_directions.push(None);
+    }
+    if is_reversible && !has_back {
+        missing_directions.push(Some(FlashCard::BACK.to_string()));
+    }
+    for additional_content in missing_directions {
+        repetitions_to_insert.push(repetition::ActiveModel {
+            file_id: Set(file_id),
+            cell_id: Set(cell_id),
+            additional_content: Set(additional_content),
+            ..Default::default()
+        });
+    }
+}
+
+/// Keeps one repetition per mask, the id of the mask is the additional
+/// content like the index of a cloze.
+fn update_repetitions_for_image_occlusion_cell(
+    content: &str,
+    file_id: i32,
+    cell_id: i32,
+    current_cell_repetitions: &[repetition::Model],
+    repetitions_to_insert: &mut Vec<repetition::ActiveModel>,
+    repetitions_to_remove: &mut Vec<i32>,
+) {
+    let mask_ids: Vec<String> = match serde_json::from_str::<ImageOcclusion>(content) {
+        Ok(image_occlusion) => image_occlusion
+            .masks
+            .iter()
+            .map(|mask| mask.id.to_string())
+            .collect(),
+        Err(_) => vec![],
+    };
+
+    // Only the first repetition of a mask is kept.
+    let mut kept_mask_ids: HashSet<&str> = HashSet::new();
+    for repetition in current_cell_repetitions {
+        let mask_id = repetition.additional_content.as_deref().unwrap_or_default();
+        if !mask_ids.iter().any(|id| id == mask_id) || !kept_mask_ids.insert(mask_id) {
+            repetitions_to_remove.push(repetition.id);
+        }
+    }
+
+    for mask_id in &mask_ids {
+        if kept_mask_ids.insert(mask_id) {
+            repetitions_to_insert.push(repetition::ActiveModel {
+                file_id: Set(file_id),
+                cell_id: Set(cell_id),
+                additional_content: Set(Some(mask_id.clone())),
+                ..Default::default()
+            });
+        }
+    }
+}
+
 pub async fn get_study_repetition_counts(
     db_conn: &DbConn,
     file_id: i32,
@@ -175,8 +293,92 @@ pub async fn get_file_repetitions(
     }
 }
 
-pub async fn update_repetition(
+/// Returns when the repetition would be due after each rating, so that the
+/// reviewer can show the intervals without scheduling in the frontend.
+pub async fn preview_repetition(
     db_conn: &DbConn,
+    repetition_id: i32,
+) -> Result<ReviewPreview, String> {
+    let repetition = get_repetition_by_id(db_conn, repetition_id).await?;
+    let scheduler = scheduler_service::get_scheduler_for_file(db_conn, repetition.file_id).await?;
+    let now = Utc::now();
+    let due = |rating| scheduler.review(&repetition, rating, now).due;
+    Ok(ReviewPreview {
+        again: due(Rating::Again),
+        hard: due(Rating::Hard),
+        good: due(Rating::Good),
+        easy: due(Rating::Easy),
+    })
+}
+
+/// Schedules the repetition based on the given rating and saves it, the
+/// scheduling is done here so that the frontend cannot corrupt it.
+pub async fn review_repetition(
+    db_conn: &DbConn,
+    repetition_id: i32,
+    rating: Rating,
+    review_duration: Option<i64>,
+) -> Result<repetition::Model, String> {
+    let txn = match db_conn.begin().await {
+        Ok(txn) => txn,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let repetition = get_repetition_by_id(&txn, repetition_id).await?;
+    let scheduler = scheduler_service::get_scheduler_for_file(&txn, repetition.file_id).await?;
+    let reviewed_repetition = scheduler.review(&repetition, rating, Utc::now());
+    save_review(
+        &txn,
+        &repetition,
+        reviewed_repetition.clone(),
+        rating,
+        review_duration,
+    )
+    .await?;
+
+    let result = txn.commit().await;
+    match result {
+        Ok(_) => Ok(reviewed_repetition),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+async fn save_review(
+    db_conn: &impl ConnectionTrait,
+    previous_repetition: &repetition::Model,
+    repetition: repetition::Model,
+    rating: Rating,
+    review_duration: Option<i64>,
+) -> Result<(), String> {
+    let review_log = review_log::ActiveModel {
+        repetition_id: Set(repetition.id),
+        cell_id: Set(repetition.cell_id),
+        file_id: Set(repetition.file_id),
+        rating: Set(rating),
+        previous_state: Set(previous_repetition.state.clone()),
+        new_state: Set(repetition.state.clone()),
+        previous_stability: Set(previous_repetition.stability),
+        new_stability: Set(repetition.stability),
+        previous_difficulty: Set(previous_repetition.difficulty),
+        new_difficulty: Set(repetition.difficulty),
+        elapsed_days: Set(repetition.elapsed_days),
+        scheduled_days: Set(repetition.scheduled_days),
+        review_duration: Set(review_duration),
+        reviewed_at: Set(repetition.last_review),
+        ..Default::default()
+    };
+
+    save_repetition(db_conn, repetition).await?;
+
+    let result = review_log::Entity::insert(review_log).exec(db_conn).await;
+    match result {
+        Ok(_) => Ok(()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+pub async fn save_repetition(
+    db_conn: &impl ConnectionTrait,
     repetition: repetition::Model,
 ) -> Result<(), String> {
     let active_entity = repetition::ActiveModel {
@@ -201,6 +403,20 @@ pub async fn update_repetition(
     }
 }
 
+pub async fn get_repetition_by_id(
+    db_conn: &impl ConnectionTrait,
+    repetition_id: i32,
+) -> Result<repetition::Model, String> {
+    let result = repetition::Entity::find_by_id(repetition_id)
+        .one(db_conn)
+        .await;
+    match result {
+        Ok(Some(repetition)) => Ok(repetition),
+        Ok(None) => Err("Repetition not found!".into()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
 pub async fn get_repetitions_for_files(
     db_conn: &DbConn,
     file_ids: Vec<i32>,
@@ -245,6 +461,7 @@ mod tests {
 
     use crate::{
         model::flash_card::FlashCard,
+        model::image_occlusion::{MaskShape, OcclusionMask},
         service::tests::{create_file_cell, create_file_cell_with_cell_type_and_content, get_db},
     };
 
@@ -296,6 +513,78 @@ mod tests {
         assert_eq!(actual.len(), 1);
     }
 
+    #[tokio::test]
+    async fn update_repetitions_for_cell_reversible_flash_card_added_back_repetition() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (file_id, cell_id) = create_file_cell(&db_conn, "file 1").await;
+        repetition::ActiveModel {
+            file_id: Set(file_id),
+            cell_id: Set(cell_id),
+            ..Default::default()
+        }
+        .insert(&db_conn)
+        .await
+        .unwrap();
+        let content = serde_json::to_string(&FlashCard {
+            question: "perro".into(),
+            answer: "dog".into(),
+            is_reversible: true,
+        })
+        .unwrap();
+
+        // Act
+
+        update_repetitions_for_cell(&db_conn, file_id, cell_id, &CellType::FlashCard, &content)
+            .await
+            .unwrap();
+
+        // Assert
+
+        let actual = get_file_repetitions(&db_conn, file_id).await.unwrap();
+        assert_eq!(actual.len(), 2);
+        assert_eq!(actual[0].additional_content, None);
+        assert_eq!(actual[1].additional_content, Some(FlashCard::BACK.into()));
+    }
+
+    #[tokio::test]
+    async fn update_repetitions_for_cell_flash_card_no_longer_reversible_removed_back_repetition() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (file_id, cell_id) = create_file_cell(&db_conn, "file 1").await;
+        for additional_content in [None, Some(FlashCard::BACK.into())] {
+            repetition::ActiveModel {
+                file_id: Set(file_id),
+                cell_id: Set(cell_id),
+                additional_content: Set(additional_content),
+                ..Default::default()
+            }
+            .insert(&db_conn)
+            .await
+            .unwrap();
+        }
+        let content = serde_json::to_string(&FlashCard {
+            question: "perro".into(),
+            answer: "dog".into(),
+            is_reversible: false,
+        })
+        .unwrap();
+
+        // Act
+
+        update_repetitions_for_cell(&db_conn, file_id, cell_id, &CellType::FlashCard, &content)
+            .await
+            .unwrap();
+
+        // Assert
+
+        let actual = get_file_repetitions(&db_conn, file_id).await.unwrap();
+        assert_eq!(actual.len(), 1);
+        assert_eq!(actual[0].additional_content, None);
+    }
+
     #[tokio::test]
     async fn update_repetitions_for_cloze_added_new_repetitions() {
         // Arrange
@@ -331,6 +620,115 @@ mod tests {
         assert_eq!(actual[1].additional_content, Some("1".to_string()));
     }
 
+    #[tokio::test]
+    async fn update_repetitions_for_cell_image_occlusion_masks_changed_repetitions_updated() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (file_id, cell_id) = create_file_cell(&db_conn, "file 1").await;
+        for mask_id in ["1", "2"] {
+            repetition::ActiveModel {
+                file_id: Set(file_id),
+                cell_id: Set(cell_id),
+                additional_content: Set(Some(mask_id.into())),
+                ..Default::default()
+            }
+            .insert(&db_conn)
+            .await
+            .unwrap();
+        }
+        let rectangle = MaskShape::Rectangle {
+            x: 0.1,
+            y: 0.1,
+            width: 0.2,
+            height: 0.2,
+        };
+        // The mask 1 is removed and the mask 3 is added.
+        let content = serde_json::to_string(&ImageOcclusion {
+            image: "data:image/png;base64,AAAA".into(),
+            masks: vec![
+                OcclusionMask {
+                    id: 2,
+                    label: "heart".into(),
+                    shape: rectangle.clone(),
+                },
+                OcclusionMask {
+                    id: 3,
+                    label: "lung".into(),
+                    shape: rectangle,
+                },
+            ],
+        })
+        .unwrap();
+
+        // Act
+
+        update_repetitions_for_cell(
+            &db_conn,
+            file_id,
+            cell_id,
+            &CellType::ImageOcclusion,
+            &content,
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        let actual: Vec<Option<String>> = get_file_repetitions(&db_conn, file_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|repetition| repetition.additional_content)
+            .collect();
+        assert_eq!(actual, vec![Some("2".into()), Some("3".into())]);
+    }
+
+    #[tokio::test]
+    async fn update_repetitions_for_cell_image_occlusion_repeated_mask_id_inserted_once() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (file_id, cell_id) = create_file_cell(&db_conn, "file 1").await;
+        let mask = OcclusionMask {
+            id: 1,
+            label: "heart".into(),
+            shape: MaskShape::Rectangle {
+                x: 0.1,
+                y: 0.1,
+                width: 0.2,
+                height: 0.2,
+            },
+        };
+        let content = serde_json::to_string(&ImageOcclusion {
+            image: "data:image/png;base64,AAAA".into(),
+            masks: vec![mask.clone(), mask],
+        })
+        .unwrap();
+
+        // Act
+
+        update_repetitions_for_cell(
+            &db_conn,
+            file_id,
+            cell_id,
+            &CellType::ImageOcclusion,
+            &content,
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        let actual: Vec<Option<String>> = get_file_repetitions(&db_conn, file_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|repetition| repetition.additional_content)
+            .collect();
+        assert_eq!(actual, vec![Some("1".into())]);
+    }
+
     #[tokio::test]
     async fn get_study_repetition_counts_valid_input_returned_count() {
         // Arrange
@@ -427,7 +825,7 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn update_repetition_valid_input_updated_repetition() {
+    async fn save_repetition_valid_input_updated_repetition() {
         // Arrange
 
         let db_conn = get_db().await;
@@ -467,9 +865,7 @@ mod tests {
 
         // Act
 
-        update_repetition(&db_conn, repetition.clone())
-            .await
-            .unwrap();
+        save_repetition(&db_conn, repetition.clone()).await.unwrap();
 
         // Assert
 
@@ -492,6 +888,123 @@ mod tests {
         assert_eq!(actual.last_review, repetition.last_review);
     }
 
+    #[tokio::test]
+    async fn review_repetition_valid_input_recorded_review_log() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (file_id, cell_id) = create_file_cell_with_cell_type_and_content(
+            &db_conn,
+            "file 1",
+            CellType::FlashCard,
+            &serde_json::to_string(&FlashCard::default()).unwrap(),
+        )
+        .await;
+        let previous = get_repetitions_by_cell_id(&db_conn, cell_id).await.unwrap()[0].clone();
+
+        // Act
+
+        let repetition = review_repetition(&db_conn, previous.id, Rating::Hard, Some(2500))
+            .await
+            .unwrap();
+
+        // Assert
+
+        let actual = review_log::Entity::find().all(&db_conn).await.unwrap();
+        assert_eq!(actual.len(), 1);
+        assert_eq!(actual[0].repetition_id, previous.id);
+        assert_eq!(actual[0].cell_id, cell_id);
+        assert_eq!(actual[0].file_id, file_id);
+        assert_eq!(actual[0].rating, Rating::Hard);
+        assert_eq!(actual[0].previous_state, State::New);
+        assert_eq!(actual[0].new_state, State::Learning);
+        assert_eq!(actual[0].previous_stability, previous.stability);
+        assert_eq!(actual[0].new_stability, repetition.stability);
+        assert_eq!(actual[0].new_difficulty, repetition.difficulty);
+        assert_eq!(actual[0].review_duration, Some(2500));
+        assert_eq!(actual[0].reviewed_at, repetition.last_review);
+    }
+
+    #[tokio::test]
+    async fn review_repetition_new_repetition_scheduled_and_saved() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (_, cell_id) = create_file_cell_with_cell_type_and_content(
+            &db_conn,
+            "file 1",
+            CellType::FlashCard,
+            &serde_json::to_string(&FlashCard::default()).unwrap(),
+        )
+        .await;
+        let repetition_id = get_repetitions_by_cell_id(&db_conn, cell_id).await.unwrap()[0].id;
+
+        // Act
+
+        let actual = review_repetition(&db_conn, repetition_id, Rating::Easy, Some(1000))
+            .await
+            .unwrap();
+
+        // Assert
+
+        let saved = get_repetition_by_id(&db_conn, repetition_id).await.unwrap();
+        assert_eq!(actual, saved);
+        assert_eq!(saved.state, State::Review);
+        assert_eq!(saved.reps, 1);
+        assert!(saved.scheduled_days > 0);
+        assert!(saved.due > Utc::now());
+        let review_logs = review_log::Entity::find().all(&db_conn).await.unwrap();
+        assert_eq!(review_logs.len(), 1);
+        assert_eq!(review_logs[0].rating, Rating::Easy);
+        assert_eq!(review_logs[0].previous_state, State::New);
+        assert_eq!(review_logs[0].new_state, State::Review);
+        assert_eq!(review_logs[0].scheduled_days, saved.scheduled_days);
+    }
+
+    #[tokio::test]
+    async fn review_repetition_not_existing_repetition_returned_error() {
+        // Arrange
+
+        let db_conn = get_db().await;
+
+        // Act
+
+        let actual = review_repetition(&db_conn, 1, Rating::Good, None).await;
+
+        // Assert
+
+        assert_eq!(actual, Err("Repetition not found!".to_string()));
+        let review_logs = review_log::Entity::find().all(&db_conn).await.unwrap();
+        assert!(review_logs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn preview_repetition_new_repetition_returned_dues_ordered_by_rating() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (_, cell_id) = create_file_cell_with_cell_type_and_content(
+            &db_conn,
+            "file 1",
+            CellType::FlashCard,
+            &serde_json::to_string(&FlashCard::default()).unwrap(),
+        )
+        .await;
+        let repetition = get_repetitions_by_cell_id(&db_conn, cell_id).await.unwrap()[0].clone();
+
+        // Act
+
+        let actual = preview_repetition(&db_conn, repetition.id).await.unwrap();
+
+        // Assert
+
+        assert!(actual.again < actual.hard);
+        assert!(actual.hard < actual.good);
+        assert!(actual.good < actual.easy);
+        let saved = get_repetition_by_id(&db_conn, repetition.id).await.unwrap();
+        assert_eq!(saved, repetition);
+    }
+
     #[tokio::test]
     async fn get_repetitions_for_files_valid_input_returned_repetitions() {
         // Arrange
@@ -556,7 +1069,7 @@ mod tests {
         )
         .await;
         let repetition_id = get_repetitions_by_cell_id(&db_conn, cell_id).await.unwrap()[0].id;
-        update_repetition(
+        save_repetition(
             &db_conn,
             repetition::Model {
                 id: repetition_id,
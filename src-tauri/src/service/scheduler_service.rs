@@ -0,0 +1,436 @@
+use chrono::Utc;
+use sea_orm::{DbConn, entity::*, query::*};
+use tokio::{sync::Mutex, task};
+
+use crate::{
+    dto::optimization_result::OptimizationResult,
+    entity::{file, repetition::State, review_log, scheduler_parameters},
+    scheduler::{
+        Parameters, Scheduler,
+        optimizer::{self, Review, ReviewHistory},
+    },
+};
+
+use super::file_service;
+
+/// Fits the scheduler weights to the review history of the folder, or of the
+/// whole collection when `folder_id` is 0, and saves them so that the next
+/// reviews inside the folder use them. The database is only locked while the
+/// history is loaded and the result saved, the fitting runs on a blocking
+/// thread.
+pub async fn optimize_parameters(
+    db_conn: &Mutex<DbConn>,
+    folder_id: i32,
+) -> Result<OptimizationResult, String> {
+    let histories = {
+        let db_conn = db_conn.lock().await;
+        let folder_path = if folder_id == 0 {
+            None
+        } else {
+            Some(file_service::get_by_id(&db_conn, folder_id).await?.path)
+        };
+        get_review_histories(&db_conn, folder_path).await?
+    };
+
+    let result =
+        task::spawn_blocking(move || optimizer::optimize(&histories, &Parameters::default())).await;
+    let optimization = match result {
+        Ok(optimization) => optimization?,
+        Err(err) => return Err(err.to_string()),
+    };
+    let result = OptimizationResult {
+        weights: optimization.parameters.weights.to_vec(),
+        log_loss_before: optimization.before.log_loss,
+        log_loss_after: optimization.after.log_loss,
+        rmse_before: optimization.before.rmse,
+        rmse_after: optimization.after.rmse,
+        review_count: optimization.before.review_count as i32,
+    };
+
+    let folder_id = if folder_id == 0 {
+        None
+    } else {
+        Some(folder_id)
+    };
+    save_parameters(&*db_conn.lock().await, folder_id, &result).await?;
+
+    Ok(result)
+}
+
+async fn get_review_histories(
+    db_conn: &DbConn,
+    folder_path: Option<String>,
+) -> Result<Vec<ReviewHistory>, String> {
+    let mut query = review_log::Entity::find();
+    if let Some(folder_path) = folder_path {
+        query = query
+            .inner_join(file::Entity)
+            .filter(file_service::get_folder_descendants_condition(&folder_path));
+    }
+    let result = query
+        .order_by_asc(review_log::Column::RepetitionId)
+        .order_by_asc(review_log::Column::ReviewedAt)
+        .order_by_asc(review_log::Column::Id)
+        .all(db_conn)
+        .await;
+    let review_logs = match result {
+        Ok(review_logs) => review_logs,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let mut histories: Vec<ReviewHistory> = vec![];
+    let mut current_repetition_id = None;
+    for review_log in review_logs {
+        if current_repetition_id != Some(review_log.repetition_id) {
+            current_repetition_id = Some(review_log.repetition_id);
+            let initial_memory_state = if review_log.previous_state == State::New
+                || review_log.previous_stability <= 0.0
+            {
+                None
+            } else {
+                Some((
+                    review_log.previous_stability as f64,
+                    review_log.previous_difficulty as f64,
+                ))
+            };
+            histories.push(ReviewHistory {
+                initial_memory_state,
+                reviews: vec![],
+            });
+        }
+
+        if let Some(history) = histories.last_mut() {
+            history.reviews.push(Review {
+                rating: review_log.rating,
+                elapsed_days: review_log.elapsed_days,
+            });
+        }
+    }
+
+    Ok(histories)
+}
+
+async fn save_parameters(
+    db_conn: &DbConn,
+    folder_id: Option<i32>,
+    result: &OptimizationResult,
+) -> Result<(), String> {
+    let txn = match db_conn.begin().await {
+        Ok(txn) => txn,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let folder_condition = match folder_id {
+        Some(folder_id) => scheduler_parameters::Column::FolderId.eq(folder_id),
+        None => scheduler_parameters::Column::FolderId.is_null(),
+    };
+    if let Err(err) = scheduler_parameters::Entity::delete_many()
+        .filter(folder_condition)
+        .exec(&txn)
+        .await
+    {
+        return Err(err.to_string());
+    }
+
+    let active_model = scheduler_parameters::ActiveModel {
+        folder_id: Set(folder_id),
+        weights: Set(serde_json::to_string(&result.weights).unwrap()),
+        log_loss_before: Set(result.log_loss_before),
+        log_loss_after: Set(result.log_loss_after),
+        rmse_before: Set(result.rmse_before),
+        rmse_after: Set(result.rmse_after),
+        review_count: Set(result.review_count),
+        optimized_at: Set(Utc::now()),
+        ..Default::default()
+    };
+    if let Err(err) = scheduler_parameters::Entity::insert(active_model)
+        .exec(&txn)
+        .await
+    {
+        return Err(err.to_string());
+    }
+
+    let result = txn.commit().await;
+    match result {
+        Ok(_) => Ok(()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Returns a scheduler that uses the parameters optimized for the closest
+/// folder containing the file, falling back to the ones of the collection and
+/// then to the defaults.
+pub async fn get_scheduler_for_file(
+    db_conn: &impl ConnectionTrait,
+    file_id: i32,
+) -> Result<Scheduler, String> {
+    let file = match file::Entity::find_by_id(file_id).one(db_conn).await {
+        Ok(Some(file)) => file,
+        Ok(None) => return Err("File not found!".into()),
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let mut folder_paths: Vec<String> = vec![];
+    let mut path = file.path.as_str();
+    while let Some(index) = path.rfind('/') {
+        path = &path[..index];
+        folder_paths.push(path.to_string());
+    }
+
+    let result = scheduler_parameters::Entity::find()
+        .find_also_related(file::Entity)
+        .filter(
+            Condition::any()
+                .add(scheduler_parameters::Column::FolderId.is_null())
+                .add(file::Column::Path.is_in(folder_paths)),
+        )
+        .all(db_conn)
+        .await;
+    let rows = match result {
+        Ok(rows) => rows,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let closest_parameters = rows
+        .into_iter()
+        .max_by_key(|(_, folder)| folder.as_ref().map_or(0, |folder| folder.path.len() + 1));
+    let parameters = match closest_parameters {
+        Some((parameters, _)) => parse_parameters(&parameters.weights),
+        None => Parameters::default(),
+    };
+
+    Ok(Scheduler::new(parameters))
+}
+
+fn parse_parameters(weights: &str) -> Parameters {
+    let weights: Option<[f64; 19]> = serde_json::from_str::<Vec<f64>>(weights)
+        .ok()
+        .and_then(|weights| weights.try_into().ok());
+    match weights {
+        Some(weights) => Parameters {
+            weights,
+            ..Default::default()
+        },
+        None => Parameters::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use crate::{
+        entity::repetition,
+        model::rating::Rating,
+        service::tests::{create_file_cell, get_db},
+    };
+
+    use super::*;
+
+    async fn insert_review_logs(db_conn: &DbConn, file_id: i32, cell_id: i32, count: i32) {
+        for i in 0..count {
+            let repetition = repetition::ActiveModel {
+                file_id: Set(file_id),
+                cell_id: Set(cell_id),
+                ..Default::default()
+            }
+            .insert(db_conn)
+            .await
+            .unwrap();
+
+            let ratings = [
+                (Rating::Good, 0, State::New),
+                (Rating::Good, 3, State::Review),
+                (
+                    if i % 4 == 0 {
+                        Rating::Again
+                    } else {
+                        Rating::Good
+                    },
+                    9,
+                    State::Review,
+                ),
+            ];
+            for (j, (rating, elapsed_days, previous_state)) in ratings.into_iter().enumerate() {
+                review_log::ActiveModel {
+                    repetition_id: Set(repetition.id),
+                    cell_id: Set(cell_id),
+                    file_id: Set(file_id),
+                    rating: Set(rating),
+                    previous_state: Set(previous_state),
+                    new_state: Set(State::Review),
+                    previous_stability: Set(3.0),
+                    new_stability: Set(3.0),
+                    previous_difficulty: Set(5.0),
+                    new_difficulty: Set(5.0),
+                    elapsed_days: Set(elapsed_days),
+                    scheduled_days: Set(elapsed_days),
+                    review_duration: Set(None),
+                    reviewed_at: Set(Utc::now() + Duration::days(j as i64)),
+                    ..Default::default()
+                }
+                .insert(db_conn)
+                .await
+                .unwrap();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn optimize_parameters_folder_with_history_saved_parameters() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let folder_id = file_service::create_folder(&db_conn, "folder".into())
+            .await
+            .unwrap();
+        let (file_id, cell_id) = create_file_cell(&db_conn, "folder/file").await;
+        insert_review_logs(&db_conn, file_id, cell_id, 10).await;
+
+        // Act
+
+        let db_conn = Mutex::new(db_conn);
+        let actual = optimize_parameters(&db_conn, folder_id).await.unwrap();
+
+        // Assert
+
+        let db_conn = db_conn.into_inner();
+        assert_eq!(actual.review_count, 20);
+        assert_eq!(actual.weights.len(), 19);
+        assert!(actual.log_loss_after <= actual.log_loss_before);
+        let saved = scheduler_parameters::Entity::find()
+            .all(&db_conn)
+            .await
+            .unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].folder_id, Some(folder_id));
+        assert_eq!(saved[0].review_count, 20);
+    }
+
+    #[tokio::test]
+    async fn optimize_parameters_folder_ignored_mixed_case_and_wildcard_siblings() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let folder_id = file_service::create_folder(&db_conn, "my_folder".into())
+            .await
+            .unwrap();
+        for path in ["my_folder/file", "My_Folder/file", "myxfolder/file"] {
+            let (file_id, cell_id) = create_file_cell(&db_conn, path).await;
+            insert_review_logs(&db_conn, file_id, cell_id, 10).await;
+        }
+
+        // Act
+
+        let actual = optimize_parameters(&Mutex::new(db_conn), folder_id)
+            .await
+            .unwrap();
+
+        // Assert
+
+        assert_eq!(actual.review_count, 20);
+    }
+
+    #[tokio::test]
+    async fn optimize_parameters_called_twice_replaced_parameters() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (file_id, cell_id) = create_file_cell(&db_conn, "file").await;
+        insert_review_logs(&db_conn, file_id, cell_id, 10).await;
+        let db_conn = Mutex::new(db_conn);
+        optimize_parameters(&db_conn, 0).await.unwrap();
+
+        // Act
+
+        optimize_parameters(&db_conn, 0).await.unwrap();
+
+        // Assert
+
+        let db_conn = db_conn.into_inner();
+        let saved = scheduler_parameters::Entity::find()
+            .all(&db_conn)
+            .await
+            .unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].folder_id, None);
+    }
+
+    #[tokio::test]
+    async fn optimize_parameters_folder_without_history_returned_error() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let folder_id = file_service::create_folder(&db_conn, "folder".into())
+            .await
+            .unwrap();
+        let (file_id, cell_id) = create_file_cell(&db_conn, "other file").await;
+        insert_review_logs(&db_conn, file_id, cell_id, 10).await;
+
+        // Act
+
+        let actual = optimize_parameters(&Mutex::new(db_conn), folder_id).await;
+
+        // Assert
+
+        assert!(actual.is_err());
+    }
+
+    #[tokio::test]
+    async fn get_scheduler_for_file_nested_folders_used_closest_parameters() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (file_id, _) = create_file_cell(&db_conn, "folder 1/folder 2/file").await;
+        let folders = file_service::get_files(&db_conn).await.unwrap();
+        let folder_id = |path: &str| folders.iter().find(|f| f.path == path).unwrap().id;
+        for (folder_id, first_weight) in [
+            (None, 1.0),
+            (Some(folder_id("folder 1")), 2.0),
+            (Some(folder_id("folder 1/folder 2")), 3.0),
+        ] {
+            let mut weights = Parameters::default().weights;
+            weights[0] = first_weight;
+            save_parameters(
+                &db_conn,
+                folder_id,
+                &OptimizationResult {
+                    weights: weights.to_vec(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        // Act
+
+        let actual = get_scheduler_for_file(&db_conn, file_id).await.unwrap();
+
+        // Assert
+
+        let mut expected_weights = Parameters::default().weights;
+        expected_weights[0] = 3.0;
+        let expected = Scheduler::new(Parameters {
+            weights: expected_weights,
+            ..Default::default()
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn get_scheduler_for_file_no_parameters_used_defaults() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (file_id, _) = create_file_cell(&db_conn, "folder/file").await;
+
+        // Act
+
+        let actual = get_scheduler_for_file(&db_conn, file_id).await.unwrap();
+
+        // Assert
+
+        assert_eq!(actual, Scheduler::default());
+    }
+}
@@ -1,22 +1,209 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, TimeDelta, Utc};
+
 use crate::{
-    dto::search_result::SearchResult,
-    entity::{cell, repetition},
+    dto::{
+        search_request::{SearchRequest, SearchSort},
+        search_result::SearchResult,
+    },
+    entity::{cell, cell_tag, file, repetition, tag},
+    model::search_query::{Comparison, FilterKind, SearchQuery, TextTerm},
+    service::{file_service, tag_service},
+};
+
+use sea_orm::{
+    DbConn, Statement, Value,
+    entity::*,
+    query::*,
+    sea_query::{Expr, NullOrdering, Query, SelectStatement, SimpleExpr, SqliteQueryBuilder},
 };
 
-use sea_orm::{DbConn, entity::*, query::*};
+const MAXIMUM_PAGE_SIZE: u64 = 500;
+const SNIPPET_TOKENS: i32 = 16;
+// Control characters cannot appear in the content, so they mark the matches
+// until the snippet is escaped.
+const MATCH_START: char = '\u{2}';
+const MATCH_END: char = '\u{3}';
+
+/// Searches a page of the cells matching the query, see `SearchQuery` for the
+/// syntax. Words match as prefixes, phrases match consecutive words and
+/// diacritics are ignored.
+pub async fn search_cells(
+    db_conn: &DbConn,
+    request: &SearchRequest,
+) -> Result<SearchResult, String> {
+    let query = SearchQuery::parse(&request.search_text)?;
+    let positive_terms: Vec<&TextTerm> = query
+        .text_terms
+        .iter()
+        .filter(|term| !term.is_negated)
+        .collect();
+    let fts_query = get_fts_query(&positive_terms);
+    let filtered_cells = get_filtered_cells(&query, &fts_query)?;
+    let limit = request.limit.clamp(1, MAXIMUM_PAGE_SIZE);
 
-pub async fn search_cells(db_conn: &DbConn, search_text: &str) -> Result<SearchResult, String> {
     let result = cell::Entity::find()
-        .find_with_related(repetition::Entity)
-        .filter(cell::Column::SearchableContent.contains(search_text.to_lowercase()))
-        .limit(150)
+        .filter(cell::Column::Id.in_subquery(filtered_cells.clone()))
+        .count(db_conn)
+        .await;
+    let total_count = match result {
+        Ok(total_count) => total_count,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let cell_ids = if request.sort == SearchSort::Relevance && !fts_query.is_empty() {
+        get_ids_by_relevance(db_conn, &fts_query, filtered_cells, request.offset, limit).await?
+    } else {
+        get_sorted_ids(db_conn, request.sort, filtered_cells, request.offset, limit).await?
+    };
+    let snippets = if fts_query.is_empty() {
+        HashMap::new()
+    } else {
+        get_snippets(db_conn, &fts_query, &cell_ids).await?
+    };
+
+    let mut search_result = get_cells_with_repetitions(db_conn, cell_ids).await?;
+    search_result.snippets = snippets;
+    search_result.total_count = total_count;
+    Ok(search_result)
+}
+
+async fn get_ids_by_relevance(
+    db_conn: &DbConn,
+    fts_query: &str,
+    filtered_cells: SelectStatement,
+    offset: u64,
+    limit: u64,
+) -> Result<Vec<i32>, String> {
+    let (filtered_cells_sql, filtered_cells_values) = filtered_cells.build(SqliteQueryBuilder);
+    let mut values: Vec<Value> = vec![fts_query.into()];
+    values.extend(filtered_cells_values.0);
+    values.push(limit.into());
+    values.push(offset.into());
+    let statement = Statement::from_sql_and_values(
+        db_conn.get_database_backend(),
+        format!(
+            r#"SELECT "rowid" AS "id"
+            FROM "cell_fts"
+            WHERE "cell_fts" MATCH ? AND "rowid" IN ({filtered_cells_sql})
+            ORDER BY bm25("cell_fts"), "rowid"
+            LIMIT ? OFFSET ?"#
+        ),
+        values,
+    );
+    let rows = match db_conn.query_all(statement).await {
+        Ok(rows) => rows,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let mut cell_ids = vec![];
+    for row in rows {
+        match row.try_get::<i32>("", "id") {
+            Ok(id) => cell_ids.push(id),
+            Err(err) => return Err(err.to_string()),
+        }
+    }
+    Ok(cell_ids)
+}
+
+async fn get_sorted_ids(
+    db_conn: &DbConn,
+    sort: SearchSort,
+    filtered_cells: SelectStatement,
+    offset: u64,
+    limit: u64,
+) -> Result<Vec<i32>, String> {
+    let mut select = cell::Entity::find()
+        .select_only()
+        .column(cell::Column::Id)
+        .filter(cell::Column::Id.in_subquery(filtered_cells));
+    select = match sort {
+        SearchSort::FilePath => select
+            .join(JoinType::InnerJoin, cell::Relation::File.def())
+            .order_by_asc(file::Column::Path)
+            .order_by_asc(cell::Column::Index),
+        SearchSort::Due => select.order_by_with_nulls(
+            Expr::cust(
+                r#"(SELECT MIN("due") FROM "repetition" WHERE "repetition"."cell_id" = "cell"."id")"#,
+            ),
+            Order::Asc,
+            NullOrdering::Last,
+        ),
+        SearchSort::LastReview => select.order_by_with_nulls(
+            Expr::cust(
+                r#"(SELECT MAX("last_review") FROM "repetition" WHERE "repetition"."cell_id" = "cell"."id")"#,
+            ),
+            Order::Desc,
+            NullOrdering::Last,
+        ),
+        // The ids are autoincremented so they follow the creation order.
+        SearchSort::Relevance | SearchSort::CreationTime => select,
+    };
+
+    let result = select
+        .order_by_desc(cell::Column::Id)
+        .offset(offset)
+        .limit(limit)
+        .into_tuple::<i32>()
         .all(db_conn)
         .await;
+    match result {
+        Ok(cell_ids) => Ok(cell_ids),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+async fn get_snippets(
+    db_conn: &DbConn,
+    fts_query: &str,
+    cell_ids: &[i32],
+) -> Result<HashMap<i32, String>, String> {
+    let mut values: Vec<Value> = vec![fts_query.into()];
+    values.extend(cell_ids.iter().map(|id| Value::from(*id)));
+    let placeholders = vec!["?"; cell_ids.len()].join(", ");
+    let statement = Statement::from_sql_and_values(
+        db_conn.get_database_backend(),
+        format!(
+            r#"SELECT "rowid" AS "id",
+                snippet("cell_fts", 0, '{MATCH_START}', '{MATCH_END}', '…', {SNIPPET_TOKENS}) AS "snippet"
+            FROM "cell_fts"
+            WHERE "cell_fts" MATCH ? AND "rowid" IN ({placeholders})"#
+        ),
+        values,
+    );
+    let rows = match db_conn.query_all(statement).await {
+        Ok(rows) => rows,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let mut snippets = HashMap::new();
+    for row in rows {
+        let (id, snippet): (i32, String) =
+            match row.try_get_many("", &["id".into(), "snippet".into()]) {
+                Ok(values) => values,
+                Err(err) => return Err(err.to_string()),
+            };
+        snippets.insert(id, highlight_snippet(&snippet));
+    }
+    Ok(snippets)
+}
 
-    let rows = match result {
+/// Returns the cells in the order of the ids.
+async fn get_cells_with_repetitions(
+    db_conn: &DbConn,
+    cell_ids: Vec<i32>,
+) -> Result<SearchResult, String> {
+    let result = cell::Entity::find()
+        .find_with_related(repetition::Entity)
+        .filter(cell::Column::Id.is_in(cell_ids.clone()))
+        .all(db_conn)
+        .await;
+    let mut rows = match result {
         Ok(rows) => rows,
         Err(err) => return Err(err.to_string()),
     };
+    rows.sort_by_key(|(cell, _)| cell_ids.iter().position(|id| *id == cell.id));
 
     let mut cells: Vec<cell::Model> = vec![];
     let mut repetitions: Vec<repetition::Model> = vec![];
@@ -26,23 +213,238 @@ pub async fn search_cells(db_conn: &DbConn, search_text: &str) -> Result<SearchR
         repetitions.append(&mut repetition);
     }
 
-    Ok(SearchResult { cells, repetitions })
+    Ok(SearchResult {
+        cells,
+        repetitions,
+        ..Default::default()
+    })
+}
+
+/// Selects the ids of the cells matching the text and the filters.
+fn get_filtered_cells(query: &SearchQuery, fts_query: &str) -> Result<SelectStatement, String> {
+    let mut condition = Condition::all();
+
+    if !fts_query.is_empty() {
+        condition = condition.add(Expr::cust_with_values(
+            r#""cell"."id" IN (SELECT "rowid" FROM "cell_fts" WHERE "cell_fts" MATCH ?)"#,
+            [fts_query],
+        ));
+    }
+
+    for term in query.text_terms.iter().filter(|term| term.is_negated) {
+        let fts_query = get_fts_query(&[term]);
+        if !fts_query.is_empty() {
+            condition = condition.add(Expr::cust_with_values(
+                r#""cell"."id" NOT IN (SELECT "rowid" FROM "cell_fts" WHERE "cell_fts" MATCH ?)"#,
+                [fts_query],
+            ));
+        }
+    }
+
+    // The repetition filters that are not negated must match the same
+    // repetition, e.g. a cloze with a new and a lapsed card is not a match for
+    // `state:new lapses:>3`.
+    let mut repetition_condition = Condition::all();
+    let mut has_repetition_filter = false;
+
+    for filter in &query.filters {
+        match (get_filter_condition(&filter.kind)?, filter.is_negated) {
+            (FilterCondition::Cell(expr), false) => condition = condition.add(expr),
+            (FilterCondition::Cell(expr), true) => {
+                condition = condition.add(Expr::expr(expr).not())
+            }
+            (FilterCondition::Repetition(expr), false) => {
+                repetition_condition = repetition_condition.add(expr);
+                has_repetition_filter = true;
+            }
+            (FilterCondition::Repetition(expr), true) => {
+                condition = condition.add(
+                    cell::Column::Id
+                        .not_in_subquery(get_repetition_cell_ids(Condition::all().add(expr))),
+                )
+            }
+        }
+    }
+
+    if has_repetition_filter {
+        condition = condition
+            .add(cell::Column::Id.in_subquery(get_repetition_cell_ids(repetition_condition)));
+    }
+
+    Ok(Query::select()
+        .column((cell::Entity, cell::Column::Id))
+        .from(cell::Entity)
+        .cond_where(condition)
+        .to_owned())
+}
+
+/// The condition of a filter, on the cell or on one of its repetitions.
+enum FilterCondition {
+    Cell(SimpleExpr),
+    Repetition(SimpleExpr),
+}
+
+fn get_filter_condition(kind: &FilterKind) -> Result<FilterCondition, String> {
+    let condition = match kind {
+        FilterKind::CellType(cell_type) => {
+            FilterCondition::Cell(cell::Column::CellType.eq(cell_type.clone()))
+        }
+        FilterKind::Folder(path) => FilterCondition::Cell(cell::Column::FileId.in_subquery(
+            get_file_ids(file_service::get_folder_descendants_condition(path)),
+        )),
+        FilterKind::File(path) => FilterCondition::Cell(
+            cell::Column::FileId.in_subquery(get_file_ids(file::Column::Path.eq(path))),
+        ),
+        FilterKind::Tag(name) => FilterCondition::Cell(cell::Column::Id.in_subquery(
+            get_tagged_cell_ids(tag_service::get_tag_with_descendants_condition(name)),
+        )),
+        FilterKind::State(state) => {
+            FilterCondition::Repetition(repetition::Column::State.eq(state.clone()))
+        }
+        FilterKind::Due(Comparison::Equal, days) => {
+            let day_start = get_date_in_days(*days)?
+                .date_naive()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc();
+            let day_end = match day_start.checked_add_signed(TimeDelta::days(1)) {
+                Some(day_end) => day_end,
+                None => return Err(get_invalid_due_error(*days)),
+            };
+            FilterCondition::Repetition(
+                repetition::Column::Due
+                    .gte(day_start)
+                    .and(repetition::Column::Due.lt(day_end)),
+            )
+        }
+        FilterKind::Due(comparison, days) => FilterCondition::Repetition(compare(
+            repetition::Column::Due,
+            *comparison,
+            get_date_in_days(*days)?,
+        )),
+        FilterKind::Lapses(comparison, lapses) => {
+            FilterCondition::Repetition(compare(repetition::Column::Lapses, *comparison, *lapses))
+        }
+        FilterKind::Reps(comparison, reps) => {
+            FilterCondition::Repetition(compare(repetition::Column::Reps, *comparison, *reps))
+        }
+    };
+    Ok(condition)
+}
+
+/// Returns now plus the given number of days, which is typed by the user and
+/// can be beyond the supported dates.
+fn get_date_in_days(days: i64) -> Result<DateTime<Utc>, String> {
+    match TimeDelta::try_days(days).and_then(|delta| Utc::now().checked_add_signed(delta)) {
+        Some(date) => Ok(date),
+        None => Err(get_invalid_due_error(days)),
+    }
+}
+
+fn get_invalid_due_error(days: i64) -> String {
+    format!("Invalid due \"{days}d\", the date is out of range!")
+}
+
+fn compare<V: Into<Value>>(
+    column: repetition::Column,
+    comparison: Comparison,
+    value: V,
+) -> SimpleExpr {
+    match comparison {
+        Comparison::Less => column.lt(value),
+        Comparison::LessOrEqual => column.lte(value),
+        Comparison::Equal => column.eq(value),
+        Comparison::GreaterOrEqual => column.gte(value),
+        Comparison::Greater => column.gt(value),
+    }
+}
+
+fn get_file_ids(condition: SimpleExpr) -> SelectStatement {
+    Query::select()
+        .column(file::Column::Id)
+        .from(file::Entity)
+        .and_where(condition)
+        .to_owned()
+}
+
+fn get_tagged_cell_ids(condition: Condition) -> SelectStatement {
+    Query::select()
+        .column(cell_tag::Column::CellId)
+        .from(cell_tag::Entity)
+        .inner_join(
+            tag::Entity,
+            Expr::col((tag::Entity, tag::Column::Id))
+                .equals((cell_tag::Entity, cell_tag::Column::TagId)),
+        )
+        .cond_where(condition)
+        .to_owned()
+}
+
+fn get_repetition_cell_ids(condition: Condition) -> SelectStatement {
+    Query::select()
+        .column(repetition::Column::CellId)
+        .from(repetition::Entity)
+        .cond_where(condition)
+        .to_owned()
+}
+
+/// Converts the text terms to an FTS5 query, every term is quoted so that the
+/// user cannot write FTS5 syntax by accident.
+fn get_fts_query(terms: &[&TextTerm]) -> String {
+    terms
+        .iter()
+        .filter(|term| term.text.chars().any(char::is_alphanumeric))
+        .map(|term| {
+            let text = term.text.replace('"', "\"\"");
+            if term.is_phrase {
+                format!("\"{text}\"")
+            } else {
+                format!("\"{text}\"*")
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Escapes the snippet as HTML and wraps the matches in `<mark>` tags.
+fn highlight_snippet(snippet: &str) -> String {
+    snippet
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace(MATCH_START, "<mark>")
+        .replace(MATCH_END, "</mark>")
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
+        dto::update_cell_request::UpdateCellRequest,
         entity::cell::CellType,
         model::flash_card::FlashCard,
         service::{
-            cell_service::create_cell,
-            repetition_service::update_repetition,
-            tests::{create_file, get_db},
+            cell_service::{create_cell, delete_cell, update_cells_contents},
+            repetition_service::save_repetition,
+            tag_service,
+            tests::{
+                create_file, create_file_cell, create_file_cell_with_cell_type_and_content, get_db,
+            },
         },
     };
 
     use super::*;
 
+    async fn search(db_conn: &DbConn, search_text: &str) -> Result<SearchResult, String> {
+        search_cells(
+            db_conn,
+            &SearchRequest {
+                search_text: search_text.into(),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
     #[tokio::test]
     pub async fn search_cells_valid_input_returned_relevant_cells_with_repetitions() {
         // Arrange
@@ -63,6 +465,7 @@ mod tests {
             &serde_json::to_string(&FlashCard {
                 question: "include".into(),
                 answer: "".into(),
+                ..Default::default()
             })
             .unwrap(),
             CellType::FlashCard,
@@ -75,7 +478,7 @@ mod tests {
             crate::service::repetition_service::get_file_repetitions(&db_conn, file2_id)
                 .await
                 .unwrap();
-        update_repetition(
+        save_repetition(
             &db_conn,
             repetition::Model {
                 id: repetition[0].id,
@@ -94,7 +497,7 @@ mod tests {
 
         // Act
 
-        let actual = search_cells(&db_conn, "include".into()).await.unwrap();
+        let actual = search(&db_conn, "include").await.unwrap();
 
         // Assert
 
@@ -102,4 +505,457 @@ mod tests {
         assert_eq!(1, actual.repetitions.len());
         assert_eq!(repetition::State::Review, actual.repetitions[0].state);
     }
+
+    async fn create_notes(db_conn: &DbConn, contents: &[&str]) -> Vec<i32> {
+        let file_id = create_file(db_conn, "file").await;
+        let mut cell_ids = vec![];
+        for (i, content) in contents.iter().enumerate() {
+            let cell_id = create_cell(db_conn, file_id, content, CellType::Note, i as i32)
+                .await
+                .unwrap();
+            cell_ids.push(cell_id);
+        }
+        cell_ids
+    }
+
+    #[tokio::test]
+    async fn search_cells_more_occurrences_ranked_first() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let cell_ids = create_notes(
+            &db_conn,
+            &[
+                "the cell membrane surrounds the cytoplasm of a long living cell",
+                "mitochondria mitochondria mitochondria",
+                "the mitochondria is inside the cell",
+            ],
+        )
+        .await;
+
+        // Act
+
+        let actual = search(&db_conn, "mitochondria").await.unwrap();
+
+        // Assert
+
+        let actual_ids: Vec<i32> = actual.cells.iter().map(|cell| cell.id).collect();
+        assert_eq!(actual_ids, vec![cell_ids[1], cell_ids[2]]);
+        assert_eq!(
+            actual.snippets[&cell_ids[2]],
+            "the <mark>mitochondria</mark> is inside the cell"
+        );
+    }
+
+    #[tokio::test]
+    async fn search_cells_prefix_and_diacritics_matched() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let cell_ids = create_notes(&db_conn, &["Un <b>café</b> crème", "cafeteria", "tea"]).await;
+
+        // Act
+
+        let actual = search(&db_conn, "CAFE").await.unwrap();
+
+        // Assert
+
+        let mut actual_ids: Vec<i32> = actual.cells.iter().map(|cell| cell.id).collect();
+        actual_ids.sort();
+        assert_eq!(actual_ids, vec![cell_ids[0], cell_ids[1]]);
+        assert_eq!(actual.snippets[&cell_ids[0]], "un <mark>café</mark> crème");
+    }
+
+    #[tokio::test]
+    async fn search_cells_phrase_matched_consecutive_words_only() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let cell_ids = create_notes(&db_conn, &["red blood cell", "red cell and blood"]).await;
+
+        // Act
+
+        let actual = search(&db_conn, "\"red blood\"").await.unwrap();
+
+        // Assert
+
+        assert_eq!(actual.cells.len(), 1);
+        assert_eq!(actual.cells[0].id, cell_ids[0]);
+    }
+
+    #[tokio::test]
+    async fn search_cells_updated_and_deleted_cells_index_kept_in_sync() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let cell_ids = create_notes(&db_conn, &["old text", "removed text"]).await;
+        update_cells_contents(
+            &db_conn,
+            vec![UpdateCellRequest {
+                cell_id: cell_ids[0],
+                content: "new text".into(),
+            }],
+        )
+        .await
+        .unwrap();
+        delete_cell(&db_conn, cell_ids[1]).await.unwrap();
+
+        // Act
+
+        let old = search(&db_conn, "old").await.unwrap();
+        let new = search(&db_conn, "new").await.unwrap();
+        let removed = search(&db_conn, "removed").await.unwrap();
+
+        // Assert
+
+        assert!(old.cells.is_empty());
+        assert_eq!(new.cells.len(), 1);
+        assert!(removed.cells.is_empty());
+    }
+
+    #[tokio::test]
+    async fn search_cells_filters_matched_only_filtered_cells() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (_, leech_id) = create_file_cell_with_cell_type_and_content(
+            &db_conn,
+            "Biology/Cells/membrane",
+            CellType::TrueFalse,
+            r#"{"question":"The membrane is made of lipids","isTrue":true}"#,
+        )
+        .await;
+        let (_, other_id) = create_file_cell_with_cell_type_and_content(
+            &db_conn,
+            "Biology/Cells/nucleus",
+            CellType::TrueFalse,
+            r#"{"question":"The nucleus has a membrane","isTrue":true}"#,
+        )
+        .await;
+        create_file_cell_with_cell_type_and_content(
+            &db_conn,
+            "Chemistry/membrane",
+            CellType::TrueFalse,
+            r#"{"question":"A membrane","isTrue":true}"#,
+        )
+        .await;
+        repetition::Entity::update_many()
+            .col_expr(repetition::Column::Lapses, Expr::value(5))
+            .col_expr(repetition::Column::State, Expr::value("Review"))
+            .filter(repetition::Column::CellId.eq(leech_id))
+            .exec(&db_conn)
+            .await
+            .unwrap();
+
+        // Act
+
+        let leeches = search(
+            &db_conn,
+            r#"type:true_false folder:"Biology/Cells" state:review lapses:>4 membrane"#,
+        )
+        .await
+        .unwrap();
+        let others = search(&db_conn, "folder:Biology -lapses:>4 membrane")
+            .await
+            .unwrap();
+
+        // Assert
+
+        assert_eq!(leeches.cells.len(), 1);
+        assert_eq!(leeches.cells[0].id, leech_id);
+        assert_eq!(others.cells.len(), 1);
+        assert_eq!(others.cells[0].id, other_id);
+    }
+
+    #[tokio::test]
+    async fn search_cells_folder_filter_ignored_mixed_case_and_wildcard_siblings() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (_, child_id) = create_file_cell(&db_conn, "my_folder/file").await;
+        create_file_cell(&db_conn, "My_Folder/file").await;
+        create_file_cell(&db_conn, "myxfolder/file").await;
+
+        // Act
+
+        let actual = search(&db_conn, "folder:my_folder").await.unwrap();
+
+        // Assert
+
+        assert_eq!(
+            actual.cells.iter().map(|cell| cell.id).collect::<Vec<_>>(),
+            vec![child_id]
+        );
+    }
+
+    #[tokio::test]
+    async fn search_cells_tag_filter_matched_tag_and_child_tags() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (_, verb_id) = create_file_cell(&db_conn, "file 1").await;
+        let (_, noun_id) = create_file_cell(&db_conn, "file 2").await;
+        let (_, other_id) = create_file_cell(&db_conn, "file 3").await;
+        tag_service::add_tag(&db_conn, vec![verb_id], "lang::spanish::verbs")
+            .await
+            .unwrap();
+        tag_service::add_tag(&db_conn, vec![noun_id], "lang::spanish")
+            .await
+            .unwrap();
+        tag_service::add_tag(&db_conn, vec![other_id], "lang::spanish2")
+            .await
+            .unwrap();
+
+        // Act
+
+        let actual = search(&db_conn, "tag:lang::spanish").await.unwrap();
+        let negated = search(&db_conn, "-tag:lang::spanish::verbs").await.unwrap();
+
+        // Assert
+
+        let mut actual_ids: Vec<i32> = actual.cells.iter().map(|cell| cell.id).collect();
+        actual_ids.sort();
+        assert_eq!(actual_ids, vec![verb_id, noun_id]);
+        assert_eq!(negated.total_count, 2);
+    }
+
+    #[tokio::test]
+    async fn search_cells_tag_filter_ignored_mixed_case_and_wildcard_siblings() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (_, child_id) = create_file_cell(&db_conn, "file 1").await;
+        let (_, mixed_case_id) = create_file_cell(&db_conn, "file 2").await;
+        let (_, wildcard_id) = create_file_cell(&db_conn, "file 3").await;
+        tag_service::add_tag(&db_conn, vec![child_id], "my_tag::child")
+            .await
+            .unwrap();
+        tag_service::add_tag(&db_conn, vec![mixed_case_id], "My_Tag::child")
+            .await
+            .unwrap();
+        tag_service::add_tag(&db_conn, vec![wildcard_id], "myxtag::child")
+            .await
+            .unwrap();
+
+        // Act
+
+        let actual = search(&db_conn, "tag:my_tag").await.unwrap();
+
+        // Assert
+
+        assert_eq!(
+            actual.cells.iter().map(|cell| cell.id).collect::<Vec<_>>(),
+            vec![child_id]
+        );
+    }
+
+    #[tokio::test]
+    async fn search_cells_due_filter_matched_overdue_cells() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (_, overdue_id) = create_file_cell_with_cell_type_and_content(
+            &db_conn,
+            "file",
+            CellType::FlashCard,
+            r#"{"question":"overdue","answer":""}"#,
+        )
+        .await;
+        create_file_cell_with_cell_type_and_content(
+            &db_conn,
+            "other file",
+            CellType::FlashCard,
+            r#"{"question":"later","answer":""}"#,
+        )
+        .await;
+        repetition::Entity::update_many()
+            .col_expr(
+                repetition::Column::Due,
+                Expr::value(Utc::now() + TimeDelta::days(10)),
+            )
+            .filter(repetition::Column::CellId.ne(overdue_id))
+            .exec(&db_conn)
+            .await
+            .unwrap();
+
+        // Act
+
+        let actual = search(&db_conn, "due:<3d").await.unwrap();
+
+        // Assert
+
+        assert_eq!(actual.cells.len(), 1);
+        assert_eq!(actual.cells[0].id, overdue_id);
+    }
+
+    #[tokio::test]
+    async fn search_cells_only_negated_term_excluded_matching_cells() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let cell_ids = create_notes(&db_conn, &["keep this", "drop this"]).await;
+
+        // Act
+
+        let actual = search(&db_conn, "-drop").await.unwrap();
+
+        // Assert
+
+        assert_eq!(actual.cells.len(), 1);
+        assert_eq!(actual.cells[0].id, cell_ids[0]);
+    }
+
+    #[tokio::test]
+    async fn search_cells_invalid_filter_returned_error() {
+        // Arrange
+
+        let db_conn = get_db().await;
+
+        // Act
+
+        let actual = search(&db_conn, "lapses:many").await;
+
+        // Assert
+
+        assert!(actual.is_err());
+    }
+
+    #[tokio::test]
+    async fn search_cells_out_of_range_due_returned_error() {
+        // Arrange
+
+        let db_conn = get_db().await;
+
+        // Act
+
+        let actual = [
+            search(&db_conn, "due:<99999999999999d").await,
+            search(&db_conn, "due:99999999999d").await,
+            search(&db_conn, "due:>9999999999999999999w").await,
+        ];
+
+        // Assert
+
+        for actual in actual {
+            assert!(actual.unwrap_err().starts_with("Invalid due"));
+        }
+    }
+
+    #[tokio::test]
+    async fn search_cells_second_page_returned_remaining_cells_and_total_count() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let cell_ids = create_notes(&db_conn, &["page", "page", "page", "page", "page"]).await;
+
+        // Act
+
+        let actual = search_cells(
+            &db_conn,
+            &SearchRequest {
+                search_text: "page".into(),
+                sort: SearchSort::CreationTime,
+                offset: 3,
+                limit: 3,
+            },
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        let actual_ids: Vec<i32> = actual.cells.iter().map(|cell| cell.id).collect();
+        assert_eq!(actual_ids, vec![cell_ids[1], cell_ids[0]]);
+        assert_eq!(actual.total_count, 5);
+        assert_eq!(actual.snippets.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn search_cells_sorted_by_file_path_returned_cells_in_path_order() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (_, b_cell_id) = create_file_cell(&db_conn, "b").await;
+        let (_, c_cell_id) = create_file_cell(&db_conn, "folder/c").await;
+        let (_, a_cell_id) = create_file_cell(&db_conn, "a").await;
+
+        // Act
+
+        let actual = search_cells(
+            &db_conn,
+            &SearchRequest {
+                sort: SearchSort::FilePath,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        let actual_ids: Vec<i32> = actual.cells.iter().map(|cell| cell.id).collect();
+        assert_eq!(actual_ids, vec![a_cell_id, b_cell_id, c_cell_id]);
+    }
+
+    #[tokio::test]
+    async fn search_cells_sorted_by_due_returned_soonest_first_and_notes_last() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (_, note_id) = create_file_cell(&db_conn, "note").await;
+        let mut card_ids = vec![];
+        for (name, days) in [("later", 5), ("sooner", 1)] {
+            let (_, cell_id) = create_file_cell_with_cell_type_and_content(
+                &db_conn,
+                name,
+                CellType::FlashCard,
+                r#"{"question":"","answer":""}"#,
+            )
+            .await;
+            repetition::Entity::update_many()
+                .col_expr(
+                    repetition::Column::Due,
+                    Expr::value(Utc::now() + TimeDelta::days(days)),
+                )
+                .filter(repetition::Column::CellId.eq(cell_id))
+                .exec(&db_conn)
+                .await
+                .unwrap();
+            card_ids.push(cell_id);
+        }
+
+        // Act
+
+        let actual = search_cells(
+            &db_conn,
+            &SearchRequest {
+                sort: SearchSort::Due,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        // Assert
+
+        let actual_ids: Vec<i32> = actual.cells.iter().map(|cell| cell.id).collect();
+        assert_eq!(actual_ids, vec![card_ids[1], card_ids[0], note_id]);
+        assert_eq!(actual.total_count, 3);
+    }
+
+    #[test]
+    fn get_fts_query_words_and_phrases_quoted() {
+        // Arrange
+
+        let query = SearchQuery::parse(r#"cell "red  blood" - AND x"#).unwrap();
+        let terms: Vec<&TextTerm> = query.text_terms.iter().collect();
+
+        // Act
+
+        let actual = get_fts_query(&terms);
+
+        // Assert
+
+        assert_eq!(actual, r#""cell"* "red  blood" "AND"* "x"*"#);
+    }
 }
@@ -8,7 +8,11 @@ use tokio::sync::Mutex;
 
 use crate::{
     dto::update_settings_request::UpdateSettingsRequest,
-    model::settings::{Settings, Theme},
+    model::{
+        settings::{DEFAULT_TRASH_RETENTION_DAYS, Settings, Theme},
+        undo_history::UndoHistory,
+    },
+    service::trash_service,
     util::database_util::load_database,
 };
 
@@ -19,6 +23,8 @@ const SETTINGS_FILE_NAME: &str = "settings.dev.json";
 
 const DEFAULT_DATABASE_FILE_NAME: &str = "brainy.db";
 
+const MEDIA_DIRECTORY_SUFFIX: &str = "_media";
+
 pub fn init_settings() {
     let settings_dir = get_settings_dir();
     if !settings_dir.join(SETTINGS_FILE_NAME).exists() {
@@ -30,16 +36,23 @@ pub fn init_settings() {
                 .into(),
             Theme::FollowSystem,
             100f32,
+            DEFAULT_TRASH_RETENTION_DAYS,
         );
         write_settings_to_disk(&settings);
     }
 }
 
-pub async fn update_settings(new_settings: UpdateSettingsRequest, db_conn: &Mutex<DbConn>) {
+pub async fn update_settings(
+    new_settings: UpdateSettingsRequest,
+    db_conn: &Mutex<DbConn>,
+    undo_history: &Mutex<UndoHistory>,
+) -> Result<(), String> {
     let mut settings = get_settings();
     if let Some(database_location) = new_settings.database_location {
         let mut db_conn = db_conn.lock().await;
-        *db_conn = load_database(&database_location).await;
+        *db_conn = load_database(&database_location).await?;
+        // The history refers to the items of the previous database.
+        undo_history.lock().await.clear();
         settings.database_location = database_location;
     }
     if let Some(theme) = new_settings.theme {
@@ -48,7 +61,17 @@ pub async fn update_settings(new_settings: UpdateSettingsRequest, db_conn: &Mute
     if let Some(zoom_percentage) = new_settings.zoom_percentage {
         settings.zoom_percentage = zoom_percentage;
     }
+    if let Some(trash_retention_days) = new_settings.trash_retention_days {
+        settings.trash_retention_days = trash_retention_days;
+    }
+    let purged_trash_ids =
+        trash_service::purge_trash(&*db_conn.lock().await, settings.trash_retention_days).await?;
+    undo_history
+        .lock()
+        .await
+        .remove_moves_to_trash(&purged_trash_ids);
     write_settings_to_disk(&settings);
+    Ok(())
 }
 
 fn write_settings_to_disk(settings: &Settings) {
@@ -65,6 +88,22 @@ pub fn get_settings() -> Settings {
     serde_json::from_reader(file).expect("Cannot parse settings!")
 }
 
+/// Returns the directory next to the database where its media files are
+/// stored, e.g. `brainy_media` for `brainy.db`. Every database has its own
+/// directory, so that unused media is not shared between them.
+pub fn get_media_directory() -> PathBuf {
+    let database_location = PathBuf::from(get_settings().database_location);
+    let database_name = database_location
+        .file_stem()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let media_directory_name = format!("{database_name}{MEDIA_DIRECTORY_SUFFIX}");
+    match database_location.parent() {
+        Some(parent) => parent.join(media_directory_name),
+        None => PathBuf::from(media_directory_name),
+    }
+}
+
 fn get_settings_dir() -> PathBuf {
     let dir_path = dirs::config_dir()
         .expect("No settings directory is found on your system!")
@@ -0,0 +1,558 @@
+use std::collections::HashMap;
+
+use sea_orm::{
+    DbConn, Statement, Value,
+    entity::*,
+    query::*,
+    sea_query::{Expr, OnConflict, Query},
+};
+
+use crate::{
+    dto::tag_with_cells_count::TagWithCellsCount,
+    entity::{cell_tag, tag},
+};
+
+/// Separates the levels of hierarchical tags like `lang::spanish::verbs`.
+pub const TAG_SEPARATOR: &str = "::";
+
+/// Returns the used tags sorted by name.
+pub async fn get_tags(db_conn: &DbConn) -> Result<Vec<TagWithCellsCount>, String> {
+    let result = tag::Entity::find()
+        .select_only()
+        .column(tag::Column::Id)
+        .column(tag::Column::Name)
+        .column_as(cell_tag::Column::CellId.count(), "cells_count")
+        .join(JoinType::InnerJoin, tag::Relation::CellTag.def())
+        .group_by(tag::Column::Id)
+        .order_by_asc(tag::Column::Name)
+        .into_tuple::<(i32, String, i32)>()
+        .all(db_conn)
+        .await;
+    match result {
+        Ok(rows) => Ok(rows
+            .into_iter()
+            .map(|(id, name, cells_count)| TagWithCellsCount {
+                id,
+                name,
+                cells_count,
+            })
+            .collect()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Returns the tag names of each cell by cell id, sorted by name.
+pub async fn get_tags_for_cells(
+    db_conn: &DbConn,
+    cell_ids: Vec<i32>,
+) -> Result<HashMap<i32, Vec<String>>, String> {
+    let result = cell_tag::Entity::find()
+        .select_only()
+        .column(cell_tag::Column::CellId)
+        .column(tag::Column::Name)
+        .join(JoinType::InnerJoin, cell_tag::Relation::Tag.def())
+        .filter(cell_tag::Column::CellId.is_in(cell_ids))
+        .order_by_asc(tag::Column::Name)
+        .into_tuple::<(i32, String)>()
+        .all(db_conn)
+        .await;
+    let rows = match result {
+        Ok(rows) => rows,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let mut cell_tags: HashMap<i32, Vec<String>> = HashMap::new();
+    for (cell_id, name) in rows {
+        cell_tags.entry(cell_id).or_default().push(name);
+    }
+    Ok(cell_tags)
+}
+
+/// Adds the tag to the cells, the tag is created when it does not exist.
+pub async fn add_tag(db_conn: &DbConn, cell_ids: Vec<i32>, name: &str) -> Result<(), String> {
+    let name = normalize_tag_name(name)?;
+
+    let txn = match db_conn.begin().await {
+        Ok(txn) => txn,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let tag_id = get_or_create_tag_id(&txn, &name).await?;
+    for cell_id in cell_ids {
+        insert_cell_tag(&txn, cell_id, tag_id).await?;
+    }
+
+    let result = txn.commit().await;
+    match result {
+        Ok(_) => Ok(()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Adds the tags to a cell, used when importing.
+pub async fn add_tags_no_transaction(
+    db_conn: &impl ConnectionTrait,
+    cell_id: i32,
+    names: &[String],
+) -> Result<(), String> {
+    for name in names {
+        let tag_id = get_or_create_tag_id(db_conn, &normalize_tag_name(name)?).await?;
+        insert_cell_tag(db_conn, cell_id, tag_id).await?;
+    }
+    Ok(())
+}
+
+/// Removes the tag from the cells, the tag is deleted when no cell has it
+/// anymore.
+pub async fn remove_tag(db_conn: &DbConn, cell_ids: Vec<i32>, name: &str) -> Result<(), String> {
+    let tag = get_tag_by_name(db_conn, name.trim()).await?;
+
+    let txn = match db_conn.begin().await {
+        Ok(txn) => txn,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let result = cell_tag::Entity::delete_many()
+        .filter(cell_tag::Column::TagId.eq(tag.id))
+        .filter(cell_tag::Column::CellId.is_in(cell_ids))
+        .exec(&txn)
+        .await;
+    if let Err(err) = result {
+        return Err(err.to_string());
+    }
+    delete_unused_tags(&txn).await?;
+
+    let result = txn.commit().await;
+    match result {
+        Ok(_) => Ok(()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Renames the tag together with its child tags, e.g. renaming `lang` to
+/// `languages` renames `lang::spanish` to `languages::spanish`.
+pub async fn rename_tag(db_conn: &DbConn, name: &str, new_name: &str) -> Result<(), String> {
+    move_tags(db_conn, name, new_name, false).await
+}
+
+/// Moves the cells of the tag and of its child tags to the other tag and
+/// deletes them, e.g. merging `es` into `lang::spanish` moves the cells of
+/// `es::verbs` to `lang::spanish::verbs`.
+pub async fn merge_tags(db_conn: &DbConn, name: &str, into_name: &str) -> Result<(), String> {
+    move_tags(db_conn, name, into_name, true).await
+}
+
+async fn move_tags(
+    db_conn: &DbConn,
+    name: &str,
+    new_name: &str,
+    merge: bool,
+) -> Result<(), String> {
+    let name = name.trim();
+    let new_name = normalize_tag_name(new_name)?;
+    if new_name == name || new_name.starts_with(&format!("{name}{TAG_SEPARATOR}")) {
+        return Err("A tag cannot be moved into itself!".into());
+    }
+
+    let txn = match db_conn.begin().await {
+        Ok(txn) => txn,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let tags = get_tag_with_descendants(&txn, name).await?;
+    if tags.is_empty() {
+        return Err(format!("The tag \"{name}\" does not exist!"));
+    }
+    for tag in tags {
+        let target_name = format!("{new_name}{}", &tag.name[name.len()..]);
+        match find_tag_by_name(&txn, &target_name).await? {
+            None => {
+                let result = tag::Entity::update(tag::ActiveModel {
+                    id: Set(tag.id),
+                    name: Set(target_name),
+                })
+                .exec(&txn)
+                .await;
+                if let Err(err) = result {
+                    return Err(err.to_string());
+                }
+            }
+            Some(target) if merge => {
+                let result = txn
+                    .execute(Statement::from_sql_and_values(
+                        txn.get_database_backend(),
+                        r#"INSERT OR IGNORE INTO "cell_tag" ("cell_id", "tag_id")
+                        SELECT "cell_id", ? FROM "cell_tag" WHERE "tag_id" = ?"#,
+                        [target.id.into(), tag.id.into()],
+                    ))
+                    .await;
+                if let Err(err) = result {
+                    return Err(err.to_string());
+                }
+                let result = tag::Entity::delete_by_id(tag.id).exec(&txn).await;
+                if let Err(err) = result {
+                    return Err(err.to_string());
+                }
+            }
+            Some(_) => {
+                return Err(format!(
+                    "The tag \"{target_name}\" exists already, merge the tags instead!"
+                ));
+            }
+        }
+    }
+
+    let result = txn.commit().await;
+    match result {
+        Ok(_) => Ok(()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Returns the tags of the cells and the tags themselves, used to restore
+/// deleted cells with their tags.
+pub async fn get_cell_tags(
+    db_conn: &impl ConnectionTrait,
+    cell_ids: Vec<i32>,
+) -> Result<(Vec<cell_tag::Model>, Vec<tag::Model>), String> {
+    let cell_tags = match cell_tag::Entity::find()
+        .filter(cell_tag::Column::CellId.is_in(cell_ids))
+        .all(db_conn)
+        .await
+    {
+        Ok(cell_tags) => cell_tags,
+        Err(err) => return Err(err.to_string()),
+    };
+    let tag_ids: Vec<i32> = cell_tags.iter().map(|cell_tag| cell_tag.tag_id).collect();
+    let tags = match tag::Entity::find()
+        .filter(tag::Column::Id.is_in(tag_ids))
+        .all(db_conn)
+        .await
+    {
+        Ok(tags) => tags,
+        Err(err) => return Err(err.to_string()),
+    };
+    Ok((cell_tags, tags))
+}
+
+/// Inserts the tags of restored cells. Tags are looked up by name as they
+/// may have been renamed, merged or deleted in the meantime.
+pub async fn restore_cell_tags(
+    db_conn: &impl ConnectionTrait,
+    cell_tags: &[cell_tag::Model],
+    tags: &[tag::Model],
+) -> Result<(), String> {
+    let tag_names: HashMap<i32, &str> =
+        tags.iter().map(|tag| (tag.id, tag.name.as_str())).collect();
+    for cell_tag in cell_tags {
+        if let Some(name) = tag_names.get(&cell_tag.tag_id) {
+            let tag_id = get_or_create_tag_id(db_conn, name).await?;
+            insert_cell_tag(db_conn, cell_tag.cell_id, tag_id).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Trims the name and checks that it has no whitespace and no empty level.
+pub fn normalize_tag_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("The tag name cannot be empty!".into());
+    }
+    if name.chars().any(char::is_whitespace) {
+        return Err(format!("The tag \"{name}\" cannot contain spaces!"));
+    }
+    if name.split(TAG_SEPARATOR).any(str::is_empty) {
+        return Err(format!("The tag \"{name}\" has an empty level!"));
+    }
+    Ok(name.to_string())
+}
+
+async fn get_or_create_tag_id(db_conn: &impl ConnectionTrait, name: &str) -> Result<i32, String> {
+    if let Some(tag) = find_tag_by_name(db_conn, name).await? {
+        return Ok(tag.id);
+    }
+
+    let active_model = tag::ActiveModel {
+        name: Set(name.to_string()),
+        ..Default::default()
+    };
+    match tag::Entity::insert(active_model).exec(db_conn).await {
+        Ok(insert_result) => Ok(insert_result.last_insert_id),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+async fn insert_cell_tag(
+    db_conn: &impl ConnectionTrait,
+    cell_id: i32,
+    tag_id: i32,
+) -> Result<(), String> {
+    let active_model = cell_tag::ActiveModel {
+        cell_id: Set(cell_id),
+        tag_id: Set(tag_id),
+    };
+    let result = cell_tag::Entity::insert(active_model)
+        .on_conflict(
+            OnConflict::columns([cell_tag::Column::CellId, cell_tag::Column::TagId])
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(db_conn)
+        .await;
+    match result {
+        Ok(_) => Ok(()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+async fn find_tag_by_name(
+    db_conn: &impl ConnectionTrait,
+    name: &str,
+) -> Result<Option<tag::Model>, String> {
+    let result = tag::Entity::find()
+        .filter(tag::Column::Name.eq(name))
+        .one(db_conn)
+        .await;
+    match result {
+        Ok(tag) => Ok(tag),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+async fn get_tag_by_name(db_conn: &impl ConnectionTrait, name: &str) -> Result<tag::Model, String> {
+    match find_tag_by_name(db_conn, name).await? {
+        Some(tag) => Ok(tag),
+        None => Err(format!("The tag \"{name}\" does not exist!")),
+    }
+}
+
+/// Matches the tag and its descendants. The prefix is compared exactly, as
+/// `LIKE` ignores the case and treats `_` and `%` as wildcards.
+pub fn get_tag_with_descendants_condition(name: &str) -> Condition {
+    let prefix = format!("{name}{TAG_SEPARATOR}");
+    Condition::any()
+        .add(tag::Column::Name.eq(name))
+        .add(Expr::cust_with_values(
+            r#"substr("tag"."name", 1, ?) = ?"#,
+            [
+                Value::from(prefix.chars().count() as i64),
+                Value::from(prefix),
+            ],
+        ))
+}
+
+async fn get_tag_with_descendants(
+    db_conn: &impl ConnectionTrait,
+    name: &str,
+) -> Result<Vec<tag::Model>, String> {
+    let result = tag::Entity::find()
+        .filter(get_tag_with_descendants_condition(name))
+        .order_by_asc(tag::Column::Name)
+        .all(db_conn)
+        .await;
+    match result {
+        Ok(tags) => Ok(tags),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+async fn delete_unused_tags(db_conn: &impl ConnectionTrait) -> Result<(), String> {
+    let result = tag::Entity::delete_many()
+        .filter(
+            Expr::col(tag::Column::Id).not_in_subquery(
+                Query::select()
+                    .column(cell_tag::Column::TagId)
+                    .from(cell_tag::Entity)
+                    .to_owned(),
+            ),
+        )
+        .exec(db_conn)
+        .await;
+    match result {
+        Ok(_) => Ok(()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        entity::cell::CellType,
+        service::{
+            cell_service,
+            tests::{create_file, get_db},
+        },
+    };
+
+    use super::*;
+
+    async fn create_note_cells(db_conn: &DbConn, count: i32) -> Vec<i32> {
+        let file_id = create_file(db_conn, "file").await;
+        let mut cell_ids = vec![];
+        for index in 0..count {
+            cell_ids.push(
+                cell_service::create_cell(db_conn, file_id, "note", CellType::Note, index)
+                    .await
+                    .unwrap(),
+            );
+        }
+        cell_ids
+    }
+
+    fn get_names(tags: Vec<TagWithCellsCount>) -> Vec<(String, i32)> {
+        tags.into_iter()
+            .map(|tag| (tag.name, tag.cells_count))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn add_tag_and_remove_tag_updated_cell_tags() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let cell_ids = create_note_cells(&db_conn, 2).await;
+
+        // Act
+
+        add_tag(&db_conn, cell_ids.clone(), " lang::spanish ")
+            .await
+            .unwrap();
+        add_tag(&db_conn, cell_ids.clone(), "lang::spanish")
+            .await
+            .unwrap();
+        add_tag(&db_conn, vec![cell_ids[0]], "verbs").await.unwrap();
+        remove_tag(&db_conn, vec![cell_ids[0]], "verbs")
+            .await
+            .unwrap();
+
+        // Assert
+
+        assert_eq!(
+            get_names(get_tags(&db_conn).await.unwrap()),
+            vec![("lang::spanish".into(), 2)]
+        );
+        let cell_tags = get_tags_for_cells(&db_conn, cell_ids.clone())
+            .await
+            .unwrap();
+        assert_eq!(cell_tags[&cell_ids[0]], vec!["lang::spanish"]);
+        assert_eq!(tag::Entity::find().count(&db_conn).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn add_tag_invalid_name_returned_error() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let cell_ids = create_note_cells(&db_conn, 1).await;
+
+        // Act
+
+        let actual = add_tag(&db_conn, cell_ids, "lang::::verbs").await;
+
+        // Assert
+
+        assert_eq!(
+            actual,
+            Err("The tag \"lang::::verbs\" has an empty level!".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn rename_tag_parent_tag_renamed_child_tags() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let cell_ids = create_note_cells(&db_conn, 2).await;
+        add_tag(&db_conn, vec![cell_ids[0]], "lang").await.unwrap();
+        add_tag(&db_conn, vec![cell_ids[1]], "lang::spanish")
+            .await
+            .unwrap();
+        add_tag(&db_conn, vec![cell_ids[1]], "language")
+            .await
+            .unwrap();
+
+        // Act
+
+        rename_tag(&db_conn, "lang", "languages").await.unwrap();
+
+        // Assert
+
+        assert_eq!(
+            get_names(get_tags(&db_conn).await.unwrap()),
+            vec![
+                ("language".into(), 1),
+                ("languages".into(), 1),
+                ("languages::spanish".into(), 1)
+            ]
+        );
+        let actual = rename_tag(&db_conn, "language", "languages").await;
+        assert_eq!(
+            actual,
+            Err("The tag \"languages\" exists already, merge the tags instead!".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn rename_tag_mixed_case_and_wildcard_siblings_not_renamed() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let cell_ids = create_note_cells(&db_conn, 3).await;
+        add_tag(&db_conn, vec![cell_ids[0]], "my_tag::child")
+            .await
+            .unwrap();
+        add_tag(&db_conn, vec![cell_ids[1]], "My_Tag::child")
+            .await
+            .unwrap();
+        add_tag(&db_conn, vec![cell_ids[2]], "myxtag::child")
+            .await
+            .unwrap();
+
+        // Act
+
+        rename_tag(&db_conn, "my_tag", "renamed").await.unwrap();
+
+        // Assert
+
+        assert_eq!(
+            get_names(get_tags(&db_conn).await.unwrap()),
+            vec![
+                ("My_Tag::child".into(), 1),
+                ("myxtag::child".into(), 1),
+                ("renamed::child".into(), 1)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn merge_tags_existing_tags_merged_cells() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let cell_ids = create_note_cells(&db_conn, 3).await;
+        add_tag(&db_conn, vec![cell_ids[0], cell_ids[1]], "es")
+            .await
+            .unwrap();
+        add_tag(&db_conn, vec![cell_ids[2]], "es::verbs")
+            .await
+            .unwrap();
+        add_tag(&db_conn, vec![cell_ids[1]], "lang::spanish")
+            .await
+            .unwrap();
+
+        // Act
+
+        merge_tags(&db_conn, "es", "lang::spanish").await.unwrap();
+
+        // Assert
+
+        assert_eq!(
+            get_names(get_tags(&db_conn).await.unwrap()),
+            vec![
+                ("lang::spanish".into(), 2),
+                ("lang::spanish::verbs".into(), 1)
+            ]
+        );
+    }
+}
@@ -0,0 +1,474 @@
+use chrono::{Duration, Utc};
+use sea_orm::{DbConn, entity::*, query::*};
+
+use crate::{
+    dto::trash_item::TrashItem,
+    entity::{cell, file, repetition, review_log, scheduler_parameters, trash},
+    model::deleted_items::DeletedItems,
+};
+
+use super::{file_service, tag_service};
+
+/// Deletes the files together with their cells, repetitions and review logs,
+/// and keeps a copy of them in the trash. The first file is the one the user
+/// deleted, the others are its descendants.
+pub async fn move_to_trash(db_conn: &DbConn, files: Vec<file::Model>) -> Result<i32, String> {
+    let root = match files.first() {
+        Some(root) => root.clone(),
+        None => return Err("Nothing to delete!".into()),
+    };
+
+    let txn = match db_conn.begin().await {
+        Ok(txn) => txn,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let file_ids: Vec<i32> = files.iter().map(|file| file.id).collect();
+    let deleted = get_deleted_items(&txn, files).await?;
+    let active_model = trash::ActiveModel {
+        path: Set(root.path),
+        is_folder: Set(root.is_folder),
+        deleted_at: Set(Utc::now()),
+        items: Set(serde_json::to_string(&deleted).unwrap()),
+        ..Default::default()
+    };
+    let trash_id = match trash::Entity::insert(active_model).exec(&txn).await {
+        Ok(insert_result) => insert_result.last_insert_id,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let result = file::Entity::delete_many()
+        .filter(file::Column::Id.is_in(file_ids))
+        .exec(&txn)
+        .await;
+    if let Err(err) = result {
+        return Err(err.to_string());
+    }
+
+    let result = txn.commit().await;
+    match result {
+        Ok(_) => Ok(trash_id),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+async fn get_deleted_items(
+    db_conn: &impl ConnectionTrait,
+    files: Vec<file::Model>,
+) -> Result<DeletedItems, String> {
+    let file_ids: Vec<i32> = files.iter().map(|file| file.id).collect();
+
+    let cells = match cell::Entity::find()
+        .filter(cell::Column::FileId.is_in(file_ids.clone()))
+        .all(db_conn)
+        .await
+    {
+        Ok(cells) => cells,
+        Err(err) => return Err(err.to_string()),
+    };
+    let repetitions = match repetition::Entity::find()
+        .filter(repetition::Column::FileId.is_in(file_ids.clone()))
+        .all(db_conn)
+        .await
+    {
+        Ok(repetitions) => repetitions,
+        Err(err) => return Err(err.to_string()),
+    };
+    let review_logs = match review_log::Entity::find()
+        .filter(review_log::Column::FileId.is_in(file_ids.clone()))
+        .all(db_conn)
+        .await
+    {
+        Ok(review_logs) => review_logs,
+        Err(err) => return Err(err.to_string()),
+    };
+    let scheduler_parameters = match scheduler_parameters::Entity::find()
+        .filter(scheduler_parameters::Column::FolderId.is_in(file_ids))
+        .all(db_conn)
+        .await
+    {
+        Ok(scheduler_parameters) => scheduler_parameters,
+        Err(err) => return Err(err.to_string()),
+    };
+    let (cell_tags, tags) =
+        tag_service::get_cell_tags(db_conn, cells.iter().map(|cell| cell.id).collect()).await?;
+
+    Ok(DeletedItems {
+        files,
+        cells,
+        repetitions,
+        review_logs,
+        scheduler_parameters,
+        cell_tags,
+        tags,
+    })
+}
+
+pub async fn list_trash(db_conn: &DbConn) -> Result<Vec<TrashItem>, String> {
+    let result = trash::Entity::find()
+        .order_by_desc(trash::Column::DeletedAt)
+        .order_by_desc(trash::Column::Id)
+        .all(db_conn)
+        .await;
+    match result {
+        Ok(trash_items) => Ok(trash_items
+            .into_iter()
+            .map(|trash_item| TrashItem {
+                id: trash_item.id,
+                path: trash_item.path,
+                is_folder: trash_item.is_folder,
+                deleted_at: trash_item.deleted_at,
+            })
+            .collect()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Puts the deleted items back with their original ids and returns the path
+/// of the restored file or folder. A number is appended to the name when the
+/// path was taken in the meantime, and missing parent folders are recreated.
+pub async fn restore_from_trash(db_conn: &DbConn, trash_id: i32) -> Result<String, String> {
+    let trash_item = match trash::Entity::find_by_id(trash_id).one(db_conn).await {
+        Ok(Some(trash_item)) => trash_item,
+        Ok(None) => return Err("Trash item not found!".into()),
+        Err(err) => return Err(err.to_string()),
+    };
+    let mut deleted: DeletedItems = match serde_json::from_str(&trash_item.items) {
+        Ok(deleted) => deleted,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let txn = match db_conn.begin().await {
+        Ok(txn) => txn,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let path = get_available_path(&txn, &trash_item.path, trash_item.is_folder).await?;
+    for file in deleted.files.iter_mut() {
+        file.path = path.clone() + &file.path[trash_item.path.len()..];
+    }
+    if let Some(index) = path.rfind('/') {
+        file_service::create_folder_recursively(&txn, &path[..index]).await?;
+    }
+    restore_items(&txn, &deleted).await?;
+
+    let result = trash::Entity::delete_by_id(trash_id).exec(&txn).await;
+    if let Err(err) = result {
+        return Err(err.to_string());
+    }
+
+    let result = txn.commit().await;
+    match result {
+        Ok(_) => Ok(path),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+async fn get_available_path(
+    db_conn: &impl ConnectionTrait,
+    path: &str,
+    is_folder: bool,
+) -> Result<String, String> {
+    let mut available_path = path.to_string();
+    let mut number = 1;
+    loop {
+        let result = file::Entity::find()
+            .filter(file::Column::Path.eq(&available_path))
+            .filter(file::Column::IsFolder.eq(is_folder))
+            .count(db_conn)
+            .await;
+        match result {
+            Ok(0) => return Ok(available_path),
+            Ok(_) => {}
+            Err(err) => return Err(err.to_string()),
+        }
+        available_path = format!("{path} ({number})");
+        number += 1;
+    }
+}
+
+/// Inserts the deleted items back with their original ids.
+pub async fn restore_items(
+    db_conn: &impl ConnectionTrait,
+    deleted: &DeletedItems,
+) -> Result<(), String> {
+    for file in &deleted.files {
+        insert(db_conn, file.clone().into_active_model()).await?;
+    }
+    for cell in &deleted.cells {
+        insert(db_conn, cell.clone().into_active_model()).await?;
+    }
+    for repetition in &deleted.repetitions {
+        insert(db_conn, repetition.clone().into_active_model()).await?;
+    }
+    for review_log in &deleted.review_logs {
+        insert(db_conn, review_log.clone().into_active_model()).await?;
+    }
+    for scheduler_parameters in &deleted.scheduler_parameters {
+        insert(db_conn, scheduler_parameters.clone().into_active_model()).await?;
+    }
+    tag_service::restore_cell_tags(db_conn, &deleted.cell_tags, &deleted.tags).await?;
+    Ok(())
+}
+
+async fn insert<A>(db_conn: &impl ConnectionTrait, active_model: A) -> Result<(), String>
+where
+    A: ActiveModelTrait + Send,
+    <A::Entity as EntityTrait>::Model: IntoActiveModel<A>,
+{
+    let result = A::Entity::insert(active_model.reset_all())
+        .exec_without_returning(db_conn)
+        .await;
+    match result {
+        Ok(_) => Ok(()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+pub async fn empty_trash(db_conn: &DbConn) -> Result<(), String> {
+    let result = trash::Entity::delete_many().exec(db_conn).await;
+    match result {
+        Ok(_) => Ok(()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Permanently deletes the items that have been in the trash for more than
+/// `retention_days`, nothing is purged when it is 0. Returns the ids of the
+/// purged items.
+pub async fn purge_trash(db_conn: &DbConn, retention_days: u32) -> Result<Vec<i32>, String> {
+    if retention_days == 0 {
+        return Ok(vec![]);
+    }
+
+    let result = trash::Entity::find()
+        .select_only()
+        .column(trash::Column::Id)
+        .filter(trash::Column::DeletedAt.lt(Utc::now() - Duration::days(retention_days as i64)))
+        .into_tuple::<i32>()
+        .all(db_conn)
+        .await;
+    let trash_ids = match result {
+        Ok(trash_ids) => trash_ids,
+        Err(err) => return Err(err.to_string()),
+    };
+    let result = trash::Entity::delete_many()
+        .filter(trash::Column::Id.is_in(trash_ids.clone()))
+        .exec(db_conn)
+        .await;
+    match result {
+        Ok(_) => Ok(trash_ids),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        entity::cell::CellType,
+        service::{
+            cell_service,
+            tests::{create_file, create_file_cell, get_db},
+        },
+    };
+
+    use super::*;
+
+    async fn create_flash_card(db_conn: &DbConn, file_id: i32) {
+        cell_service::create_cell(
+            db_conn,
+            file_id,
+            r#"{"question":"question","answer":"answer"}"#,
+            CellType::FlashCard,
+            1,
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn delete_folder_valid_input_moved_to_trash() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let folder_id = file_service::create_folder(&db_conn, "folder".into())
+            .await
+            .unwrap();
+        create_file_cell(&db_conn, "folder/file").await;
+
+        // Act
+
+        let trash_id = file_service::delete_folder(&db_conn, folder_id)
+            .await
+            .unwrap();
+
+        // Assert
+
+        let actual = list_trash(&db_conn).await.unwrap();
+        assert_eq!(actual.len(), 1);
+        assert_eq!(actual[0].id, trash_id);
+        assert_eq!(actual[0].path, "folder");
+        assert!(actual[0].is_folder);
+        let trash_item = trash::Entity::find_by_id(trash_id)
+            .one(&db_conn)
+            .await
+            .unwrap()
+            .unwrap();
+        let deleted: DeletedItems = serde_json::from_str(&trash_item.items).unwrap();
+        assert_eq!(deleted.files.len(), 2);
+        assert_eq!(deleted.cells.len(), 1);
+        assert!(file::Entity::find().all(&db_conn).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn restore_from_trash_deleted_folder_restored_items() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let (file_id, _) = create_file_cell(&db_conn, "folder/file").await;
+        create_flash_card(&db_conn, file_id).await;
+        let files = file::Entity::find().all(&db_conn).await.unwrap();
+        let cells = cell::Entity::find().all(&db_conn).await.unwrap();
+        let repetitions = repetition::Entity::find().all(&db_conn).await.unwrap();
+        let folder_id = files.iter().find(|file| file.is_folder).unwrap().id;
+        let trash_id = file_service::delete_folder(&db_conn, folder_id)
+            .await
+            .unwrap();
+
+        // Act
+
+        let actual = restore_from_trash(&db_conn, trash_id).await.unwrap();
+
+        // Assert
+
+        assert_eq!(actual, "folder");
+        assert_eq!(file::Entity::find().all(&db_conn).await.unwrap(), files);
+        assert_eq!(cell::Entity::find().all(&db_conn).await.unwrap(), cells);
+        assert_eq!(
+            repetition::Entity::find().all(&db_conn).await.unwrap(),
+            repetitions
+        );
+        assert!(list_trash(&db_conn).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn restore_from_trash_path_taken_renamed_folder() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let folder_id = file_service::create_folder(&db_conn, "parent/folder".into())
+            .await
+            .unwrap();
+        create_file(&db_conn, "parent/folder/file").await;
+        let trash_id = file_service::delete_folder(&db_conn, folder_id)
+            .await
+            .unwrap();
+        file_service::create_folder(&db_conn, "parent/folder".into())
+            .await
+            .unwrap();
+
+        // Act
+
+        let actual = restore_from_trash(&db_conn, trash_id).await.unwrap();
+
+        // Assert
+
+        assert_eq!(actual, "parent/folder (1)");
+        let mut paths: Vec<String> = file::Entity::find()
+            .all(&db_conn)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|file| file.path)
+            .collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![
+                "parent",
+                "parent/folder",
+                "parent/folder (1)",
+                "parent/folder (1)/file"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn restore_from_trash_parent_deleted_recreated_parent() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let file_id = create_file(&db_conn, "parent/file").await;
+        let trash_id = file_service::delete_file(&db_conn, file_id).await.unwrap();
+        let parent = file::Entity::find().one(&db_conn).await.unwrap().unwrap();
+        file_service::delete_folder(&db_conn, parent.id)
+            .await
+            .unwrap();
+
+        // Act
+
+        let actual = restore_from_trash(&db_conn, trash_id).await.unwrap();
+
+        // Assert
+
+        assert_eq!(actual, "parent/file");
+        let files = file::Entity::find().all(&db_conn).await.unwrap();
+        assert_eq!(files.len(), 2);
+        assert!(
+            files
+                .iter()
+                .any(|file| file.path == "parent" && file.is_folder)
+        );
+    }
+
+    #[tokio::test]
+    async fn purge_trash_old_items_deleted_only_old_items() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let old_file_id = create_file(&db_conn, "old file").await;
+        let old_trash_id = file_service::delete_file(&db_conn, old_file_id)
+            .await
+            .unwrap();
+        trash::ActiveModel {
+            id: Set(old_trash_id),
+            deleted_at: Set(Utc::now() - Duration::days(31)),
+            ..Default::default()
+        }
+        .update(&db_conn)
+        .await
+        .unwrap();
+        let new_file_id = create_file(&db_conn, "new file").await;
+        file_service::delete_file(&db_conn, new_file_id)
+            .await
+            .unwrap();
+
+        // Act
+
+        let purged_trash_ids = purge_trash(&db_conn, 30).await.unwrap();
+
+        // Assert
+
+        assert_eq!(purged_trash_ids, vec![old_trash_id]);
+        let actual = list_trash(&db_conn).await.unwrap();
+        assert_eq!(actual.len(), 1);
+        assert_eq!(actual[0].path, "new file");
+    }
+
+    #[tokio::test]
+    async fn empty_trash_valid_input_deleted_all_items() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let file_id = create_file(&db_conn, "file").await;
+        file_service::delete_file(&db_conn, file_id).await.unwrap();
+
+        // Act
+
+        empty_trash(&db_conn).await.unwrap();
+
+        // Assert
+
+        assert!(list_trash(&db_conn).await.unwrap().is_empty());
+    }
+}
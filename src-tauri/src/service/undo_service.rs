@@ -0,0 +1,990 @@
+use sea_orm::{DbConn, entity::*, query::*};
+
+use crate::{
+    dto::{undo_redo_state::UndoRedoState, update_cell_request::UpdateCellRequest},
+    entity::{cell, cell_tag, repetition, review_log, trash},
+    model::{
+        deleted_items::DeletedItems,
+        rating::Rating,
+        undo_history::{UndoAction, UndoHistory},
+    },
+};
+
+use super::{
+    cell_service, duplicate_service, file_service, repetition_service, tag_service, trash_service,
+};
+
+pub fn get_undo_redo_state(undo_history: &UndoHistory) -> UndoRedoState {
+    UndoRedoState {
+        undo_description: undo_history.peek_undo().map(UndoAction::describe),
+        redo_description: undo_history.peek_redo().map(UndoAction::describe),
+    }
+}
+
+pub async fn review_repetition(
+    db_conn: &DbConn,
+    undo_history: &mut UndoHistory,
+    repetition_id: i32,
+    rating: Rating,
+    review_duration: Option<i64>,
+) -> Result<repetition::Model, String> {
+    let previous = repetition_service::get_repetition_by_id(db_conn, repetition_id).await?;
+    let next =
+        repetition_service::review_repetition(db_conn, repetition_id, rating, review_duration)
+            .await?;
+    push_review(db_conn, undo_history, previous).await?;
+    Ok(next)
+}
+
+async fn push_review(
+    db_conn: &DbConn,
+    undo_history: &mut UndoHistory,
+    previous: repetition::Model,
+) -> Result<(), String> {
+    let next = repetition_service::get_repetition_by_id(db_conn, previous.id).await?;
+    let result = review_log::Entity::find()
+        .filter(review_log::Column::RepetitionId.eq(previous.id))
+        .order_by_desc(review_log::Column::Id)
+        .one(db_conn)
+        .await;
+    let review_log = match result {
+        Ok(Some(review_log)) => review_log,
+        Ok(None) => return Err("Review log not found!".into()),
+        Err(err) => return Err(err.to_string()),
+    };
+
+    undo_history.push(UndoAction::UpdateRepetition {
+        previous,
+        next,
+        review_log,
+    });
+    Ok(())
+}
+
+/// Resets the repetitions of the cell, its reviews cannot be undone anymore.
+pub async fn reset_repetitions_for_cell(
+    db_conn: &DbConn,
+    undo_history: &mut UndoHistory,
+    cell_id: i32,
+) -> Result<(), String> {
+    repetition_service::reset_repetitions_for_cell(db_conn, cell_id).await?;
+    undo_history.remove_reviews_of_cells(&[cell_id]);
+    Ok(())
+}
+
+/// Updates the contents of the cells, their reviews cannot be undone anymore
+/// as the edit may have changed their repetitions.
+pub async fn update_cells_contents(
+    db_conn: &DbConn,
+    undo_history: &mut UndoHistory,
+    requests: Vec<UpdateCellRequest>,
+) -> Result<(), String> {
+    let cell_ids: Vec<i32> = requests.iter().map(|request| request.cell_id).collect();
+    cell_service::update_cells_contents(db_conn, requests).await?;
+    undo_history.remove_reviews_of_cells(&cell_ids);
+    Ok(())
+}
+
+pub async fn delete_cell(
+    db_conn: &DbConn,
+    undo_history: &mut UndoHistory,
+    cell_id: i32,
+) -> Result<(), String> {
+    let deleted = get_cell_items(db_conn, &[cell_id]).await?;
+    cell_service::delete_cell(db_conn, cell_id).await?;
+    undo_history.push(UndoAction::DeleteCell(deleted));
+    Ok(())
+}
+
+/// Keeps the most reviewed of the duplicate cells and deletes the others,
+/// returns the id of the kept cell.
+pub async fn merge_duplicate_cells(
+    db_conn: &DbConn,
+    undo_history: &mut UndoHistory,
+    cell_ids: Vec<i32>,
+) -> Result<i32, String> {
+    duplicate_service::check_mergeable(db_conn, &cell_ids).await?;
+
+    let kept_cell_id = duplicate_service::get_most_reviewed_cell_id(db_conn, &cell_ids).await?;
+    let merged_cell_ids: Vec<i32> = cell_ids
+        .into_iter()
+        .filter(|cell_id| *cell_id != kept_cell_id)
+        .collect();
+    let deleted = merge_cells(db_conn, kept_cell_id, &merged_cell_ids).await?;
+    undo_history.push(UndoAction::MergeCells {
+        kept_cell_id,
+        deleted,
+    });
+    Ok(kept_cell_id)
+}
+
+/// Adds the tags of the merged cells to the kept cell and deletes them.
+async fn merge_cells(
+    db_conn: &DbConn,
+    kept_cell_id: i32,
+    merged_cell_ids: &[i32],
+) -> Result<DeletedItems, String> {
+    let deleted = get_cell_items(db_conn, merged_cell_ids).await?;
+    let kept_cell_tags: Vec<cell_tag::Model> = deleted
+        .cell_tags
+        .iter()
+        .map(|cell_tag| cell_tag::Model {
+            cell_id: kept_cell_id,
+            tag_id: cell_tag.tag_id,
+        })
+        .collect();
+    tag_service::restore_cell_tags(db_conn, &kept_cell_tags, &deleted.tags).await?;
+    cell_service::delete_cells(db_conn, &deleted.cells).await?;
+    Ok(deleted)
+}
+
+pub async fn delete_file(
+    db_conn: &DbConn,
+    undo_history: &mut UndoHistory,
+    file_id: i32,
+) -> Result<(), String> {
+    let file = file_service::get_by_id(db_conn, file_id).await?;
+    let trash_id = file_service::delete_file(db_conn, file_id).await?;
+    undo_history.push(UndoAction::MoveToTrash {
+        file_id,
+        trash_id,
+        path: file.path,
+        is_folder: false,
+    });
+    Ok(())
+}
+
+pub async fn delete_folder(
+    db_conn: &DbConn,
+    undo_history: &mut UndoHistory,
+    folder_id: i32,
+) -> Result<(), String> {
+    let folder = file_service::get_by_id(db_conn, folder_id).await?;
+    let trash_id = file_service::delete_folder(db_conn, folder_id).await?;
+    undo_history.push(UndoAction::MoveToTrash {
+        file_id: folder_id,
+        trash_id,
+        path: folder.path,
+        is_folder: true,
+    });
+    Ok(())
+}
+
+/// Reverts the most recent action, it stays in the history when it cannot be
+/// reverted so that the user can fix the conflict and try again. An action
+/// whose repetition or trash item is gone is removed instead, as it can never
+/// be reverted.
+pub async fn undo(
+    db_conn: &DbConn,
+    undo_history: &mut UndoHistory,
+) -> Result<UndoRedoState, String> {
+    let action = match undo_history.peek_undo() {
+        Some(action) => action.clone(),
+        None => return Err("Nothing to undo!".into()),
+    };
+
+    let action = match action {
+        UndoAction::UpdateRepetition {
+            previous,
+            next,
+            review_log,
+        } => {
+            match repetition::Entity::find_by_id(previous.id)
+                .one(db_conn)
+                .await
+            {
+                Ok(Some(_)) => {}
+                Ok(None) => return remove_stale_undo(undo_history),
+                Err(err) => return Err(err.to_string()),
+            }
+            let txn = match db_conn.begin().await {
+                Ok(txn) => txn,
+                Err(err) => return Err(err.to_string()),
+            };
+            repetition_service::save_repetition(&txn, previous.clone()).await?;
+            if let Err(err) = review_log::Entity::delete_by_id(review_log.id)
+                .exec(&txn)
+                .await
+            {
+                return Err(err.to_string());
+            }
+            if let Err(err) = txn.commit().await {
+                return Err(err.to_string());
+            }
+            UndoAction::UpdateRepetition {
+                previous,
+                next,
+                review_log,
+            }
+        }
+        UndoAction::DeleteCell(deleted) => {
+            restore_cells(db_conn, &deleted).await?;
+            UndoAction::DeleteCell(deleted)
+        }
+        UndoAction::MergeCells {
+            kept_cell_id,
+            deleted,
+        } => {
+            restore_cells(db_conn, &deleted).await?;
+            UndoAction::MergeCells {
+                kept_cell_id,
+                deleted,
+            }
+        }
+        UndoAction::MoveToTrash {
+            file_id,
+            trash_id,
+            is_folder,
+            ..
+        } => {
+            match trash::Entity::find_by_id(trash_id).one(db_conn).await {
+                Ok(Some(_)) => {}
+                Ok(None) => return remove_stale_undo(undo_history),
+                Err(err) => return Err(err.to_string()),
+            }
+            // The path may change if it was taken in the meantime.
+            let path = trash_service::restore_from_trash(db_conn, trash_id).await?;
+            UndoAction::MoveToTrash {
+                file_id,
+                trash_id,
+                path,
+                is_folder,
+            }
+        }
+    };
+
+    undo_history.pop_undo();
+    undo_history.push_undone(action);
+    Ok(get_undo_redo_state(undo_history))
+}
+
+fn remove_stale_undo(undo_history: &mut UndoHistory) -> Result<UndoRedoState, String> {
+    let description = undo_history
+        .pop_undo()
+        .map(|action| action.describe())
+        .unwrap_or_default();
+    Err(format!(
+        "Cannot undo \"{description}\" anymore, it was removed from the history!"
+    ))
+}
+
+/// Applies again the most recently undone action.
+pub async fn redo(
+    db_conn: &DbConn,
+    undo_history: &mut UndoHistory,
+) -> Result<UndoRedoState, String> {
+    let action = match undo_history.peek_redo() {
+        Some(action) => action.clone(),
+        None => return Err("Nothing to redo!".into()),
+    };
+
+    // Deleted cells are snapshotted again as they may have changed since the
+    // undo.
+    let action = match action {
+        UndoAction::UpdateRepetition {
+            previous,
+            next,
+            review_log,
+        } => {
+            let txn = match db_conn.begin().await {
+                Ok(txn) => txn,
+                Err(err) => return Err(err.to_string()),
+            };
+            repetition_service::save_repetition(&txn, next.clone()).await?;
+            trash_service::restore_items(
+                &txn,
+                &DeletedItems {
+                    review_logs: vec![review_log.clone()],
+                    ..Default::default()
+                },
+            )
+            .await?;
+            if let Err(err) = txn.commit().await {
+                return Err(err.to_string());
+            }
+            UndoAction::UpdateRepetition {
+                previous,
+                next,
+                review_log,
+            }
+        }
+        UndoAction::DeleteCell(deleted) => {
+            let cell_id = deleted.cells[0].id;
+            let deleted = get_cell_items(db_conn, &[cell_id]).await?;
+            cell_service::delete_cell(db_conn, cell_id).await?;
+            UndoAction::DeleteCell(deleted)
+        }
+        UndoAction::MergeCells {
+            kept_cell_id,
+            deleted,
+        } => {
+            let cell_ids: Vec<i32> = deleted.cells.iter().map(|cell| cell.id).collect();
+            let deleted = merge_cells(db_conn, kept_cell_id, &cell_ids).await?;
+            UndoAction::MergeCells {
+                kept_cell_id,
+                deleted,
+            }
+        }
+        UndoAction::MoveToTrash {
+            file_id,
+            path,
+            is_folder,
+            ..
+        } => {
+            let trash_id = if is_folder {
+                file_service::delete_folder(db_conn, file_id).await?
+            } else {
+                file_service::delete_file(db_conn, file_id).await?
+            };
+            UndoAction::MoveToTrash {
+                file_id,
+                trash_id,
+                path,
+                is_folder,
+            }
+        }
+    };
+
+    undo_history.pop_redo();
+    undo_history.push_redone(action);
+    Ok(get_undo_redo_state(undo_history))
+}
+
+/// Makes space for the deleted cells and inserts them back, the cells are
+/// sorted by file and index so that each lands at its original index.
+async fn restore_cells(db_conn: &DbConn, deleted: &DeletedItems) -> Result<(), String> {
+    let txn = match db_conn.begin().await {
+        Ok(txn) => txn,
+        Err(err) => return Err(err.to_string()),
+    };
+    for cell in &deleted.cells {
+        cell_service::increase_cells_indices_starting_from(&txn, cell.file_id, cell.index, 1)
+            .await?;
+    }
+    trash_service::restore_items(&txn, deleted).await?;
+    match txn.commit().await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Returns the cells sorted by file and index with their repetitions and
+/// review logs.
+async fn get_cell_items(db_conn: &DbConn, cell_ids: &[i32]) -> Result<DeletedItems, String> {
+    let cells = match cell::Entity::find()
+        .filter(cell::Column::Id.is_in(cell_ids.to_vec()))
+        .order_by_asc(cell::Column::FileId)
+        .order_by_asc(cell::Column::Index)
+        .all(db_conn)
+        .await
+    {
+        Ok(cells) if cells.len() == cell_ids.len() => cells,
+        Ok(_) => return Err("Cell not found!".into()),
+        Err(err) => return Err(err.to_string()),
+    };
+    let repetitions = match repetition::Entity::find()
+        .filter(repetition::Column::CellId.is_in(cell_ids.to_vec()))
+        .all(db_conn)
+        .await
+    {
+        Ok(repetitions) => repetitions,
+        Err(err) => return Err(err.to_string()),
+    };
+    let review_logs = match review_log::Entity::find()
+        .filter(review_log::Column::CellId.is_in(cell_ids.to_vec()))
+        .all(db_conn)
+        .await
+    {
+        Ok(review_logs) => review_logs,
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let (cell_tags, tags) = tag_service::get_cell_tags(db_conn, cell_ids.to_vec()).await?;
+
+    Ok(DeletedItems {
+        cells,
+        repetitions,
+        review_logs,
+        cell_tags,
+        tags,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        entity::{cell, cell::CellType, repetition::State},
+        service::tests::{create_file_cell, create_file_cell_with_cell_type_and_content, get_db},
+    };
+
+    use super::*;
+
+    async fn get_flash_card_repetition(db_conn: &DbConn, file_name: &str) -> repetition::Model {
+        let (file_id, _) = create_file_cell_with_cell_type_and_content(
+            db_conn,
+            file_name,
+            CellType::FlashCard,
+            r#"{"question":"question","answer":"answer"}"#,
+        )
+        .await;
+        repetition_service::get_file_repetitions(db_conn, file_id)
+            .await
+            .unwrap()
+            .remove(0)
+    }
+
+    #[tokio::test]
+    async fn undo_review_restored_previous_repetition() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let mut undo_history = UndoHistory::default();
+        let repetition = get_flash_card_repetition(&db_conn, "file").await;
+        review_repetition(
+            &db_conn,
+            &mut undo_history,
+            repetition.id,
+            Rating::Good,
+            None,
+        )
+        .await
+        .unwrap();
+
+        // Act
+
+        let actual = undo(&db_conn, &mut undo_history).await.unwrap();
+
+        // Assert
+
+        assert_eq!(
+            actual,
+            UndoRedoState {
+                undo_description: None,
+                redo_description: Some("rate card Good".into()),
+            }
+        );
+        let restored = repetition_service::get_repetition_by_id(&db_conn, repetition.id)
+            .await
+            .unwrap();
+        assert_eq!(restored, repetition);
+        let review_logs = review_log::Entity::find().all(&db_conn).await.unwrap();
+        assert!(review_logs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn redo_undone_review_applied_review_again() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let mut undo_history = UndoHistory::default();
+        let repetition = get_flash_card_repetition(&db_conn, "file").await;
+        let reviewed = review_repetition(
+            &db_conn,
+            &mut undo_history,
+            repetition.id,
+            Rating::Easy,
+            Some(1000),
+        )
+        .await
+        .unwrap();
+        let review_log = review_log::Entity::find().one(&db_conn).await.unwrap();
+        undo(&db_conn, &mut undo_history).await.unwrap();
+
+        // Act
+
+        let actual = redo(&db_conn, &mut undo_history).await.unwrap();
+
+        // Assert
+
+        assert_eq!(actual.undo_description, Some("rate card Easy".into()));
+        assert_eq!(actual.redo_description, None);
+        let repetition = repetition_service::get_repetition_by_id(&db_conn, repetition.id)
+            .await
+            .unwrap();
+        assert_eq!(repetition, reviewed);
+        assert_eq!(repetition.state, State::Review);
+        let actual_review_log = review_log::Entity::find().one(&db_conn).await.unwrap();
+        assert_eq!(actual_review_log, review_log);
+    }
+
+    #[tokio::test]
+    async fn undo_delete_cell_restored_cell_at_index() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let mut undo_history = UndoHistory::default();
+        let repetition = get_flash_card_repetition(&db_conn, "file").await;
+        let file_id = repetition.file_id;
+        cell_service::create_cell(&db_conn, file_id, "", CellType::Note, 0)
+            .await
+            .unwrap();
+        cell_service::create_cell(&db_conn, file_id, "", CellType::Note, 2)
+            .await
+            .unwrap();
+        let cells = cell_service::get_file_cells_ordered_by_index(&db_conn, file_id)
+            .await
+            .unwrap();
+        delete_cell(&db_conn, &mut undo_history, repetition.cell_id)
+            .await
+            .unwrap();
+
+        // Act
+
+        undo(&db_conn, &mut undo_history).await.unwrap();
+
+        // Assert
+
+        let actual = cell_service::get_file_cells_ordered_by_index(&db_conn, file_id)
+            .await
+            .unwrap();
+        assert_eq!(actual, cells);
+        let repetitions = repetition_service::get_file_repetitions(&db_conn, file_id)
+            .await
+            .unwrap();
+        assert_eq!(repetitions, vec![repetition]);
+    }
+
+    #[tokio::test]
+    async fn undo_delete_cell_restored_tags() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let mut undo_history = UndoHistory::default();
+        let (_, cell_id) = create_file_cell(&db_conn, "file").await;
+        tag_service::add_tag(&db_conn, vec![cell_id], "lang::spanish")
+            .await
+            .unwrap();
+        delete_cell(&db_conn, &mut undo_history, cell_id)
+            .await
+            .unwrap();
+
+        // Act
+
+        undo(&db_conn, &mut undo_history).await.unwrap();
+
+        // Assert
+
+        let cell_tags = tag_service::get_tags_for_cells(&db_conn, vec![cell_id])
+            .await
+            .unwrap();
+        assert_eq!(cell_tags[&cell_id], vec!["lang::spanish"]);
+    }
+
+    #[tokio::test]
+    async fn undo_merge_duplicate_cells_restored_merged_cells() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let mut undo_history = UndoHistory::default();
+        let repetition = get_flash_card_repetition(&db_conn, "file").await;
+        let file_id = repetition.file_id;
+        let mut cell_ids = vec![repetition.cell_id];
+        for index in [0, 2] {
+            cell_ids.push(
+                cell_service::create_cell(
+                    &db_conn,
+                    file_id,
+                    r#"{"question":"question","answer":"answer"}"#,
+                    CellType::FlashCard,
+                    index,
+                )
+                .await
+                .unwrap(),
+            );
+        }
+        cell_service::create_cell(&db_conn, file_id, "", CellType::Note, 2)
+            .await
+            .unwrap();
+        review_repetition(
+            &db_conn,
+            &mut undo_history,
+            repetition.id,
+            Rating::Good,
+            None,
+        )
+        .await
+        .unwrap();
+        let cells = cell_service::get_file_cells_ordered_by_index(&db_conn, file_id)
+            .await
+            .unwrap();
+        let kept_cell_id = merge_duplicate_cells(&db_conn, &mut undo_history, cell_ids)
+            .await
+            .unwrap();
+        let merged_cells = cell_service::get_file_cells_ordered_by_index(&db_conn, file_id)
+            .await
+            .unwrap();
+
+        // Act
+
+        let actual = undo(&db_conn, &mut undo_history).await.unwrap();
+
+        // Assert
+
+        assert_eq!(kept_cell_id, repetition.cell_id);
+        assert_eq!(merged_cells.len(), 2);
+        assert_eq!(merged_cells[0].id, repetition.cell_id);
+        assert_eq!(merged_cells[1].index, 1);
+        assert_eq!(
+            actual.redo_description,
+            Some("merge duplicate cells".into())
+        );
+        let restored_cells = cell_service::get_file_cells_ordered_by_index(&db_conn, file_id)
+            .await
+            .unwrap();
+        assert_eq!(restored_cells, cells);
+    }
+
+    #[tokio::test]
+    async fn redo_merge_duplicate_cells_moved_tags_to_kept_cell() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let mut undo_history = UndoHistory::default();
+        let repetition = get_flash_card_repetition(&db_conn, "file").await;
+        let merged_cell_id = cell_service::create_cell(
+            &db_conn,
+            repetition.file_id,
+            r#"{"question":"question","answer":"answer"}"#,
+            CellType::FlashCard,
+            1,
+        )
+        .await
+        .unwrap();
+        tag_service::add_tag(&db_conn, vec![repetition.cell_id], "kept")
+            .await
+            .unwrap();
+        tag_service::add_tag(&db_conn, vec![repetition.cell_id, merged_cell_id], "both")
+            .await
+            .unwrap();
+        tag_service::add_tag(&db_conn, vec![merged_cell_id], "merged")
+            .await
+            .unwrap();
+        review_repetition(
+            &db_conn,
+            &mut undo_history,
+            repetition.id,
+            Rating::Good,
+            None,
+        )
+        .await
+        .unwrap();
+        merge_duplicate_cells(
+            &db_conn,
+            &mut undo_history,
+            vec![merged_cell_id, repetition.cell_id],
+        )
+        .await
+        .unwrap();
+        let merged_tags = tag_service::get_tags_for_cells(&db_conn, vec![repetition.cell_id])
+            .await
+            .unwrap();
+        undo(&db_conn, &mut undo_history).await.unwrap();
+        let restored_tags =
+            tag_service::get_tags_for_cells(&db_conn, vec![repetition.cell_id, merged_cell_id])
+                .await
+                .unwrap();
+
+        // Act
+
+        redo(&db_conn, &mut undo_history).await.unwrap();
+
+        // Assert
+
+        let expected = vec!["both".to_string(), "kept".into(), "merged".into()];
+        assert_eq!(merged_tags[&repetition.cell_id], expected);
+        assert_eq!(restored_tags[&merged_cell_id], vec!["both", "merged"]);
+        let actual = tag_service::get_tags_for_cells(&db_conn, vec![repetition.cell_id])
+            .await
+            .unwrap();
+        assert_eq!(actual[&repetition.cell_id], expected);
+        assert!(
+            cell::Entity::find_by_id(merged_cell_id)
+                .one(&db_conn)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn undo_delete_folder_restored_subtree() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let mut undo_history = UndoHistory::default();
+        let repetition = get_flash_card_repetition(&db_conn, "folder/sub folder/file").await;
+        create_file_cell(&db_conn, "other file").await;
+        review_repetition(
+            &db_conn,
+            &mut undo_history,
+            repetition.id,
+            Rating::Hard,
+            None,
+        )
+        .await
+        .unwrap();
+        let files = file_service::get_files(&db_conn).await.unwrap();
+        let cells = cell::Entity::find().all(&db_conn).await.unwrap();
+        let repetitions = repetition::Entity::find().all(&db_conn).await.unwrap();
+        let review_logs = review_log::Entity::find().all(&db_conn).await.unwrap();
+        let folder_id = files.iter().find(|file| file.path == "folder").unwrap().id;
+        delete_folder(&db_conn, &mut undo_history, folder_id)
+            .await
+            .unwrap();
+
+        // Act
+
+        undo(&db_conn, &mut undo_history).await.unwrap();
+
+        // Assert
+
+        assert_eq!(file_service::get_files(&db_conn).await.unwrap(), files);
+        assert_eq!(cell::Entity::find().all(&db_conn).await.unwrap(), cells);
+        assert_eq!(
+            repetition::Entity::find().all(&db_conn).await.unwrap(),
+            repetitions
+        );
+        assert_eq!(
+            review_log::Entity::find().all(&db_conn).await.unwrap(),
+            review_logs
+        );
+        assert_eq!(
+            get_undo_redo_state(&undo_history).undo_description,
+            Some("rate card Hard".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn undo_delete_file_path_taken_restored_with_new_name() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let mut undo_history = UndoHistory::default();
+        let (file_id, _) = create_file_cell(&db_conn, "file").await;
+        delete_file(&db_conn, &mut undo_history, file_id)
+            .await
+            .unwrap();
+        create_file_cell(&db_conn, "file").await;
+
+        // Act
+
+        let actual = undo(&db_conn, &mut undo_history).await;
+
+        // Assert
+
+        assert_eq!(
+            actual.unwrap().redo_description,
+            Some("delete file \"file (1)\"".into())
+        );
+        let file = file_service::get_by_id(&db_conn, file_id).await.unwrap();
+        assert_eq!(file.path, "file (1)");
+    }
+
+    #[tokio::test]
+    async fn undo_file_restored_from_trash_removed_stale_action() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let mut undo_history = UndoHistory::default();
+        let repetition = get_flash_card_repetition(&db_conn, "reviewed").await;
+        review_repetition(
+            &db_conn,
+            &mut undo_history,
+            repetition.id,
+            Rating::Good,
+            None,
+        )
+        .await
+        .unwrap();
+        let (file_id, _) = create_file_cell(&db_conn, "file").await;
+        delete_file(&db_conn, &mut undo_history, file_id)
+            .await
+            .unwrap();
+        let trash_id = trash_service::list_trash(&db_conn).await.unwrap()[0].id;
+        trash_service::restore_from_trash(&db_conn, trash_id)
+            .await
+            .unwrap();
+
+        // Act
+
+        let actual = undo(&db_conn, &mut undo_history).await;
+
+        // Assert
+
+        assert_eq!(
+            actual,
+            Err(
+                "Cannot undo \"delete file \"file\"\" anymore, it was removed from the history!"
+                    .into()
+            )
+        );
+        assert_eq!(
+            get_undo_redo_state(&undo_history).undo_description,
+            Some("rate card Good".into())
+        );
+        assert!(undo(&db_conn, &mut undo_history).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn undo_review_of_edited_cell_removed_stale_action() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let mut undo_history = UndoHistory::default();
+        let (file_id, cell_id) = create_file_cell_with_cell_type_and_content(
+            &db_conn,
+            "file",
+            CellType::Cloze,
+            "<cloze index=\"1\">a</cloze>",
+        )
+        .await;
+        let repetition = repetition_service::get_file_repetitions(&db_conn, file_id)
+            .await
+            .unwrap()
+            .remove(0);
+        review_repetition(
+            &db_conn,
+            &mut undo_history,
+            repetition.id,
+            Rating::Good,
+            None,
+        )
+        .await
+        .unwrap();
+        // The repetition of the cloze is removed without going through the
+        // history.
+        cell_service::update_cells_contents(
+            &db_conn,
+            vec![UpdateCellRequest {
+                cell_id,
+                content: "<cloze index=\"2\">a</cloze>".into(),
+            }],
+        )
+        .await
+        .unwrap();
+
+        // Act
+
+        let actual = undo(&db_conn, &mut undo_history).await;
+
+        // Assert
+
+        assert_eq!(
+            actual,
+            Err("Cannot undo \"rate card Good\" anymore, it was removed from the history!".into())
+        );
+        assert_eq!(get_undo_redo_state(&undo_history).undo_description, None);
+    }
+
+    #[tokio::test]
+    async fn reset_repetitions_for_cell_removed_reviews_of_cell() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let mut undo_history = UndoHistory::default();
+        let other = get_flash_card_repetition(&db_conn, "other").await;
+        let repetition = get_flash_card_repetition(&db_conn, "file").await;
+        for id in [other.id, repetition.id] {
+            review_repetition(&db_conn, &mut undo_history, id, Rating::Hard, None)
+                .await
+                .unwrap();
+        }
+
+        // Act
+
+        reset_repetitions_for_cell(&db_conn, &mut undo_history, repetition.cell_id)
+            .await
+            .unwrap();
+
+        // Assert
+
+        undo(&db_conn, &mut undo_history).await.unwrap();
+        assert_eq!(
+            repetition_service::get_repetition_by_id(&db_conn, other.id)
+                .await
+                .unwrap(),
+            other
+        );
+        assert_eq!(get_undo_redo_state(&undo_history).undo_description, None);
+    }
+
+    #[tokio::test]
+    async fn remove_moves_to_trash_restored_item_kept_other_actions() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let mut undo_history = UndoHistory::default();
+        let (first_file_id, _) = create_file_cell(&db_conn, "first").await;
+        let (second_file_id, _) = create_file_cell(&db_conn, "second").await;
+        for file_id in [first_file_id, second_file_id] {
+            delete_file(&db_conn, &mut undo_history, file_id)
+                .await
+                .unwrap();
+        }
+        let trash_id = match undo_history.peek_undo() {
+            Some(UndoAction::MoveToTrash { trash_id, .. }) => *trash_id,
+            _ => panic!("The deletion is not in the history!"),
+        };
+        trash_service::restore_from_trash(&db_conn, trash_id)
+            .await
+            .unwrap();
+
+        // Act
+
+        undo_history.remove_moves_to_trash(&[trash_id]);
+
+        // Assert
+
+        assert_eq!(
+            get_undo_redo_state(&undo_history).undo_description,
+            Some("delete file \"first\"".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn undo_empty_history_returned_error() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let mut undo_history = UndoHistory::default();
+
+        // Act
+
+        let actual = undo(&db_conn, &mut undo_history).await;
+
+        // Assert
+
+        assert_eq!(actual, Err("Nothing to undo!".into()));
+    }
+
+    #[tokio::test]
+    async fn delete_cell_after_undo_cleared_redo() {
+        // Arrange
+
+        let db_conn = get_db().await;
+        let mut undo_history = UndoHistory::default();
+        let (_, first_cell_id) = create_file_cell(&db_conn, "first file").await;
+        let (_, second_cell_id) = create_file_cell(&db_conn, "second file").await;
+        delete_cell(&db_conn, &mut undo_history, first_cell_id)
+            .await
+            .unwrap();
+        undo(&db_conn, &mut undo_history).await.unwrap();
+
+        // Act
+
+        delete_cell(&db_conn, &mut undo_history, second_cell_id)
+            .await
+            .unwrap();
+
+        // Assert
+
+        assert_eq!(
+            get_undo_redo_state(&undo_history),
+            UndoRedoState {
+                undo_description: Some("delete cell".into()),
+                redo_description: None,
+            }
+        );
+    }
+}
@@ -2,12 +2,15 @@ use sea_orm::{Database, DatabaseConnection};
 
 use crate::migration;
 
-pub async fn load_database(path: &String) -> DatabaseConnection {
-    let db_conn = Database::connect(format!("sqlite:///{}?mode=rwc", path))
-        .await
-        .expect("Cannot open the database");
-    migration::setup_schema(&db_conn)
-        .await
-        .expect("Could not setup the database schema!");
-    db_conn
+/// Opens the database and migrates it to the latest schema version. Databases
+/// created by a newer version of the app are refused.
+pub async fn load_database(path: &String) -> Result<DatabaseConnection, String> {
+    let db_conn = match Database::connect(format!("sqlite:///{}?mode=rwc", path)).await {
+        Ok(db_conn) => db_conn,
+        Err(err) => return Err(format!("Cannot open the database: {err}")),
+    };
+    if let Err(err) = migration::setup_schema(&db_conn).await {
+        return Err(format!("Could not setup the database schema: {err}"));
+    }
+    Ok(db_conn)
 }
@@ -0,0 +1,332 @@
+use std::ops::Range;
+
+use pulldown_cmark::{Event, Options, Parser, Tag, html};
+use regex::{Captures, Regex};
+use scraper::{ElementRef, Html, Node};
+
+/// Converts the HTML of a cell to Markdown. Cloze elements become
+/// `{{c1::text}}` and elements without a Markdown equivalent, like underlines,
+/// are kept as inline HTML so that `markdown_to_html` restores them.
+pub fn html_to_markdown(html: &str) -> String {
+    let fragment = Html::parse_fragment(html);
+    let markdown = convert_children(fragment.root_element());
+    let blank_lines_regex = Regex::new(r"\n{3,}").expect("Invalid regex");
+    blank_lines_regex
+        .replace_all(&markdown, "\n\n")
+        .trim()
+        .to_string()
+}
+
+/// Converts Markdown to HTML, `{{c1::text}}` becomes a cloze element.
+pub fn markdown_to_html(markdown: &str) -> String {
+    let markdown = convert_clozes(markdown);
+    let parser = Parser::new_ext(&markdown, Options::ENABLE_STRIKETHROUGH);
+    let mut html = String::new();
+    html::push_html(&mut html, parser);
+
+    // The editor does not put line breaks between blocks.
+    let block_end_regex =
+        Regex::new(r"(</?(?:p|ul|ol|li|h[1-6]|blockquote|pre)>|<hr />)\n").expect("Invalid regex");
+    block_end_regex
+        .replace_all(&html, "$1")
+        .replace("<del>", "<s>")
+        .replace("</del>", "</s>")
+        .trim()
+        .to_string()
+}
+
+/// Replaces `{{c1::text}}` by inline cloze elements before rendering, so that
+/// escaped braces and code stay literal.
+fn convert_clozes(markdown: &str) -> String {
+    let code_ranges: Vec<Range<usize>> = Parser::new_ext(markdown, Options::ENABLE_STRIKETHROUGH)
+        .into_offset_iter()
+        .filter_map(|(event, range)| match event {
+            Event::Code(_) | Event::Start(Tag::CodeBlock(_)) => Some(range),
+            _ => None,
+        })
+        .collect();
+    let cloze_regex = Regex::new(r"(?s)\{\{c(\d+)::(.*?)\}\}").expect("Invalid regex");
+    cloze_regex
+        .replace_all(markdown, |captures: &Captures| {
+            let start = captures.get(0).unwrap().start();
+            let backslash_count = markdown[..start]
+                .chars()
+                .rev()
+                .take_while(|c| *c == '\\')
+                .count();
+            if backslash_count % 2 == 1 || code_ranges.iter().any(|range| range.contains(&start)) {
+                captures[0].to_string()
+            } else {
+                format!("<cloze index=\"{}\">{}</cloze>", &captures[1], &captures[2])
+            }
+        })
+        .to_string()
+}
+
+fn convert_children(element: ElementRef) -> String {
+    let whitespace_regex = Regex::new(r"\s+").expect("Invalid regex");
+    let mut markdown = String::new();
+    for node in element.children() {
+        if let Some(child) = ElementRef::wrap(node) {
+            markdown.push_str(&convert_element(child));
+        } else if let Node::Text(text) = node.value() {
+            markdown.push_str(&escape_text(&whitespace_regex.replace_all(text, " ")));
+        }
+    }
+    markdown
+}
+
+fn convert_element(element: ElementRef) -> String {
+    let children = || convert_children(element);
+    let name = element.value().name();
+    match name {
+        "p" | "div" => format!("\n\n{}\n\n", children().trim()),
+        "br" => "\\\n".into(),
+        "strong" | "b" => wrap_inline(&children(), "**"),
+        "em" | "i" => wrap_inline(&children(), "*"),
+        "s" | "del" | "strike" => wrap_inline(&children(), "~~"),
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+            let level = name[1..].parse().unwrap();
+            format!("\n\n{} {}\n\n", "#".repeat(level), children().trim())
+        }
+        "hr" => "\n\n---\n\n".into(),
+        "a" => format!(
+            "[{}]({})",
+            children(),
+            get_destination(element.value().attr("href").unwrap_or_default())
+        ),
+        "img" => format!(
+            "![{}]({})",
+            escape_text(element.value().attr("alt").unwrap_or_default()),
+            get_destination(element.value().attr("src").unwrap_or_default())
+        ),
+        "code" => {
+            let code: String = element.text().collect();
+            let fence = get_fence(&code, '`', 1);
+            // Code starting or ending with a backtick needs spaces.
+            if code.starts_with('`') || code.ends_with('`') {
+                format!("{fence} {code} {fence}")
+            } else {
+                format!("{fence}{code}{fence}")
+            }
+        }
+        "pre" => {
+            let code: String = element.text().collect();
+            let language = element
+                .select(&scraper::Selector::parse("code").unwrap())
+                .next()
+                .and_then(|code| {
+                    code.value()
+                        .classes()
+                        .find_map(|class| class.strip_prefix("language-"))
+                })
+                .unwrap_or_default();
+            let fence = get_fence(&code, '`', 3);
+            format!(
+                "\n\n{fence}{language}\n{}\n{fence}\n\n",
+                code.trim_end_matches('\n')
+            )
+        }
+        "blockquote" => {
+            let quote = children();
+            let lines: Vec<String> = quote
+                .trim()
+                .lines()
+                .map(|line| format!("> {line}").trim_end().to_string())
+                .collect();
+            format!("\n\n{}\n\n", lines.join("\n"))
+        }
+        "ul" | "ol" => format!("\n\n{}\n\n", convert_list(element, name == "ol")),
+        "cloze" => format!(
+            "{{{{c{}::{}}}}}",
+            element.value().attr("index").unwrap_or("1"),
+            children()
+        ),
+        _ => element.html(),
+    }
+}
+
+fn convert_list(list: ElementRef, is_ordered: bool) -> String {
+    // Items are kept tight, the editor wraps them in paragraphs anyway.
+    let blank_lines_regex = Regex::new(r"\n{2,}").expect("Invalid regex");
+    let mut items = vec![];
+    for (i, item) in list.child_elements().enumerate() {
+        let marker = if is_ordered {
+            format!("{}. ", i + 1)
+        } else {
+            "- ".into()
+        };
+        let indentation = " ".repeat(marker.len());
+        let content = convert_children(item);
+        let content = blank_lines_regex.replace_all(content.trim(), "\n");
+        let lines: Vec<String> = content
+            .lines()
+            .enumerate()
+            .map(|(j, line)| {
+                if j == 0 {
+                    format!("{marker}{line}")
+                } else {
+                    format!("{indentation}{line}")
+                }
+            })
+            .collect();
+        items.push(lines.join("\n"));
+    }
+    items.join("\n")
+}
+
+/// Keeps spaces outside of the markers, `** bold**` would not be bold.
+fn wrap_inline(text: &str, marker: &str) -> String {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return text.to_string();
+    }
+    let leading = &text[..text.len() - text.trim_start().len()];
+    let trailing = &text[text.trim_end().len()..];
+    format!("{leading}{marker}{trimmed}{marker}{trailing}")
+}
+
+/// Wraps the URL of a link or an image in angle brackets when it has spaces
+/// or parentheses, which would end it otherwise.
+fn get_destination(url: &str) -> String {
+    if url.contains(|c: char| c.is_whitespace() || c == '(' || c == ')') {
+        format!("<{}>", url.replace('<', "\\<").replace('>', "\\>"))
+    } else {
+        url.to_string()
+    }
+}
+
+/// Returns a fence longer than any run of the character inside the text.
+fn get_fence(text: &str, character: char, minimum_length: usize) -> String {
+    let mut longest = 0;
+    let mut current = 0;
+    for c in text.chars() {
+        if c == character {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    character
+        .to_string()
+        .repeat(minimum_length.max(longest + 1))
+}
+
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '&' | '{' | '}' | '~' | '|'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    // List markers only have a meaning at the start of a line.
+    let list_marker_regex = Regex::new(r"^([-+]|\d+)([. ])").expect("Invalid regex");
+    list_marker_regex
+        .replace(&escaped, |captures: &regex::Captures| {
+            if &captures[1] == "-" || &captures[1] == "+" {
+                format!("\\{}{}", &captures[1], &captures[2])
+            } else if &captures[2] == "." {
+                format!("{}\\.", &captures[1])
+            } else {
+                captures[0].to_string()
+            }
+        })
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn html_to_markdown_formatted_html_converted() {
+        // Arrange
+
+        let html = "<h2>Title</h2><p>Some <strong>bold</strong> and <em>italic</em> text,<br>a <a href=\"https://example.com\">link</a> and <code>code</code>.</p><ul><li><p>one</p></li><li><p>two</p><ol><li><p>nested</p></li></ol></li></ul><blockquote><p>quote</p></blockquote><pre><code class=\"language-rust\">let x = 1;</code></pre>";
+
+        // Act
+
+        let actual = html_to_markdown(html);
+
+        // Assert
+
+        assert_eq!(
+            actual,
+            "## Title\n\nSome **bold** and *italic* text,\\\na [link](https://example.com) and `code`.\n\n- one\n- two\n  1. nested\n\n> quote\n\n```rust\nlet x = 1;\n```"
+        );
+    }
+
+    #[test]
+    fn html_to_markdown_special_characters_and_unknown_elements_preserved() {
+        // Arrange
+
+        let html = "<p>1. a *star* &amp; <u>underlined</u> <cloze index=\"2\">Paris</cloze></p>";
+
+        // Act
+
+        let actual = html_to_markdown(html);
+
+        // Assert
+
+        assert_eq!(
+            actual,
+            "1\\. a \\*star\\* \\& <u>underlined</u> {{c2::Paris}}"
+        );
+    }
+
+    #[test]
+    fn markdown_to_html_converted_markdown_returned_original_html() {
+        // Arrange
+
+        let html = "<h2>Title</h2><p>Some <strong>bold</strong>, <s>struck</s> &amp; <u>underlined</u> text <cloze index=\"1\">Paris</cloze></p><ul><li>one</li><li>two</li></ul><pre><code class=\"language-rust\">let x = 1;\n</code></pre>";
+
+        // Act
+
+        let actual = markdown_to_html(&html_to_markdown(html));
+
+        // Assert
+
+        assert_eq!(actual, html);
+    }
+
+    #[test]
+    fn markdown_to_html_escaped_braces_and_code_not_converted_to_clozes() {
+        // Arrange
+
+        let html = "<p>{{c1::literal}} <code>{{c2::code}}</code> <cloze index=\"3\"><strong>Paris</strong></cloze></p>";
+
+        // Act
+
+        let actual = markdown_to_html(&html_to_markdown(html));
+
+        // Assert
+
+        assert_eq!(actual, html);
+    }
+
+    #[test]
+    fn markdown_to_html_image_source_with_spaces_and_parentheses_preserved() {
+        // Arrange
+
+        let html = "<p><img src=\"my image (1).png\" alt=\"map\" /></p>";
+
+        // Act
+
+        let markdown = html_to_markdown(html);
+        let actual = markdown_to_html(&markdown);
+
+        // Assert
+
+        assert_eq!(markdown, "![map](<my image (1).png>)");
+        assert_eq!(
+            actual,
+            "<p><img src=\"my%20image%20(1).png\" alt=\"map\" /></p>"
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use std::path::{Path, PathBuf};
+
+/// The scheme of the references to the files of the media store, which cells
+/// keep instead of local paths, e.g. `brainy-media://<sha256>.png`. The app
+/// serves them from the media store of the opened database, so they keep
+/// working when the database is moved or restored on another machine.
+pub const MEDIA_SCHEME: &str = "brainy-media";
+
+const ASSET_URL_PREFIXES: [&str; 3] = [
+    "asset://",
+    "http://asset.localhost/",
+    "https://asset.localhost/",
+];
+
+/// Returns the reference to the file of the media store with the given name.
+pub fn get_media_reference(name: &str) -> String {
+    format!("{MEDIA_SCHEME}://{name}")
+}
+
+/// Returns the name of the file of the media store the source references,
+/// `None` for other sources.
+pub fn get_referenced_media_name(src: &str) -> Option<&str> {
+    src.strip_prefix(MEDIA_SCHEME)
+        .and_then(|rest| rest.strip_prefix("://"))
+        .filter(|name| is_valid_media_name(name))
+}
+
+/// Returns the name of the requested file from a URL of the media scheme as
+/// loaded by the webview, like `brainy-media://localhost/<name>`,
+/// `http://brainy-media.localhost/<name>` on Windows or an unresolved
+/// reference.
+pub fn get_requested_media_name(url: &str) -> Option<&str> {
+    let (_, rest) = url.split_once("://")?;
+    let rest = rest.split(['?', '#']).next().unwrap_or_default();
+    rest.rsplit('/')
+        .find(|segment| !segment.is_empty())
+        .filter(|name| is_valid_media_name(name))
+}
+
+/// Checks that the name is a plain file name, so that it cannot point outside
+/// of the media store.
+pub fn is_valid_media_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '.')
+}
+
+/// Returns the file of the media store the source references, `None` for
+/// other sources so that exports never read other local files.
+pub fn get_media_store_path(src: &str, media_directory: &Path) -> Option<PathBuf> {
+    get_referenced_media_name(src).map(|name| media_directory.join(name))
+}
+
+/// Checks whether the URL points to a local file, like `file://` and asset
+/// URLs. Imported cells must not reference such files, as they would be read
+/// when the cells are exported.
+pub fn is_local_file_url(url: &str) -> bool {
+    // The URLs of JSON contents are surrounded by escaped quotes.
+    let url = url
+        .trim_matches(|c: char| c == '\\' || c == '"' || c.is_whitespace())
+        .to_lowercase();
+    url.starts_with("file:")
+        || ASSET_URL_PREFIXES
+            .iter()
+            .any(|prefix| url.starts_with(prefix))
+}
+
+/// Returns the MIME type of the media file based on its extension.
+pub fn get_media_type(file_name: &str) -> &'static str {
+    let extension = match file_name.rsplit_once('.') {
+        Some((_, extension)) => extension.to_lowercase(),
+        None => return "application/octet-stream",
+    };
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "mp3" => "audio/mpeg",
+        "ogg" => "audio/ogg",
+        "wav" => "audio/wav",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_local_file_url_file_and_asset_urls_returned_true() {
+        // Arrange
+
+        let urls = [
+            "file:///home/user/.ssh/id_rsa",
+            "FILE:///C:/media/image.png",
+            "asset://localhost/%2Fhome%2Fuser%2Fimage.png",
+            "http://asset.localhost/%2Fhome%2Fuser%2Fimage.png",
+            "\\\"file:///home/user/image.png\\\"",
+            "https://example.com/image.png",
+            "brainy-media://abc.png",
+            "data:image/png;base64,AAAA",
+        ];
+
+        // Act
+
+        let actual: Vec<bool> = urls.iter().map(|url| is_local_file_url(url)).collect();
+
+        // Assert
+
+        assert_eq!(
+            actual,
+            vec![true, true, true, true, true, false, false, false]
+        );
+    }
+
+    #[test]
+    fn get_media_store_path_media_reference_returned_file_of_media_directory() {
+        // Arrange
+
+        let media_directory = Path::new("/data/brainy_media");
+        let sources = [
+            get_media_reference("abc.png"),
+            "brainy-media://../secret.png".to_string(),
+            "file:///home/user/image.png".to_string(),
+        ];
+
+        // Act
+
+        let actual: Vec<Option<PathBuf>> = sources
+            .iter()
+            .map(|src| get_media_store_path(src, media_directory))
+            .collect();
+
+        // Assert
+
+        assert_eq!(
+            actual,
+            vec![Some(media_directory.join("abc.png")), None, None,]
+        );
+    }
+
+    #[test]
+    fn get_requested_media_name_resolved_and_unresolved_urls_returned_name() {
+        // Arrange
+
+        let urls = [
+            "brainy-media://localhost/abc.png",
+            "http://brainy-media.localhost/abc.png?v=1",
+            "brainy-media://abc.png/",
+            "brainy-media://localhost/..%2Fsecret.png",
+        ];
+
+        // Act
+
+        let actual: Vec<Option<&str>> = urls
+            .iter()
+            .map(|url| get_requested_media_name(url))
+            .collect();
+
+        // Assert
+
+        assert_eq!(
+            actual,
+            vec![Some("abc.png"), Some("abc.png"), Some("abc.png"), None]
+        );
+    }
+}
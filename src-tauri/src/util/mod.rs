@@ -1 +1,3 @@
 pub mod database_util;
+pub mod markdown_util;
+pub mod media_util;
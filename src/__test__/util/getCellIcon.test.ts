@@ -2,7 +2,11 @@ import {
 	mdiCardMultipleOutline,
 	mdiCheckCircleOutline,
 	mdiDotsHorizontal,
+	mdiFormatListChecks,
+	mdiFormTextbox,
+	mdiImageArea,
 	mdiNoteOutline,
+	mdiOrderNumericAscending,
 } from "@mdi/js";
 import getCellIcon from "../../util/getCellIcon";
 import { CellType } from "../../type/backend/entity/cell";
@@ -16,6 +20,10 @@ describe(getCellIcon, () => {
 			["Note", mdiNoteOutline],
 			["Cloze", mdiDotsHorizontal],
 			["TrueFalse", mdiCheckCircleOutline],
+			["MultipleChoice", mdiFormatListChecks],
+			["TypeAnswer", mdiFormTextbox],
+			["ImageOcclusion", mdiImageArea],
+			["Ordering", mdiOrderNumericAscending],
 		];
 
 		// Act & Assert
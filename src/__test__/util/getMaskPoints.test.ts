@@ -0,0 +1,45 @@
+import getMaskPoints from "../../util/getMaskPoints";
+import { MaskShape } from "../../type/cell/imageOcclusion";
+
+describe(getMaskPoints, () => {
+	it("Returns the corners of a rectangle", () => {
+		// Arrange
+
+		const shape: MaskShape = {
+			kind: "rectangle",
+			x: 0.25,
+			y: 0.5,
+			width: 0.5,
+			height: 0.25,
+		};
+
+		// Act
+
+		const actual = getMaskPoints(shape);
+
+		// Assert
+
+		expect(actual).toBe("0.25,0.5 0.75,0.5 0.75,0.75 0.25,0.75");
+	});
+
+	it("Returns the points of a polygon", () => {
+		// Arrange
+
+		const shape: MaskShape = {
+			kind: "polygon",
+			points: [
+				[0, 0],
+				[1, 0],
+				[0.5, 1],
+			],
+		};
+
+		// Act
+
+		const actual = getMaskPoints(shape);
+
+		// Assert
+
+		expect(actual).toBe("0,0 1,0 0.5,1");
+	});
+});
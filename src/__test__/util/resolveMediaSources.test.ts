@@ -0,0 +1,43 @@
+import referenceMediaSources from "../../util/referenceMediaSources";
+import resolveMediaSources from "../../util/resolveMediaSources";
+
+vi.mock("@tauri-apps/api/core", () => ({
+	convertFileSrc: (filePath: string, protocol: string) =>
+		`http://${protocol}.localhost/${filePath}`,
+}));
+
+describe(resolveMediaSources, () => {
+	it("Resolves media references", () => {
+		// Arrange
+
+		const html =
+			'<img src="brainy-media://abc.png"><img src="https://a.com/b.png">';
+
+		// Act
+
+		const actual = resolveMediaSources(html);
+
+		// Assert
+
+		expect(actual).toBe(
+			'<img src="http://brainy-media.localhost/abc.png">' +
+				'<img src="https://a.com/b.png">',
+		);
+	});
+});
+
+describe(referenceMediaSources, () => {
+	it("Restores resolved media references", () => {
+		// Arrange
+
+		const html = '<p>text</p><img src="brainy-media://abc.png">';
+
+		// Act
+
+		const actual = referenceMediaSources(resolveMediaSources(html));
+
+		// Assert
+
+		expect(actual).toBe(html);
+	});
+});
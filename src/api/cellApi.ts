@@ -1,6 +1,9 @@
 import { invoke } from "@tauri-apps/api/core";
 import Cell from "../type/backend/entity/cell";
 import UpdateCellRequest from "../type/backend/dto/updateCellRequest";
+import DuplicateGroup from "../type/backend/dto/duplicateGroup";
+import AnswerComparison from "../type/backend/dto/answerComparison";
+import OrderingGrade from "../type/backend/dto/orderingGrade";
 
 export function getFileCellsOrderedByIndex(fileId: number): Promise<Cell[]> {
 	return invoke("get_file_cells_ordered_by_index", {
@@ -30,3 +33,27 @@ export function moveCell(cellId: number, newIndex: number) {
 export function getCellsForFiles(fileIds: number[]): Promise<Cell[]> {
 	return invoke("get_cells_for_files", { fileIds });
 }
+
+export function findDuplicateCells(
+	similarityThreshold: number,
+): Promise<DuplicateGroup[]> {
+	return invoke("find_duplicate_cells", { similarityThreshold });
+}
+
+export function mergeDuplicateCells(cellIds: number[]): Promise<number> {
+	return invoke("merge_duplicate_cells", { cellIds });
+}
+
+export function checkTypedAnswer(
+	cellId: number,
+	typedAnswer: string,
+): Promise<AnswerComparison> {
+	return invoke("check_typed_answer", { cellId, typedAnswer });
+}
+
+export function gradeOrdering(
+	cellId: number,
+	submittedOrder: number[],
+): Promise<OrderingGrade> {
+	return invoke("grade_ordering", { cellId, submittedOrder });
+}
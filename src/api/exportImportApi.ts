@@ -1,15 +1,111 @@
 import { invoke } from "@tauri-apps/api/core";
+import AnkiImportResult from "../type/backend/dto/ankiImportResult";
+import CsvImportOptions from "../type/backend/dto/csvImportOptions";
+import CsvImportResult from "../type/backend/dto/csvImportResult";
+import ImportOptions from "../type/backend/dto/importOptions";
+import ImportResult from "../type/backend/dto/importResult";
 
-export function exportItem(itemId: number, exportPath: string) {
+export function exportItem(
+	itemId: number,
+	exportPath: string,
+	includeProgress: boolean,
+) {
 	return invoke("export", {
 		itemId,
 		exportPath,
+		includeProgress,
 	});
 }
 
-export function importFile(importItemPath: string, importIntoFolderId: number) {
+export function exportBundle(
+	itemId: number,
+	exportPath: string,
+	includeProgress: boolean,
+) {
+	return invoke("export_bundle", {
+		itemId,
+		exportPath,
+		includeProgress,
+	});
+}
+
+export function exportAnkiPackage(
+	itemId: number,
+	exportPath: string,
+	noteTypeName: string | null,
+) {
+	return invoke("export_anki_package", {
+		itemId,
+		exportPath,
+		noteTypeName,
+	});
+}
+
+export function importFile(
+	importItemPath: string,
+	importIntoFolderId: number,
+	options: ImportOptions,
+): Promise<ImportResult> {
 	return invoke("import", {
 		importItemPath,
 		importIntoFolderId,
+		options,
+	});
+}
+
+export function importBundle(
+	bundlePath: string,
+	importIntoFolderId: number,
+	options: ImportOptions,
+): Promise<ImportResult> {
+	return invoke("import_bundle", {
+		bundlePath,
+		importIntoFolderId,
+		options,
+	});
+}
+
+export function importAnkiPackage(
+	packagePath: string,
+	importIntoFolderId: number,
+	importScheduling: boolean,
+): Promise<AnkiImportResult> {
+	return invoke("import_anki_package", {
+		packagePath,
+		importIntoFolderId,
+		importScheduling,
+	});
+}
+
+export function exportCsv(itemId: number, exportPath: string) {
+	return invoke("export_csv", {
+		itemId,
+		exportPath,
+	});
+}
+
+export function importCsv(
+	importPath: string,
+	fileId: number,
+	options: CsvImportOptions,
+): Promise<CsvImportResult> {
+	return invoke("import_csv", {
+		importPath,
+		fileId,
+		options,
+	});
+}
+
+export function exportMarkdown(itemId: number, exportDirectory: string) {
+	return invoke("export_markdown", {
+		itemId,
+		exportDirectory,
+	});
+}
+
+export function importMarkdown(importPath: string, importIntoFolderId: number) {
+	return invoke("import_markdown", {
+		importPath,
+		importIntoFolderId,
 	});
 }
@@ -0,0 +1,24 @@
+import { invoke } from "@tauri-apps/api/core";
+import MediaFile from "../type/backend/dto/mediaFile";
+
+export function addMediaFromPath(path: string): Promise<MediaFile> {
+	return invoke("add_media_from_path", { path });
+}
+
+export function addMediaFromBytes(
+	bytes: Uint8Array,
+	extension: string,
+): Promise<MediaFile> {
+	return invoke("add_media_from_bytes", {
+		bytes: Array.from(bytes),
+		extension,
+	});
+}
+
+export function listUnusedMedia(): Promise<MediaFile[]> {
+	return invoke("list_unused_media");
+}
+
+export function deleteUnusedMedia(): Promise<number> {
+	return invoke("delete_unused_media");
+}
@@ -1,6 +1,8 @@
 import { invoke } from "@tauri-apps/api/core";
 import FileRepetitionCounts from "../type/backend/model/fileRepetitionCounts";
 import Repetition from "../type/backend/entity/repetition";
+import Rating from "../type/backend/model/rating";
+import ReviewPreview from "../type/backend/dto/reviewPreview";
 
 export function getStudyRepetitionCounts(
 	fileId: number,
@@ -10,8 +12,22 @@ export function getStudyRepetitionCounts(
 	});
 }
 
-export function updateRepetition(repetition: Repetition) {
-	return invoke("update_repetition", { repetition });
+export function previewRepetition(
+	repetitionId: number,
+): Promise<ReviewPreview> {
+	return invoke("preview_repetition", { repetitionId });
+}
+
+export function reviewRepetition(
+	repetitionId: number,
+	rating: Rating,
+	reviewDuration?: number,
+): Promise<Repetition> {
+	return invoke("review_repetition", {
+		repetitionId,
+		rating,
+		reviewDuration,
+	});
 }
 
 export function getFileRepetitions(fileId: number): Promise<Repetition[]> {
@@ -0,0 +1,8 @@
+import { invoke } from "@tauri-apps/api/core";
+import OptimizationResult from "../type/backend/dto/optimizationResult";
+
+export function optimizeSchedulerParameters(
+	folderId: number,
+): Promise<OptimizationResult> {
+	return invoke("optimize_scheduler_parameters", { folderId });
+}
@@ -1,6 +1,7 @@
 import { invoke } from "@tauri-apps/api/core";
+import SearchRequest from "../type/backend/dto/searchRequest";
 import SearchResult from "../type/backend/dto/searchResult";
 
-export function searchCells(searchText: string): Promise<SearchResult> {
-	return invoke("search_cells", { searchText });
+export function searchCells(request: SearchRequest): Promise<SearchResult> {
+	return invoke("search_cells", { request });
 }
@@ -0,0 +1,28 @@
+import { invoke } from "@tauri-apps/api/core";
+import TagWithCellsCount from "../type/backend/dto/tagWithCellsCount";
+
+export function getTags(): Promise<TagWithCellsCount[]> {
+	return invoke("get_tags");
+}
+
+export function getTagsForCells(
+	cellIds: number[],
+): Promise<Record<number, string[]>> {
+	return invoke("get_tags_for_cells", { cellIds });
+}
+
+export function addTag(cellIds: number[], name: string): Promise<void> {
+	return invoke("add_tag", { cellIds, name });
+}
+
+export function removeTag(cellIds: number[], name: string): Promise<void> {
+	return invoke("remove_tag", { cellIds, name });
+}
+
+export function renameTag(name: string, newName: string): Promise<void> {
+	return invoke("rename_tag", { name, newName });
+}
+
+export function mergeTags(name: string, intoName: string): Promise<void> {
+	return invoke("merge_tags", { name, intoName });
+}
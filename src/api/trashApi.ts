@@ -0,0 +1,14 @@
+import { invoke } from "@tauri-apps/api/core";
+import TrashItem from "../type/backend/dto/trashItem";
+
+export function listTrash(): Promise<TrashItem[]> {
+	return invoke("list_trash");
+}
+
+export function restoreFromTrash(trashId: number): Promise<string> {
+	return invoke("restore_from_trash", { trashId });
+}
+
+export function emptyTrash(): Promise<void> {
+	return invoke("empty_trash");
+}
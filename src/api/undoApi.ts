@@ -0,0 +1,14 @@
+import { invoke } from "@tauri-apps/api/core";
+import UndoRedoState from "../type/backend/dto/undoRedoState";
+
+export function getUndoRedoState(): Promise<UndoRedoState> {
+	return invoke("get_undo_redo_state");
+}
+
+export function undo(): Promise<UndoRedoState> {
+	return invoke("undo");
+}
+
+export function redo(): Promise<UndoRedoState> {
+	return invoke("redo");
+}
@@ -0,0 +1,137 @@
+import Icon from "@mdi/react";
+import styles from "./styles.module.css";
+import { mdiTableArrowLeft } from "@mdi/js";
+import { message } from "@tauri-apps/plugin-dialog";
+import { useRef, useState } from "react";
+import { importCsv } from "../../api/exportImportApi";
+import useOutsideClick from "../../hooks/useOutsideClick";
+import useGlobalKey from "../../hooks/useGlobalKey";
+import errorToString from "../../util/errorToString";
+
+interface Props {
+	importPath: string;
+	fileId: number;
+	onClose: () => void;
+	onError: (error: string) => void;
+}
+
+/**
+ * Asks which columns hold the question, answer and type before importing the
+ * rows of a CSV or TSV file. Columns are shown starting at 1.
+ */
+function CsvImportPopup({ importPath, fileId, onClose, onError }: Props) {
+	const [questionColumn, setQuestionColumn] = useState(1);
+	const [answerColumn, setAnswerColumn] = useState(2);
+	const [typeColumn, setTypeColumn] = useState(0);
+	const [hasHeader, setHasHeader] = useState(true);
+	const boxRef = useRef<HTMLFormElement>(null);
+
+	useOutsideClick(boxRef as React.RefObject<HTMLElement>, onClose);
+
+	const handleSubmit = async (e: React.FormEvent<HTMLFormElement>) => {
+		e.preventDefault();
+
+		try {
+			const result = await importCsv(importPath, fileId, {
+				questionColumn: questionColumn - 1,
+				answerColumn: answerColumn - 1,
+				typeColumn: typeColumn > 0 ? typeColumn - 1 : null,
+				hasHeader,
+				delimiter: null,
+			});
+			onClose();
+			if (result.rowErrors.length > 0) {
+				const rowErrors = result.rowErrors
+					.map(
+						rowError =>
+							`Line ${rowError.line}: ${rowError.message}`,
+					)
+					.join("\n");
+				await message(
+					`Imported ${result.importedCellsCount} cells, skipped these rows:\n${rowErrors}`,
+					{ title: "Import CSV", kind: "warning" },
+				);
+			}
+		} catch (e) {
+			console.error(e);
+			onError(errorToString(e));
+		}
+	};
+
+	useGlobalKey((e: KeyboardEvent) => {
+		if (e.key === "Escape") {
+			onClose();
+		}
+	});
+
+	return (
+		<div className="overlay">
+			<form
+				className={styles.box}
+				ref={boxRef}
+				onSubmit={e => void handleSubmit(e)}>
+				<div className={`row ${styles.header}`}>
+					<Icon path={mdiTableArrowLeft} size={1.2} />
+					<p>Import {importPath.split(/[\\/]/).pop()}</p>
+				</div>
+				<div className={styles.optionRows}>
+					<div className={styles.optionRow}>
+						<p>Question column:</p>
+						<input
+							type="number"
+							value={questionColumn}
+							onChange={e =>
+								setQuestionColumn(Number(e.target.value))
+							}
+							min={1}
+							autoFocus
+						/>
+					</div>
+					<div className={styles.optionRow}>
+						<p>Answer column:</p>
+						<input
+							type="number"
+							value={answerColumn}
+							onChange={e =>
+								setAnswerColumn(Number(e.target.value))
+							}
+							min={1}
+						/>
+					</div>
+					<div className={styles.optionRow}>
+						<p>Type column (0 = none):</p>
+						<input
+							type="number"
+							value={typeColumn}
+							onChange={e =>
+								setTypeColumn(Number(e.target.value))
+							}
+							min={0}
+						/>
+					</div>
+					<div className={styles.optionRow}>
+						<p>First row is a header:</p>
+						<input
+							type="checkbox"
+							checked={hasHeader}
+							onChange={e => setHasHeader(e.target.checked)}
+						/>
+					</div>
+				</div>
+				<div className={styles.buttons}>
+					<button
+						className="transparent"
+						type="button"
+						onClick={onClose}>
+						Cancel
+					</button>
+					<button className="primary" type="submit">
+						Import
+					</button>
+				</div>
+			</form>
+		</div>
+	);
+}
+
+export default CsvImportPopup;
@@ -18,7 +18,9 @@ function formatDate(dateString: string) {
 
 function RepetitionsInfo({ repetitions, cellType }: Props) {
 	const sortedRepetitions = useMemo(() => {
-		if (cellType !== "Cloze") return repetitions;
+		if (cellType !== "Cloze" && cellType !== "ImageOcclusion") {
+			return repetitions;
+		}
 		return repetitions.sort(
 			(a, b) =>
 				Number(a.additionalContent ?? "0") -
@@ -37,6 +39,11 @@ function RepetitionsInfo({ repetitions, cellType }: Props) {
 							<p>Cloze Group: {repetition.additionalContent}</p>
 						</strong>
 					)}
+					{cellType === "ImageOcclusion" && (
+						<strong>
+							<p>Mask: {repetition.additionalContent}</p>
+						</strong>
+					)}
 
 					<p>Due: {formatDate(repetition.due)}</p>
 					<p>Stability: {repetition.stability.toFixed(1)}</p>
@@ -3,7 +3,11 @@ import Cell from "../../type/backend/entity/cell";
 import RichTextEditor from "../../ui/RichTextEditor/RichTextEditor";
 import ClozeCell from "./Cloze/Cloze";
 import FlashCardCell from "./FlashCardCell";
+import ImageOcclusionCell from "./ImageOcclusionCell";
+import MultipleChoiceCell from "./MultipleChoiceCell";
+import OrderingCell from "./OrderingCell";
 import TrueFalseCell from "./TrueFalseCell";
+import TypeAnswerCell from "./TypeAnswerCell";
 
 interface Props {
 	cell: Cell;
@@ -55,6 +59,44 @@ function EditorCell({ cell, autofocus, editable, onUpdate, onFocus }: Props) {
 					onFocus={onFocus}
 				/>
 			);
+		case "MultipleChoice":
+			return (
+				<MultipleChoiceCell
+					editable={editable}
+					cell={cell}
+					autofocus={autofocus}
+					onUpdate={onUpdate}
+					onFocus={onFocus}
+				/>
+			);
+		case "TypeAnswer":
+			return (
+				<TypeAnswerCell
+					editable={editable}
+					cell={cell}
+					autofocus={autofocus}
+					onUpdate={onUpdate}
+					onFocus={onFocus}
+				/>
+			);
+		case "ImageOcclusion":
+			return (
+				<ImageOcclusionCell
+					editable={editable}
+					cell={cell}
+					onUpdate={onUpdate}
+				/>
+			);
+		case "Ordering":
+			return (
+				<OrderingCell
+					editable={editable}
+					cell={cell}
+					autofocus={autofocus}
+					onUpdate={onUpdate}
+					onFocus={onFocus}
+				/>
+			);
 	}
 }
 
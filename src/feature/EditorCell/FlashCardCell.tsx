@@ -1,4 +1,4 @@
-import { useRef } from "react";
+import { useRef, useState } from "react";
 import Cell from "../../type/backend/entity/cell";
 import FlashCard from "../../type/cell/flashCard";
 import RichTextEditor from "../../ui/RichTextEditor/RichTextEditor";
@@ -24,26 +24,34 @@ function FlashCardCell({
 
 	const question = useRef(flashCard.question);
 	const answer = useRef(flashCard.answer);
+	const isReversible = useRef(flashCard.isReversible ?? false);
+	const [, setRenderedIsReversible] = useState(isReversible.current);
 	const isAnswerEditorFocused = useRef(false);
 
-	const handleQuestionUpdate = (html: string) => {
-		question.current = html;
+	const update = () => {
 		onUpdate(
 			JSON.stringify({
-				question: html,
+				question: question.current,
 				answer: answer.current,
+				isReversible: isReversible.current,
 			} as FlashCard),
 		);
 	};
 
+	const handleQuestionUpdate = (html: string) => {
+		question.current = html;
+		update();
+	};
+
 	const handleAnswerUpdate = (html: string) => {
 		answer.current = html;
-		onUpdate(
-			JSON.stringify({
-				question: question.current,
-				answer: html,
-			} as FlashCard),
-		);
+		update();
+	};
+
+	const handleIsReversibleUpdate = (checked: boolean) => {
+		isReversible.current = checked;
+		setRenderedIsReversible(checked);
+		update();
 	};
 
 	return (
@@ -68,6 +76,15 @@ function FlashCardCell({
 				onBlur={() => (isAnswerEditorFocused.current = false)}
 				editable={editable}
 			/>
+			<label className={styles.reversible}>
+				<input
+					type="checkbox"
+					checked={isReversible.current}
+					disabled={!editable}
+					onChange={e => handleIsReversibleUpdate(e.target.checked)}
+				/>
+				Also review from the answer to the question
+			</label>
 		</div>
 	);
 }
@@ -0,0 +1,245 @@
+import { mdiClose } from "@mdi/js";
+import Icon from "@mdi/react";
+import React, { useRef, useState } from "react";
+import { addMediaFromBytes } from "../../api/mediaApi";
+import Cell from "../../type/backend/entity/cell";
+import ImageOcclusion, { MaskShape } from "../../type/cell/imageOcclusion";
+import errorToString from "../../util/errorToString";
+import getMaskPoints from "../../util/getMaskPoints";
+import resolveMediaSources from "../../util/resolveMediaSources";
+import styles from "./styles.module.css";
+
+interface Props {
+	cell: Cell;
+	editable: boolean;
+	onUpdate: (content: string) => void;
+}
+
+type Point = [number, number];
+
+/** Rectangles smaller than this are treated as accidental clicks. */
+const MIN_MASK_SIZE = 0.01;
+
+export function ImageOcclusionCell({ cell, editable, onUpdate }: Props) {
+	const [imageOcclusion, setImageOcclusion] = useState(
+		() => JSON.parse(cell.content) as ImageOcclusion,
+	);
+	const [shapeKind, setShapeKind] = useState<MaskShape["kind"]>("rectangle");
+	// The corners of the rectangle or the points of the polygon being drawn.
+	const [draftPoints, setDraftPoints] = useState<Point[]>([]);
+	const [error, setError] = useState<string | null>(null);
+	const masksRef = useRef<SVGSVGElement>(null);
+
+	const update = (changes: Partial<ImageOcclusion>) => {
+		const updatedImageOcclusion = { ...imageOcclusion, ...changes };
+		setImageOcclusion(updatedImageOcclusion);
+		onUpdate(JSON.stringify(updatedImageOcclusion));
+	};
+
+	const handleImageChange = async (file: File) => {
+		try {
+			const extension = file.name.split(".").pop() ?? "";
+			const mediaFile = await addMediaFromBytes(
+				new Uint8Array(await file.arrayBuffer()),
+				extension,
+			);
+			setError(null);
+			update({ image: mediaFile.url });
+		} catch (e) {
+			console.error(e);
+			setError(errorToString(e));
+		}
+	};
+
+	const getPoint = (e: React.PointerEvent): Point => {
+		const rect = masksRef.current!.getBoundingClientRect();
+		const clamp = (value: number) => Math.min(1, Math.max(0, value));
+		return [
+			clamp((e.clientX - rect.left) / rect.width),
+			clamp((e.clientY - rect.top) / rect.height),
+		];
+	};
+
+	const addMask = (shape: MaskShape) => {
+		// The ids of the other masks are kept, so that their repetitions are.
+		const id = Math.max(0, ...imageOcclusion.masks.map(m => m.id)) + 1;
+		update({ masks: [...imageOcclusion.masks, { id, label: "", shape }] });
+	};
+
+	const handlePointerDown = (e: React.PointerEvent) => {
+		if (!editable) {
+			return;
+		}
+		const point = getPoint(e);
+		if (shapeKind === "polygon") {
+			setDraftPoints([...draftPoints, point]);
+		} else {
+			e.currentTarget.setPointerCapture(e.pointerId);
+			setDraftPoints([point, point]);
+		}
+	};
+
+	const handlePointerMove = (e: React.PointerEvent) => {
+		if (shapeKind === "rectangle" && draftPoints.length === 2) {
+			setDraftPoints([draftPoints[0], getPoint(e)]);
+		}
+	};
+
+	const handlePointerUp = () => {
+		if (shapeKind !== "rectangle" || draftPoints.length !== 2) {
+			return;
+		}
+		const [[x1, y1], [x2, y2]] = draftPoints;
+		setDraftPoints([]);
+		const width = Math.abs(x2 - x1);
+		const height = Math.abs(y2 - y1);
+		if (width >= MIN_MASK_SIZE && height >= MIN_MASK_SIZE) {
+			addMask({
+				kind: "rectangle",
+				x: Math.min(x1, x2),
+				y: Math.min(y1, y2),
+				width,
+				height,
+			});
+		}
+	};
+
+	const handlePolygonFinish = () => {
+		addMask({ kind: "polygon", points: draftPoints });
+		setDraftPoints([]);
+	};
+
+	const handleShapeKindChange = (kind: MaskShape["kind"]) => {
+		setShapeKind(kind);
+		setDraftPoints([]);
+	};
+
+	const handleLabelUpdate = (id: number, label: string) => {
+		update({
+			masks: imageOcclusion.masks.map(mask =>
+				mask.id === id ? { ...mask, label } : mask,
+			),
+		});
+	};
+
+	const handleMaskRemove = (id: number) => {
+		update({
+			masks: imageOcclusion.masks.filter(mask => mask.id !== id),
+		});
+	};
+
+	const draftPolygon =
+		shapeKind === "rectangle" && draftPoints.length === 2
+			? getMaskPoints({
+					kind: "rectangle",
+					x: Math.min(draftPoints[0][0], draftPoints[1][0]),
+					y: Math.min(draftPoints[0][1], draftPoints[1][1]),
+					width: Math.abs(draftPoints[1][0] - draftPoints[0][0]),
+					height: Math.abs(draftPoints[1][1] - draftPoints[0][1]),
+				})
+			: getMaskPoints({ kind: "polygon", points: draftPoints });
+
+	return (
+		<div className={styles.imageOcclusion}>
+			<label>
+				<p>Image</p>
+				<input
+					type="file"
+					accept="image/*"
+					disabled={!editable}
+					onChange={e => {
+						const file = e.target.files?.[0];
+						if (file) {
+							void handleImageChange(file);
+						}
+					}}
+				/>
+			</label>
+			{error && <p>{error}</p>}
+			{imageOcclusion.image && (
+				<>
+					<div className={styles.shapeKinds}>
+						{(["rectangle", "polygon"] as const).map(kind => (
+							<label key={kind}>
+								<input
+									type="radio"
+									checked={shapeKind === kind}
+									disabled={!editable}
+									onChange={() => handleShapeKindChange(kind)}
+								/>
+								{kind === "rectangle" ? "Rectangle" : "Polygon"}
+							</label>
+						))}
+						{shapeKind === "polygon" && (
+							<button
+								className="transparent"
+								disabled={!editable || draftPoints.length < 3}
+								onClick={e => {
+									e.stopPropagation();
+									handlePolygonFinish();
+								}}>
+								Finish polygon
+							</button>
+						)}
+					</div>
+					<div className={styles.occludedImage}>
+						<img
+							src={resolveMediaSources(imageOcclusion.image)}
+							alt=""
+							draggable={false}
+						/>
+						<svg
+							ref={masksRef}
+							viewBox="0 0 1 1"
+							preserveAspectRatio="none"
+							onPointerDown={handlePointerDown}
+							onPointerMove={handlePointerMove}
+							onPointerUp={handlePointerUp}>
+							{imageOcclusion.masks.map(mask => (
+								<polygon
+									key={mask.id}
+									className={styles.mask}
+									points={getMaskPoints(mask.shape)}
+									vectorEffect="non-scaling-stroke">
+									<title>{mask.label}</title>
+								</polygon>
+							))}
+							{draftPoints.length > 0 && (
+								<polygon
+									className={styles.draftMask}
+									points={draftPolygon}
+									vectorEffect="non-scaling-stroke"
+								/>
+							)}
+						</svg>
+					</div>
+				</>
+			)}
+			{imageOcclusion.masks.map((mask, i) => (
+				<div className={styles.maskLabel} key={mask.id}>
+					<input
+						type="text"
+						value={mask.label}
+						placeholder={`Mask ${i + 1}`}
+						disabled={!editable}
+						onChange={e =>
+							handleLabelUpdate(mask.id, e.target.value)
+						}
+					/>
+					<button
+						className="transparent"
+						title="Remove mask"
+						disabled={!editable}
+						onClick={e => {
+							e.stopPropagation();
+							handleMaskRemove(mask.id);
+						}}>
+						<Icon path={mdiClose} size={1} />
+					</button>
+				</div>
+			))}
+		</div>
+	);
+}
+
+export default ImageOcclusionCell;
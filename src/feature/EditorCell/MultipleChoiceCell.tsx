@@ -0,0 +1,152 @@
+import { Editor } from "@tiptap/react";
+import { mdiClose } from "@mdi/js";
+import Icon from "@mdi/react";
+import { useRef, useState } from "react";
+import Cell from "../../type/backend/entity/cell";
+import MultipleChoice from "../../type/cell/multipleChoice";
+import RichTextEditor from "../../ui/RichTextEditor/RichTextEditor";
+import styles from "./styles.module.css";
+
+interface Props {
+	cell: Cell;
+	autofocus: boolean;
+	editable: boolean;
+	onUpdate: (content: string) => void;
+	onFocus: (editor: Editor) => void;
+}
+
+export function MultipleChoiceCell({
+	cell,
+	autofocus,
+	editable,
+	onUpdate,
+	onFocus,
+}: Props) {
+	const initialMultipleChoice = JSON.parse(cell.content) as MultipleChoice;
+
+	// The editors keep the callbacks they were created with, so the latest
+	// values are read from refs and every option is found by its key.
+	const multipleChoice = useRef(initialMultipleChoice);
+	const optionKeys = useRef(initialMultipleChoice.options.map((_, i) => i));
+	const nextKey = useRef(initialMultipleChoice.options.length);
+	const [, setRenderedMultipleChoice] = useState(initialMultipleChoice);
+
+	const update = (changes: Partial<MultipleChoice>) => {
+		multipleChoice.current = { ...multipleChoice.current, ...changes };
+		setRenderedMultipleChoice(multipleChoice.current);
+		onUpdate(JSON.stringify(multipleChoice.current));
+	};
+
+	const handleOptionUpdate = (key: number, html: string) => {
+		const index = optionKeys.current.indexOf(key);
+		update({
+			options: multipleChoice.current.options.map((option, i) =>
+				i === index ? html : option,
+			),
+		});
+	};
+
+	const handleCorrectToggle = (index: number) => {
+		const { correctIndices } = multipleChoice.current;
+		update({
+			correctIndices: correctIndices.includes(index)
+				? correctIndices.filter(i => i !== index)
+				: [...correctIndices, index].sort((a, b) => a - b),
+		});
+	};
+
+	const handleOptionAdd = () => {
+		optionKeys.current = [...optionKeys.current, nextKey.current++];
+		update({ options: [...multipleChoice.current.options, ""] });
+	};
+
+	const handleOptionRemove = (index: number) => {
+		optionKeys.current = optionKeys.current.filter((_, i) => i !== index);
+		update({
+			options: multipleChoice.current.options.filter(
+				(_, i) => i !== index,
+			),
+			correctIndices: multipleChoice.current.correctIndices
+				.filter(i => i !== index)
+				.map(i => (i > index ? i - 1 : i)),
+		});
+	};
+
+	const { question, options, correctIndices, explanation, shuffleOptions } =
+		multipleChoice.current;
+
+	return (
+		<div className={styles.multipleChoice}>
+			<RichTextEditor
+				title="Question"
+				initialContent={question}
+				onUpdate={html => update({ question: html })}
+				autofocus={autofocus}
+				onFocus={onFocus}
+				editable={editable}
+			/>
+			{options.map((option, i) => {
+				const key = optionKeys.current[i];
+				return (
+					<div className={styles.option} key={key}>
+						<input
+							type="checkbox"
+							title="Correct"
+							checked={correctIndices.includes(i)}
+							disabled={!editable}
+							onChange={() => handleCorrectToggle(i)}
+						/>
+						<RichTextEditor
+							title={`Option ${i + 1}`}
+							initialContent={option}
+							onUpdate={html => handleOptionUpdate(key, html)}
+							onFocus={onFocus}
+							editable={editable}
+						/>
+						<button
+							className="transparent"
+							title="Remove option"
+							disabled={!editable || options.length <= 2}
+							onClick={e => {
+								e.stopPropagation();
+								handleOptionRemove(i);
+							}}>
+							<Icon path={mdiClose} size={1} />
+						</button>
+					</div>
+				);
+			})}
+			<button
+				className="transparent"
+				disabled={!editable}
+				onClick={e => {
+					e.stopPropagation();
+					handleOptionAdd();
+				}}>
+				Add option
+			</button>
+			<RichTextEditor
+				title="Explanation"
+				initialContent={explanation ?? ""}
+				onUpdate={html =>
+					update({
+						explanation: html === "<p></p>" ? undefined : html,
+					})
+				}
+				onFocus={onFocus}
+				editable={editable}
+			/>
+			<label className={styles.shuffle}>
+				<input
+					type="checkbox"
+					checked={shuffleOptions}
+					disabled={!editable}
+					onChange={e => update({ shuffleOptions: e.target.checked })}
+				/>
+				Shuffle the options when reviewing
+			</label>
+		</div>
+	);
+}
+
+export default MultipleChoiceCell;
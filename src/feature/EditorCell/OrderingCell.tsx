@@ -0,0 +1,143 @@
+import { Editor } from "@tiptap/react";
+import { mdiChevronDown, mdiChevronUp, mdiClose } from "@mdi/js";
+import Icon from "@mdi/react";
+import { useRef, useState } from "react";
+import Cell from "../../type/backend/entity/cell";
+import Ordering from "../../type/cell/ordering";
+import RichTextEditor from "../../ui/RichTextEditor/RichTextEditor";
+import styles from "./styles.module.css";
+
+interface Props {
+	cell: Cell;
+	autofocus: boolean;
+	editable: boolean;
+	onUpdate: (content: string) => void;
+	onFocus: (editor: Editor) => void;
+}
+
+export function OrderingCell({
+	cell,
+	autofocus,
+	editable,
+	onUpdate,
+	onFocus,
+}: Props) {
+	const initialOrdering = JSON.parse(cell.content) as Ordering;
+
+	// The editors keep the callbacks they were created with, so the latest
+	// values are read from refs and every item is found by its key.
+	const ordering = useRef(initialOrdering);
+	const itemKeys = useRef(initialOrdering.items.map((_, i) => i));
+	const nextKey = useRef(initialOrdering.items.length);
+	const [, setRenderedOrdering] = useState(initialOrdering);
+
+	const update = (changes: Partial<Ordering>) => {
+		ordering.current = { ...ordering.current, ...changes };
+		setRenderedOrdering(ordering.current);
+		onUpdate(JSON.stringify(ordering.current));
+	};
+
+	const handleItemUpdate = (key: number, html: string) => {
+		const index = itemKeys.current.indexOf(key);
+		update({
+			items: ordering.current.items.map((item, i) =>
+				i === index ? html : item,
+			),
+		});
+	};
+
+	const handleItemAdd = () => {
+		itemKeys.current = [...itemKeys.current, nextKey.current++];
+		update({ items: [...ordering.current.items, ""] });
+	};
+
+	const handleItemRemove = (index: number) => {
+		itemKeys.current = itemKeys.current.filter((_, i) => i !== index);
+		update({
+			items: ordering.current.items.filter((_, i) => i !== index),
+		});
+	};
+
+	const handleItemMove = (index: number, offset: number) => {
+		const swap = <T,>(values: T[]) => {
+			const swapped = [...values];
+			[swapped[index], swapped[index + offset]] = [
+				swapped[index + offset],
+				swapped[index],
+			];
+			return swapped;
+		};
+		itemKeys.current = swap(itemKeys.current);
+		update({ items: swap(ordering.current.items) });
+	};
+
+	const { question, items } = ordering.current;
+
+	return (
+		<div className={styles.ordering}>
+			<RichTextEditor
+				title="Question"
+				initialContent={question}
+				onUpdate={html => update({ question: html })}
+				autofocus={autofocus}
+				onFocus={onFocus}
+				editable={editable}
+			/>
+			{items.map((item, i) => {
+				const key = itemKeys.current[i];
+				return (
+					<div className={styles.item} key={key}>
+						<RichTextEditor
+							title={`Item ${i + 1}`}
+							initialContent={item}
+							onUpdate={html => handleItemUpdate(key, html)}
+							onFocus={onFocus}
+							editable={editable}
+						/>
+						<button
+							className="transparent"
+							title="Move up"
+							disabled={!editable || i === 0}
+							onClick={e => {
+								e.stopPropagation();
+								handleItemMove(i, -1);
+							}}>
+							<Icon path={mdiChevronUp} size={1} />
+						</button>
+						<button
+							className="transparent"
+							title="Move down"
+							disabled={!editable || i === items.length - 1}
+							onClick={e => {
+								e.stopPropagation();
+								handleItemMove(i, 1);
+							}}>
+							<Icon path={mdiChevronDown} size={1} />
+						</button>
+						<button
+							className="transparent"
+							title="Remove item"
+							disabled={!editable || items.length <= 2}
+							onClick={e => {
+								e.stopPropagation();
+								handleItemRemove(i);
+							}}>
+							<Icon path={mdiClose} size={1} />
+						</button>
+					</div>
+				);
+			})}
+			<button
+				className="transparent"
+				disabled={!editable}
+				onClick={e => {
+					e.stopPropagation();
+					handleItemAdd();
+				}}>
+				Add item
+			</button>
+		</div>
+	);
+}
+
+export default OrderingCell;
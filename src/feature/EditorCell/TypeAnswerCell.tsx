@@ -0,0 +1,104 @@
+import { Editor } from "@tiptap/react";
+import { useRef, useState } from "react";
+import Cell from "../../type/backend/entity/cell";
+import TypeAnswer from "../../type/cell/typeAnswer";
+import RichTextEditor from "../../ui/RichTextEditor/RichTextEditor";
+import styles from "./styles.module.css";
+
+interface Props {
+	cell: Cell;
+	autofocus: boolean;
+	editable: boolean;
+	onUpdate: (content: string) => void;
+	onFocus: (editor: Editor) => void;
+}
+
+const sensitivities = [
+	["caseSensitive", "Case sensitive"],
+	["accentSensitive", "Accent sensitive"],
+	["whitespaceSensitive", "Whitespace sensitive"],
+] as const;
+
+export function TypeAnswerCell({
+	cell,
+	autofocus,
+	editable,
+	onUpdate,
+	onFocus,
+}: Props) {
+	const initialTypeAnswer = JSON.parse(cell.content) as TypeAnswer;
+
+	// The question editor keeps the callback it was created with.
+	const typeAnswer = useRef(initialTypeAnswer);
+	const [alternativeAnswers, setAlternativeAnswers] = useState(
+		initialTypeAnswer.alternativeAnswers.join("\n"),
+	);
+	const [, setRenderedTypeAnswer] = useState(initialTypeAnswer);
+
+	const update = (changes: Partial<TypeAnswer>) => {
+		typeAnswer.current = { ...typeAnswer.current, ...changes };
+		setRenderedTypeAnswer(typeAnswer.current);
+		onUpdate(JSON.stringify(typeAnswer.current));
+	};
+
+	const handleAlternativeAnswersUpdate = (text: string) => {
+		setAlternativeAnswers(text);
+		update({
+			alternativeAnswers: text
+				.split("\n")
+				.map(answer => answer.trim())
+				.filter(answer => answer !== ""),
+		});
+	};
+
+	return (
+		<div className={styles.typeAnswer}>
+			<RichTextEditor
+				title="Question"
+				initialContent={typeAnswer.current.question}
+				onUpdate={html => update({ question: html })}
+				autofocus={autofocus}
+				onFocus={onFocus}
+				editable={editable}
+			/>
+			<label>
+				<p>Answer</p>
+				<input
+					type="text"
+					value={typeAnswer.current.answer}
+					disabled={!editable}
+					onChange={e => update({ answer: e.target.value })}
+				/>
+			</label>
+			<label>
+				<p>Alternative answers, one per line</p>
+				<textarea
+					value={alternativeAnswers}
+					disabled={!editable}
+					onChange={e =>
+						handleAlternativeAnswersUpdate(e.target.value)
+					}
+				/>
+			</label>
+			<div className={styles.sensitivities}>
+				{sensitivities.map(([key, label]) => (
+					<label key={key}>
+						<input
+							type="checkbox"
+							checked={typeAnswer.current[key]}
+							disabled={!editable}
+							onChange={e =>
+								update({
+									[key]: e.target.checked,
+								} as Partial<TypeAnswer>)
+							}
+						/>
+						{label}
+					</label>
+				))}
+			</div>
+		</div>
+	);
+}
+
+export default TypeAnswerCell;
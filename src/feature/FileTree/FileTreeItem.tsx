@@ -1,6 +1,8 @@
 import {
 	save as openSaveDialog,
 	open as openOpenDialog,
+	ask,
+	message,
 	DialogFilter,
 } from "@tauri-apps/plugin-dialog";
 import styles from "./styles.module.css";
@@ -10,26 +12,39 @@ import {
 	mdiFileDocumentPlusOutline,
 	mdiFolderPlusOutline,
 	mdiImport,
+	mdiLanguageMarkdownOutline,
 	mdiPencilOutline,
+	mdiTableArrowLeft,
+	mdiTuneVariant,
 } from "@mdi/js";
 import React, { useRef, useState } from "react";
 import { Action } from "./ActionsMenu";
 import useAppDispatch from "../../hooks/useAppDispatch";
 import {
-	importFile,
+	importAnkiPackage,
+	importMarkdown,
 	moveFile,
 	moveFolder,
 } from "../../store/actions/fileSystemActions";
 import getFileName from "../../util/getFileName";
 import { requestFailure } from "../../store/reducers/fileSystemReducers";
 import UiFolder from "../../type/ui/uiFolder";
-import { exportItem } from "../../api/exportImportApi";
+import {
+	exportAnkiPackage,
+	exportBundle,
+	exportCsv,
+	exportItem,
+	exportMarkdown,
+} from "../../api/exportImportApi";
+import CsvImportPopup from "../CsvImportPopup/CsvImportPopup";
+import ImportPopup from "../ImportPopup/ImportPopup";
 import FileTreeItemRow from "./FileTreeItemRow";
 import FileTreeItemChildren from "./FileTreeItemChildren";
 import errorToString from "../../util/errorToString";
 import { useSearchParams } from "react-router";
 import { fileIdQueryParameter } from "../../constants";
 import { useNavigate } from "react-router";
+import { optimizeSchedulerParameters } from "../../api/schedulerApi";
 
 const dragFormatForFolder = "brainy/folderpath";
 const dragFormatForFile = "brainy/filepath";
@@ -49,6 +64,33 @@ const jsonFileFilter: DialogFilter = {
 	extensions: ["json"],
 };
 
+const bundleFileFilter: DialogFilter = {
+	name: "Brainy bundle",
+	extensions: ["brainy"],
+};
+
+const ankiPackageFilter: DialogFilter = {
+	name: "Anki package",
+	extensions: ["apkg", "colpkg"],
+};
+
+const ankiExportFilter: DialogFilter = {
+	name: "Anki package",
+	extensions: ["apkg"],
+};
+
+const ankiNoteTypeName = "Brainy Note";
+
+const csvFileFilter: DialogFilter = {
+	name: "CSV/TSV",
+	extensions: ["csv", "tsv"],
+};
+
+const markdownFileFilter: DialogFilter = {
+	name: "Markdown",
+	extensions: ["md"],
+};
+
 /**
  * Displays a folder or a file based on whether the folder parameter is given
  * or not.
@@ -69,6 +111,8 @@ function FileTreeItem({
 	const [creatingNewFile, setCreatingNewFile] = useState(false);
 	const [isDragOver, setIsDragOver] = useState(false);
 	const [isOpen, setIsOpen] = useState(false);
+	const [csvImportPath, setCsvImportPath] = useState<string | null>(null);
+	const [importPath, setImportPath] = useState<string | null>(null);
 	const [searchParams] = useSearchParams();
 	const navigate = useNavigate();
 	const dispatch = useAppDispatch();
@@ -128,12 +172,59 @@ function FileTreeItem({
 			void (async () => {
 				setShowActions(false);
 				const savePath = await openSaveDialog({
-					filters: [jsonFileFilter],
+					filters: [
+						jsonFileFilter,
+						bundleFileFilter,
+						ankiExportFilter,
+						csvFileFilter,
+					],
 					defaultPath: getFileName(fullPath),
 				});
 				if (!savePath) return;
 				try {
-					await exportItem(id, savePath);
+					if (/\.apkg$/i.test(savePath)) {
+						const exportNotes = await ask(
+							"Do you want to export note cells as well?",
+							{ title: "Export Anki package", kind: "info" },
+						);
+						await exportAnkiPackage(
+							id,
+							savePath,
+							exportNotes ? ankiNoteTypeName : null,
+						);
+					} else if (/\.(csv|tsv)$/i.test(savePath)) {
+						await exportCsv(id, savePath);
+					} else {
+						const includeProgress = await ask(
+							"Do you want to include the review progress of the cards?",
+							{ title: "Export", kind: "info" },
+						);
+						if (/\.brainy$/i.test(savePath)) {
+							await exportBundle(id, savePath, includeProgress);
+						} else {
+							await exportItem(id, savePath, includeProgress);
+						}
+					}
+				} catch (e) {
+					console.error(e);
+					dispatch(requestFailure(errorToString(e)));
+				}
+			})();
+		},
+	});
+
+	actions.push({
+		iconName: mdiLanguageMarkdownOutline,
+		text: "Export Markdown",
+		onClick: () => {
+			void (async () => {
+				setShowActions(false);
+				const exportDirectory = await openOpenDialog({
+					directory: true,
+				});
+				if (!exportDirectory) return;
+				try {
+					await exportMarkdown(id, exportDirectory);
 				} catch (e) {
 					console.error(e);
 					dispatch(requestFailure(errorToString(e)));
@@ -150,10 +241,94 @@ function FileTreeItem({
 				void (async () => {
 					setShowActions(false);
 					const openPath = await openOpenDialog({
-						filters: [jsonFileFilter],
+						filters: [
+							jsonFileFilter,
+							bundleFileFilter,
+							ankiPackageFilter,
+							markdownFileFilter,
+						],
+					});
+					if (!openPath) return;
+					if (/\.md$/i.test(openPath)) {
+						await dispatch(importMarkdown(openPath, id));
+						return;
+					}
+					if (!/\.(apkg|colpkg)$/i.test(openPath)) {
+						setImportPath(openPath);
+						return;
+					}
+
+					const importScheduling = await ask(
+						"Do you want to keep the review progress of the cards?",
+						{ title: "Import Anki package", kind: "info" },
+					);
+					const result = await dispatch(
+						importAnkiPackage(openPath, id, importScheduling),
+					);
+					if (result && result.unmappedNoteTypes.length > 0) {
+						const skipped = result.unmappedNoteTypes
+							.map(t => `${t.name} (${t.notesCount})`)
+							.join(", ");
+						await message(
+							`Skipped notes of unsupported types: ${skipped}`,
+							{ title: "Import Anki package" },
+						);
+					}
+				})();
+			},
+		});
+		actions.push({
+			iconName: mdiTuneVariant,
+			text: "Optimize Scheduler",
+			onClick: () => {
+				void (async () => {
+					setShowActions(false);
+					try {
+						// The root folder has the id 0, which optimizes the
+						// parameters of the whole collection.
+						const result = await optimizeSchedulerParameters(id);
+						await message(
+							`Optimized using ${result.reviewCount} reviews, ` +
+								`the log loss went from ` +
+								`${result.logLossBefore.toFixed(4)} to ` +
+								`${result.logLossAfter.toFixed(4)}.`,
+							{ title: "Optimize Scheduler" },
+						);
+					} catch (e) {
+						console.error(e);
+						dispatch(requestFailure(errorToString(e)));
+					}
+				})();
+			},
+		});
+		actions.push({
+			iconName: mdiLanguageMarkdownOutline,
+			text: "Import Markdown Folder",
+			onClick: () => {
+				void (async () => {
+					setShowActions(false);
+					const importDirectory = await openOpenDialog({
+						directory: true,
+					});
+					if (!importDirectory) return;
+					await dispatch(importMarkdown(importDirectory, id));
+				})();
+			},
+		});
+	}
+
+	if (!folder) {
+		actions.push({
+			iconName: mdiTableArrowLeft,
+			text: "Import CSV",
+			onClick: () => {
+				void (async () => {
+					setShowActions(false);
+					const openPath = await openOpenDialog({
+						filters: [csvFileFilter],
 					});
 					if (!openPath) return;
-					await dispatch(importFile(openPath, id));
+					setCsvImportPath(openPath);
 				})();
 			},
 		});
@@ -284,6 +459,24 @@ function FileTreeItem({
 					onDragEnd={onDragEnd}
 				/>
 
+				{csvImportPath && (
+					<CsvImportPopup
+						importPath={csvImportPath}
+						fileId={id}
+						onClose={() => setCsvImportPath(null)}
+						onError={error => dispatch(requestFailure(error))}
+					/>
+				)}
+
+				{importPath && (
+					<ImportPopup
+						importPath={importPath}
+						folderId={id}
+						onClose={() => setImportPath(null)}
+						onError={error => dispatch(requestFailure(error))}
+					/>
+				)}
+
 				{folder && isExpanded && (
 					<FileTreeItemChildren
 						creatingNewFile={creatingNewFile}
@@ -0,0 +1,147 @@
+import Icon from "@mdi/react";
+import styles from "./styles.module.css";
+import { mdiImport } from "@mdi/js";
+import { useRef, useState } from "react";
+import {
+	importBundle as importBundleApi,
+	importFile as importFileApi,
+} from "../../api/exportImportApi";
+import {
+	importBundle,
+	importFile,
+} from "../../store/actions/fileSystemActions";
+import useAppDispatch from "../../hooks/useAppDispatch";
+import useOutsideClick from "../../hooks/useOutsideClick";
+import useGlobalKey from "../../hooks/useGlobalKey";
+import errorToString from "../../util/errorToString";
+import ImportOptions, {
+	ImportMode,
+} from "../../type/backend/dto/importOptions";
+import { ImportAction } from "../../type/backend/dto/importResult";
+
+interface Props {
+	importPath: string;
+	folderId: number;
+	onClose: () => void;
+	onError: (error: string) => void;
+}
+
+const actionTexts = {
+	CreateFolder: "Create folder",
+	MergeFolder: "Merge into folder",
+	CreateFile: "Create file",
+	MergeFile: "Merge into file",
+	OverwriteFile: "Overwrite file",
+};
+
+/**
+ * Asks what happens to files which exist already before importing a JSON
+ * export or a `.brainy` bundle. The preview is a dry run of the import.
+ */
+function ImportPopup({ importPath, folderId, onClose, onError }: Props) {
+	const [mode, setMode] = useState<ImportMode>("Rename");
+	const [previewActions, setPreviewActions] = useState<
+		ImportAction[] | null
+	>(null);
+	const dispatch = useAppDispatch();
+	const boxRef = useRef<HTMLFormElement>(null);
+	const isBundle = /\.brainy$/i.test(importPath);
+
+	useOutsideClick(boxRef as React.RefObject<HTMLElement>, onClose);
+
+	const handlePreviewClick = async () => {
+		const options: ImportOptions = { mode, dryRun: true };
+		try {
+			const result = isBundle
+				? await importBundleApi(importPath, folderId, options)
+				: await importFileApi(importPath, folderId, options);
+			setPreviewActions(result.actions);
+		} catch (e) {
+			console.error(e);
+			onError(errorToString(e));
+		}
+	};
+
+	const handleSubmit = async (e: React.FormEvent<HTMLFormElement>) => {
+		e.preventDefault();
+
+		const options: ImportOptions = { mode, dryRun: false };
+		onClose();
+		if (isBundle) {
+			await dispatch(importBundle(importPath, folderId, options));
+		} else {
+			await dispatch(importFile(importPath, folderId, options));
+		}
+	};
+
+	useGlobalKey((e: KeyboardEvent) => {
+		if (e.key === "Escape") {
+			onClose();
+		}
+	});
+
+	return (
+		<div className="overlay">
+			<form
+				className={styles.box}
+				ref={boxRef}
+				onSubmit={e => void handleSubmit(e)}>
+				<div className={`row ${styles.header}`}>
+					<Icon path={mdiImport} size={1.2} />
+					<p>Import {importPath.split(/[\\/]/).pop()}</p>
+				</div>
+				<div className={styles.optionRows}>
+					<div className={styles.optionRow}>
+						<p>Existing files:</p>
+						<select
+							value={mode}
+							onChange={e => {
+								setMode(e.target.value as ImportMode);
+								setPreviewActions(null);
+							}}
+							autoFocus>
+							<option value="Rename">Import as a copy</option>
+							<option value="Merge">Add the cells</option>
+							<option value="SkipDuplicates">
+								Add the new cells
+							</option>
+							<option value="Overwrite">Replace the cells</option>
+						</select>
+					</div>
+					{previewActions && (
+						<ul className={styles.preview}>
+							{previewActions.map(action => (
+								<li key={action.path}>
+									{actionTexts[action.kind]} {action.path}
+									{action.kind.endsWith("File") &&
+										` (${action.addedCellsCount} added` +
+											`, ${action.skippedCellsCount}` +
+											" skipped)"}
+								</li>
+							))}
+						</ul>
+					)}
+				</div>
+				<div className={styles.buttons}>
+					<button
+						className="transparent"
+						type="button"
+						onClick={onClose}>
+						Cancel
+					</button>
+					<button
+						className="transparent"
+						type="button"
+						onClick={() => void handlePreviewClick()}>
+						Preview
+					</button>
+					<button className="primary" type="submit">
+						Import
+					</button>
+				</div>
+			</form>
+		</div>
+	);
+}
+
+export default ImportPopup;
@@ -1,24 +1,24 @@
-import { useEffect, useMemo, useRef, useState } from "react";
+import { useEffect, useRef, useState } from "react";
 import styles from "./styles.module.css";
 import ReviewerCell from "../ReviewerCell/ReviewerCell";
 import Icon from "@mdi/react";
 import { mdiPencilOutline } from "@mdi/js";
-import { FSRS, generatorParameters, Grade, Rating, RecordLog } from "ts-fsrs";
-import createCardFromCellRepetition from "../../util/createCardFromRepetition";
 import durationToString from "../../util/durationToString";
 import useGlobalKey from "../../hooks/useGlobalKey";
 import Repetition from "../../type/backend/entity/repetition";
-import createRepetitionFromCard from "../../util/createRepetitionFromCard";
 import Cell from "../../type/backend/entity/cell";
 import {
 	getRepetitionsForFiles,
-	updateRepetition,
+	previewRepetition,
+	reviewRepetition,
 } from "../../api/repetitionApi";
 import Timer from "./Timer";
 import { Navigate, useLocation, useNavigate } from "react-router";
 import FromRouteState from "../../type/fromRouteState";
 import { getCellsForFiles } from "../../api/cellApi";
 import errorToString from "../../util/errorToString";
+import Rating from "../../type/backend/model/rating";
+import ReviewPreview from "../../type/backend/dto/reviewPreview";
 
 interface Props {
 	fileIds: number[];
@@ -26,15 +26,14 @@ interface Props {
 	onError: (message: string) => void;
 }
 
-const params = generatorParameters();
-const fsrs = new FSRS(params);
-
 function Reviewer({ fileIds, onEditButtonClick, onError }: Props) {
 	const [showAnswer, setShowAnswer] = useState(false);
 	const [currentCellIndex, setCurrentCellIndex] = useState(0);
 	const [isSendingRequest, setIsSendingRequest] = useState(true);
 	const [cells, setCells] = useState<Cell[]>([]);
 	const [repetitions, setRepetitions] = useState<Repetition[]>([]);
+	const [suggestedRating, setSuggestedRating] = useState<Rating | null>(null);
+	const [preview, setPreview] = useState<ReviewPreview | null>(null);
 	const navigate = useNavigate();
 	const startTime = useRef(new Date());
 	const location = useLocation();
@@ -56,16 +55,31 @@ function Reviewer({ fileIds, onEditButtonClick, onError }: Props) {
 	const dueToday = repetitions.filter(
 		c => new Date(c.due) <= startTime.current,
 	);
-	const currentCard =
-		dueToday.length > 0
-			? createCardFromCellRepetition(dueToday[currentCellIndex])
-			: null;
+	const currentRepetitionId = dueToday[currentCellIndex]?.id;
 
-	const schedulingCards: RecordLog | null = useMemo(
-		() =>
-			currentCard ? fsrs.repeat(currentCard, startTime.current) : null,
-		[currentCard, startTime],
-	);
+	// The intervals are computed by the backend, which also schedules the
+	// repetition once it is answered.
+	useEffect(() => {
+		if (currentRepetitionId === undefined) {
+			return;
+		}
+		let isCurrent = true;
+		setPreview(null);
+		void (async () => {
+			try {
+				const preview = await previewRepetition(currentRepetitionId);
+				if (isCurrent) {
+					setPreview(preview);
+				}
+			} catch (e) {
+				console.error(e);
+				onError(errorToString(e));
+			}
+		})();
+		return () => {
+			isCurrent = false;
+		};
+	}, [currentRepetitionId, onError]);
 
 	useGlobalKey(e => {
 		if (e.key === " ") {
@@ -81,31 +95,27 @@ function Reviewer({ fileIds, onEditButtonClick, onError }: Props) {
 			return;
 		}
 		if (e.key === "1") {
-			void handleGradeSubmit(Rating.Again);
+			void handleGradeSubmit("Again");
 		} else if (e.key === "2") {
-			void handleGradeSubmit(Rating.Hard);
+			void handleGradeSubmit("Hard");
 		} else if (e.key === "3") {
-			void handleGradeSubmit(Rating.Good);
+			void handleGradeSubmit("Good");
 		} else if (e.key === "4") {
-			void handleGradeSubmit(Rating.Easy);
+			void handleGradeSubmit("Easy");
 		}
 	});
 
-	const handleGradeSubmit = async (grade: Grade) => {
-		if (isSendingRequest || !schedulingCards) {
+	const handleGradeSubmit = async (rating: Rating) => {
+		if (isSendingRequest || !preview) {
 			return;
 		}
 		setIsSendingRequest(true);
 		try {
-			const card = schedulingCards[grade]?.card;
-			const repetition = createRepetitionFromCard(
-				card,
+			await reviewRepetition(
 				dueToday[currentCellIndex].id,
-				dueToday[currentCellIndex].fileId,
-				dueToday[currentCellIndex].cellId,
-				dueToday[currentCellIndex].additionalContent,
+				rating,
+				new Date().getTime() - startTime.current.getTime(),
 			);
-			await updateRepetition(repetition);
 		} catch (e) {
 			onError("An error happened!");
 			console.error(e);
@@ -113,6 +123,7 @@ function Reviewer({ fileIds, onEditButtonClick, onError }: Props) {
 			setIsSendingRequest(false);
 		}
 		setShowAnswer(false);
+		setSuggestedRating(null);
 		if (currentCellIndex + 1 === dueToday.length) {
 			const state = location.state as FromRouteState;
 			await navigate(
@@ -173,6 +184,8 @@ function Reviewer({ fileIds, onEditButtonClick, onError }: Props) {
 						}
 						repetition={dueToday[currentCellIndex]}
 						showAnswer={showAnswer}
+						onShowAnswer={() => setShowAnswer(true)}
+						onRatingSuggested={setSuggestedRating}
 						key={currentCellIndex}
 					/>
 				)}
@@ -225,19 +238,20 @@ function Reviewer({ fileIds, onEditButtonClick, onError }: Props) {
 					</div>
 				)}
 
-				{showAnswer && schedulingCards && (
+				{showAnswer && preview && (
 					<div className={styles.buttonRow}>
 						<div className={styles.buttonColumn}>
 							<p>
 								{durationToString(
 									startTime.current,
-									schedulingCards[Rating.Again].card.due,
+									new Date(preview.again),
 								)}
 							</p>
 							<button
-								className={styles.againButton}
+								className={`${styles.againButton}
+                                ${suggestedRating === "Again" ? styles.suggested : ""}`}
 								onClick={() =>
-									void handleGradeSubmit(Rating.Again)
+									void handleGradeSubmit("Again")
 								}
 								disabled={isSendingRequest}
 								title="(1)">
@@ -248,13 +262,14 @@ function Reviewer({ fileIds, onEditButtonClick, onError }: Props) {
 							<p>
 								{durationToString(
 									startTime.current,
-									schedulingCards[Rating.Hard].card.due,
+									new Date(preview.hard),
 								)}
 							</p>
 							<button
-								className={styles.hardButton}
+								className={`${styles.hardButton}
+                                ${suggestedRating === "Hard" ? styles.suggested : ""}`}
 								onClick={() =>
-									void handleGradeSubmit(Rating.Hard)
+									void handleGradeSubmit("Hard")
 								}
 								disabled={isSendingRequest}
 								title="(2)">
@@ -265,13 +280,14 @@ function Reviewer({ fileIds, onEditButtonClick, onError }: Props) {
 							<p>
 								{durationToString(
 									startTime.current,
-									schedulingCards[Rating.Good].card.due,
+									new Date(preview.good),
 								)}
 							</p>
 							<button
-								className={styles.goodButton}
+								className={`${styles.goodButton}
+                                ${suggestedRating === "Good" ? styles.suggested : ""}`}
 								onClick={() =>
-									void handleGradeSubmit(Rating.Good)
+									void handleGradeSubmit("Good")
 								}
 								disabled={isSendingRequest}
 								title="(3)">
@@ -282,13 +298,14 @@ function Reviewer({ fileIds, onEditButtonClick, onError }: Props) {
 							<p>
 								{durationToString(
 									startTime.current,
-									schedulingCards[Rating.Easy].card.due,
+									new Date(preview.easy),
 								)}
 							</p>
 							<button
-								className={styles.easyButton}
+								className={`${styles.easyButton}
+                                ${suggestedRating === "Easy" ? styles.suggested : ""}`}
 								onClick={() =>
-									void handleGradeSubmit(Rating.Easy)
+									void handleGradeSubmit("Easy")
 								}
 								disabled={isSendingRequest}
 								title="(4)">
@@ -1,21 +1,25 @@
 import Cell from "../../type/backend/entity/cell";
+import Repetition from "../../type/backend/entity/repetition";
 import FlashCard from "../../type/cell/flashCard";
 
 interface Props {
 	cell: Cell;
 	showAnswer: boolean;
+	repetition: Repetition;
 }
 
-function FlashCardReviewView({ cell, showAnswer }: Props) {
+function FlashCardReviewView({ cell, showAnswer, repetition }: Props) {
 	const flashCard = JSON.parse(cell.content) as FlashCard;
+	// The back repetition of a reversible flash card asks for the question.
+	const isBack = repetition.additionalContent === "back";
+	const front = isBack ? flashCard.answer : flashCard.question;
+	const back = isBack ? flashCard.question : flashCard.answer;
 
 	return (
 		<>
-			<div dangerouslySetInnerHTML={{ __html: flashCard.question }} />
+			<div dangerouslySetInnerHTML={{ __html: front }} />
 			<hr />
-			{showAnswer && (
-				<div dangerouslySetInnerHTML={{ __html: flashCard.answer }} />
-			)}
+			{showAnswer && <div dangerouslySetInnerHTML={{ __html: back }} />}
 		</>
 	);
 }
@@ -0,0 +1,47 @@
+import Cell from "../../type/backend/entity/cell";
+import Repetition from "../../type/backend/entity/repetition";
+import ImageOcclusion from "../../type/cell/imageOcclusion";
+import getMaskPoints from "../../util/getMaskPoints";
+import styles from "./styles.module.css";
+
+interface Props {
+	cell: Cell;
+	showAnswer: boolean;
+	repetition: Repetition;
+}
+
+function ImageOcclusionReviewView({ cell, showAnswer, repetition }: Props) {
+	const imageOcclusion = JSON.parse(cell.content) as ImageOcclusion;
+	const askedMask = imageOcclusion.masks.find(
+		mask => mask.id.toString() === repetition.additionalContent,
+	);
+
+	// The other masks stay hidden, so that they do not give the answer away.
+	const getMaskClassName = (id: number) => {
+		if (id !== askedMask?.id) {
+			return styles.mask;
+		}
+		return showAnswer ? styles.revealedMask : styles.askedMask;
+	};
+
+	return (
+		<>
+			<div className={styles.occludedImage}>
+				<img src={imageOcclusion.image} alt="" />
+				<svg viewBox="0 0 1 1" preserveAspectRatio="none">
+					{imageOcclusion.masks.map(mask => (
+						<polygon
+							key={mask.id}
+							className={getMaskClassName(mask.id)}
+							points={getMaskPoints(mask.shape)}
+							vectorEffect="non-scaling-stroke"
+						/>
+					))}
+				</svg>
+			</div>
+			{showAnswer && askedMask?.label && <p>{askedMask.label}</p>}
+		</>
+	);
+}
+
+export default ImageOcclusionReviewView;
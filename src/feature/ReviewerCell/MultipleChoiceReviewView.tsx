@@ -0,0 +1,71 @@
+import React, { useState } from "react";
+import Cell from "../../type/backend/entity/cell";
+import MultipleChoice from "../../type/cell/multipleChoice";
+import shuffle from "../../util/shuffle";
+import styles from "./styles.module.css";
+
+interface Props {
+	cell: Cell;
+	showAnswer: boolean;
+}
+
+export function MultipleChoiceReviewView({ cell, showAnswer }: Props) {
+	const multipleChoice = JSON.parse(cell.content) as MultipleChoice;
+	const [order] = useState(() => {
+		const indices = multipleChoice.options.map((_, i) => i);
+		return multipleChoice.shuffleOptions ? shuffle(indices) : indices;
+	});
+	const [chosenIndices, setChosenIndices] = useState<number[]>([]);
+
+	const handleKeyUp = (e: React.KeyboardEvent) => {
+		if (e.code === "Space") {
+			e.stopPropagation();
+		}
+	};
+
+	const handleChoose = (index: number) => {
+		setChosenIndices(
+			chosenIndices.includes(index)
+				? chosenIndices.filter(i => i !== index)
+				: [...chosenIndices, index],
+		);
+	};
+
+	return (
+		<>
+			<div dangerouslySetInnerHTML={{ __html: multipleChoice.question }} />
+			<hr />
+			<div className={styles.multipleChoiceOptions}>
+				{order.map(index => {
+					const isCorrect =
+						multipleChoice.correctIndices.includes(index);
+					const isChosen = chosenIndices.includes(index);
+					return (
+						<button
+							key={index}
+							className={`transparent
+                                ${isChosen && !showAnswer && styles.checked}
+                                ${showAnswer && isCorrect && styles.correct}
+                                ${showAnswer && isChosen && !isCorrect && styles.wrong}`}
+							disabled={showAnswer}
+							onKeyUp={handleKeyUp}
+							onClick={() => handleChoose(index)}
+							dangerouslySetInnerHTML={{
+								__html: multipleChoice.options[index],
+							}}
+						/>
+					);
+				})}
+			</div>
+			{showAnswer && multipleChoice.explanation && (
+				<div
+					dangerouslySetInnerHTML={{
+						__html: multipleChoice.explanation,
+					}}
+				/>
+			)}
+		</>
+	);
+}
+
+export default MultipleChoiceReviewView;
@@ -0,0 +1,129 @@
+import { mdiChevronDown, mdiChevronUp } from "@mdi/js";
+import Icon from "@mdi/react";
+import React, { useEffect, useState } from "react";
+import { gradeOrdering } from "../../api/cellApi";
+import OrderingGrade from "../../type/backend/dto/orderingGrade";
+import Cell from "../../type/backend/entity/cell";
+import Rating from "../../type/backend/model/rating";
+import Ordering from "../../type/cell/ordering";
+import errorToString from "../../util/errorToString";
+import shuffle from "../../util/shuffle";
+import styles from "./styles.module.css";
+
+interface Props {
+	cell: Cell;
+	showAnswer: boolean;
+	onRatingSuggested: (rating: Rating) => void;
+}
+
+export function OrderingReviewView({
+	cell,
+	showAnswer,
+	onRatingSuggested,
+}: Props) {
+	const ordering = JSON.parse(cell.content) as Ordering;
+	// The indices of the items in the order they are shown, which is never
+	// the correct order to begin with.
+	const [order, setOrder] = useState(() => {
+		const indices = shuffle(ordering.items.map((_, i) => i));
+		if (indices.every((index, i) => index === i)) {
+			indices.push(indices.shift()!);
+		}
+		return indices;
+	});
+	const [grade, setGrade] = useState<OrderingGrade | null>(null);
+	const [error, setError] = useState<string | null>(null);
+
+	// The items cannot be moved once the answer is shown, so the order is
+	// graded once.
+	useEffect(() => {
+		const cellId = cell.id;
+		if (!showAnswer || cellId === undefined) {
+			return;
+		}
+		void (async () => {
+			try {
+				const grade = await gradeOrdering(cellId, order);
+				setGrade(grade);
+				onRatingSuggested(grade.suggestedRating);
+			} catch (e) {
+				console.error(e);
+				setError(errorToString(e));
+			}
+		})();
+	}, [showAnswer, cell.id, order, onRatingSuggested]);
+
+	const handleKeyUp = (e: React.KeyboardEvent) => {
+		if (e.code === "Space") {
+			e.stopPropagation();
+		}
+	};
+
+	const handleMove = (position: number, offset: number) => {
+		const moved = [...order];
+		[moved[position], moved[position + offset]] = [
+			moved[position + offset],
+			moved[position],
+		];
+		setOrder(moved);
+	};
+
+	const getItemClassName = (position: number) => {
+		if (!grade) {
+			return "";
+		}
+		return grade.inPlace[position] ? styles.correct : styles.wrong;
+	};
+
+	return (
+		<>
+			<div dangerouslySetInnerHTML={{ __html: ordering.question }} />
+			<hr />
+			<div className={styles.orderingItems}>
+				{order.map((index, position) => (
+					<div
+						key={index}
+						className={`${styles.orderingItem}
+                            ${getItemClassName(position)}`}>
+						<div
+							dangerouslySetInnerHTML={{
+								__html: ordering.items[index],
+							}}
+						/>
+						<button
+							className="transparent"
+							title="Move up"
+							disabled={showAnswer || position === 0}
+							onKeyUp={handleKeyUp}
+							onClick={() => handleMove(position, -1)}>
+							<Icon path={mdiChevronUp} size={1} />
+						</button>
+						<button
+							className="transparent"
+							title="Move down"
+							disabled={
+								showAnswer || position === order.length - 1
+							}
+							onKeyUp={handleKeyUp}
+							onClick={() => handleMove(position, 1)}>
+							<Icon path={mdiChevronDown} size={1} />
+						</button>
+					</div>
+				))}
+			</div>
+			{error && <p>{error}</p>}
+			{grade && !grade.isCorrect && (
+				<ol>
+					{ordering.items.map((item, i) => (
+						<li
+							key={i}
+							dangerouslySetInnerHTML={{ __html: item }}
+						/>
+					))}
+				</ol>
+			)}
+		</>
+	);
+}
+
+export default OrderingReviewView;
@@ -1,19 +1,50 @@
+import { useMemo } from "react";
 import Cell from "../../type/backend/entity/cell";
 import Repetition from "../../type/backend/entity/repetition";
+import Rating from "../../type/backend/model/rating";
 import ClozeReviewView from "./Cloze";
 import FlashCardReviewView from "./FlashCardReviewView";
+import ImageOcclusionReviewView from "./ImageOcclusionReviewView";
+import MultipleChoiceReviewView from "./MultipleChoiceReviewView";
+import OrderingReviewView from "./OrderingReviewView";
 import TrueFalseReviewView from "./TrueFalseReviewView";
+import TypeAnswerReviewView from "./TypeAnswerReviewView";
+import resolveMediaSources from "../../util/resolveMediaSources";
 
 interface Props {
 	cell: Cell;
 	showAnswer: boolean;
 	repetition: Repetition;
+	onShowAnswer: () => void;
+	onRatingSuggested: (rating: Rating) => void;
 }
 
-function ReviewerCell({ cell, showAnswer, repetition }: Props) {
+function ReviewerCell({
+	cell: storedCell,
+	showAnswer,
+	repetition,
+	onShowAnswer,
+	onRatingSuggested,
+}: Props) {
+	// The media references only contain characters that are safe in JSON, so
+	// the contents of every cell type can be resolved as a whole.
+	const cell = useMemo(
+		() => ({
+			...storedCell,
+			content: resolveMediaSources(storedCell.content),
+		}),
+		[storedCell],
+	);
+
 	switch (cell.cellType) {
 		case "FlashCard":
-			return <FlashCardReviewView cell={cell} showAnswer={showAnswer} />;
+			return (
+				<FlashCardReviewView
+					cell={cell}
+					showAnswer={showAnswer}
+					repetition={repetition}
+				/>
+			);
 		case "Cloze":
 			return (
 				<ClozeReviewView
@@ -24,6 +55,35 @@ function ReviewerCell({ cell, showAnswer, repetition }: Props) {
 			);
 		case "TrueFalse":
 			return <TrueFalseReviewView cell={cell} showAnswer={showAnswer} />;
+		case "MultipleChoice":
+			return (
+				<MultipleChoiceReviewView cell={cell} showAnswer={showAnswer} />
+			);
+		case "TypeAnswer":
+			return (
+				<TypeAnswerReviewView
+					cell={cell}
+					showAnswer={showAnswer}
+					onShowAnswer={onShowAnswer}
+					onRatingSuggested={onRatingSuggested}
+				/>
+			);
+		case "ImageOcclusion":
+			return (
+				<ImageOcclusionReviewView
+					cell={cell}
+					showAnswer={showAnswer}
+					repetition={repetition}
+				/>
+			);
+		case "Ordering":
+			return (
+				<OrderingReviewView
+					cell={cell}
+					showAnswer={showAnswer}
+					onRatingSuggested={onRatingSuggested}
+				/>
+			);
 		case "Note":
 			return null;
 	}
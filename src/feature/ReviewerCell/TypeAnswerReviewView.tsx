@@ -0,0 +1,96 @@
+import React, { useEffect, useState } from "react";
+import { checkTypedAnswer } from "../../api/cellApi";
+import AnswerComparison from "../../type/backend/dto/answerComparison";
+import Cell from "../../type/backend/entity/cell";
+import Rating from "../../type/backend/model/rating";
+import TypeAnswer from "../../type/cell/typeAnswer";
+import errorToString from "../../util/errorToString";
+import styles from "./styles.module.css";
+
+interface Props {
+	cell: Cell;
+	showAnswer: boolean;
+	onShowAnswer: () => void;
+	onRatingSuggested: (rating: Rating) => void;
+}
+
+export function TypeAnswerReviewView({
+	cell,
+	showAnswer,
+	onShowAnswer,
+	onRatingSuggested,
+}: Props) {
+	const typeAnswer = JSON.parse(cell.content) as TypeAnswer;
+	const [typedAnswer, setTypedAnswer] = useState("");
+	const [comparison, setComparison] = useState<AnswerComparison | null>(
+		null,
+	);
+	const [error, setError] = useState<string | null>(null);
+
+	// The input is disabled once the answer is shown, so the typed answer is
+	// checked once.
+	useEffect(() => {
+		const cellId = cell.id;
+		if (!showAnswer || cellId === undefined) {
+			return;
+		}
+		void (async () => {
+			try {
+				const comparison = await checkTypedAnswer(cellId, typedAnswer);
+				setComparison(comparison);
+				onRatingSuggested(comparison.suggestedRating);
+			} catch (e) {
+				console.error(e);
+				setError(errorToString(e));
+			}
+		})();
+	}, [showAnswer, cell.id, typedAnswer, onRatingSuggested]);
+
+	const handleKeyUp = (e: React.KeyboardEvent) => {
+		// Typing a space must not show the answer.
+		e.stopPropagation();
+		if (e.key === "Enter") {
+			onShowAnswer();
+		}
+	};
+
+	return (
+		<>
+			<div dangerouslySetInnerHTML={{ __html: typeAnswer.question }} />
+			<hr />
+			<input
+				className={styles.typedAnswer}
+				type="text"
+				value={typedAnswer}
+				placeholder="Type the answer"
+				autoFocus
+				disabled={showAnswer}
+				onKeyUp={handleKeyUp}
+				onChange={e => setTypedAnswer(e.target.value)}
+			/>
+			{error && <p>{error}</p>}
+			{showAnswer && comparison && (
+				<>
+					<p className={styles.diff}>
+						{comparison.diff.map((segment, i) => (
+							<span
+								key={i}
+								className={
+									segment.kind === "Equal"
+										? undefined
+										: styles[segment.kind.toLowerCase()]
+								}>
+								{segment.text}
+							</span>
+						))}
+					</p>
+					{!comparison.isCorrect && (
+						<p>{comparison.expectedAnswer}</p>
+					)}
+				</>
+			)}
+		</>
+	);
+}
+
+export default TypeAnswerReviewView;
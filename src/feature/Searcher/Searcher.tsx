@@ -7,6 +7,7 @@ import errorToString from "../../util/errorToString";
 import EditableCells from "../EditableCells/EditableCells";
 import SearchResult from "../../type/backend/dto/searchResult";
 import { searchCells } from "../../api/searchApi";
+import { SearchSort } from "../../type/backend/dto/searchRequest";
 import { useSearchParams } from "react-router";
 
 interface Props {
@@ -15,10 +16,12 @@ interface Props {
 }
 
 const searchTextQueryParameter = "searchText";
+const pageSize = 50;
 
 function Searcher({ onError, onEditButtonClick }: Props) {
 	const [searchText, setSearchText] = useState("");
 	const [searchResult, setSearchResult] = useState<SearchResult | null>(null);
+	const [sort, setSort] = useState<SearchSort>("Relevance");
 	const [searchParams, setSearchParams] = useSearchParams();
 	const searchInputRef = useRef<HTMLInputElement>(null);
 	const searchParamsSearchText =
@@ -26,13 +29,42 @@ function Searcher({ onError, onEditButtonClick }: Props) {
 
 	const retrieveSearchResult = useCallback(async () => {
 		try {
-			const result = await searchCells(searchParamsSearchText);
+			const result = await searchCells({
+				searchText: searchParamsSearchText,
+				sort,
+				limit: pageSize,
+			});
 			setSearchResult(result);
 		} catch (e) {
 			console.error(e);
 			onError(errorToString(e));
 		}
-	}, [onError, searchParamsSearchText]);
+	}, [onError, searchParamsSearchText, sort]);
+
+	const handleLoadMoreClick = async () => {
+		if (!searchResult) return;
+
+		try {
+			const result = await searchCells({
+				searchText: searchParamsSearchText,
+				sort,
+				offset: searchResult.cells.length,
+				limit: pageSize,
+			});
+			setSearchResult({
+				cells: [...searchResult.cells, ...result.cells],
+				repetitions: [
+					...searchResult.repetitions,
+					...result.repetitions,
+				],
+				snippets: { ...searchResult.snippets, ...result.snippets },
+				totalCount: result.totalCount,
+			});
+		} catch (e) {
+			console.error(e);
+			onError(errorToString(e));
+		}
+	};
 
 	useEffect(() => {
 		void retrieveSearchResult();
@@ -66,6 +98,24 @@ function Searcher({ onError, onEditButtonClick }: Props) {
 				/>
 			</form>
 
+			{searchResult && searchResult.cells.length > 0 && (
+				<div className={`row ${styles.resultHeader}`}>
+					<p>
+						{searchResult.totalCount}{" "}
+						{searchResult.totalCount === 1 ? "result" : "results"}
+					</p>
+					<select
+						value={sort}
+						onChange={e => setSort(e.target.value as SearchSort)}>
+						<option value="Relevance">Relevance</option>
+						<option value="FilePath">File path</option>
+						<option value="Due">Due date</option>
+						<option value="LastReview">Last review</option>
+						<option value="CreationTime">Creation time</option>
+					</select>
+				</div>
+			)}
+
 			{!searchResult && (
 				<p className={styles.noSearchLabel}>
 					Type something and press Enter.
@@ -88,6 +138,16 @@ function Searcher({ onError, onEditButtonClick }: Props) {
 					onEditButtonClick={onEditButtonClick}
 				/>
 			)}
+
+			{searchResult &&
+				searchResult.cells.length < searchResult.totalCount && (
+					<button
+						className="transparent"
+						type="button"
+						onClick={() => void handleLoadMoreClick()}>
+						Load more
+					</button>
+				)}
 		</div>
 	);
 }
@@ -138,6 +138,20 @@ function SettingsPopup({ onClose, onError }: Props) {
 							max={400}
 						/>
 					</div>
+					<div className={styles.settingsRow}>
+						<p>Keep deleted files (days, 0 = forever):</p>
+						<input
+							type="number"
+							value={settings?.trashRetentionDays ?? ""}
+							onChange={e =>
+								setSettings({
+									...settings!,
+									trashRetentionDays: Number(e.target.value),
+								})
+							}
+							min={0}
+						/>
+					</div>
 				</div>
 				<div className={styles.buttons}>
 					<button
@@ -16,8 +16,14 @@ import {
 	getFiles as getFilesApi,
 	renameFolder as renameFolderApi,
 } from "../../api/fileApi";
-import { importFile as importFileApi } from "../../api/exportImportApi";
+import {
+	importFile as importFileApi,
+	importBundle as importBundleApi,
+	importAnkiPackage as importAnkiPackageApi,
+	importMarkdown as importMarkdownApi,
+} from "../../api/exportImportApi";
 import { AppDispatch, RootState } from "../store";
+import ImportOptions from "../../type/backend/dto/importOptions";
 import errorToString from "../../util/errorToString";
 
 export function fetchFiles() {
@@ -56,9 +62,39 @@ export function moveFolder(folderId: number, destinationFolderId: number) {
 	return executeRequest(() => moveFolderApi(folderId, destinationFolderId));
 }
 
-export function importFile(importItemPath: string, importIntoFolderId: number) {
+export function importFile(
+	importItemPath: string,
+	importIntoFolderId: number,
+	options: ImportOptions,
+) {
+	return executeRequest(() =>
+		importFileApi(importItemPath, importIntoFolderId, options),
+	);
+}
+
+export function importBundle(
+	bundlePath: string,
+	importIntoFolderId: number,
+	options: ImportOptions,
+) {
+	return executeRequest(() =>
+		importBundleApi(bundlePath, importIntoFolderId, options),
+	);
+}
+
+export function importAnkiPackage(
+	packagePath: string,
+	importIntoFolderId: number,
+	importScheduling: boolean,
+) {
+	return executeRequest(() =>
+		importAnkiPackageApi(packagePath, importIntoFolderId, importScheduling),
+	);
+}
+
+export function importMarkdown(importPath: string, importIntoFolderId: number) {
 	return executeRequest(() =>
-		importFileApi(importItemPath, importIntoFolderId),
+		importMarkdownApi(importPath, importIntoFolderId),
 	);
 }
 
@@ -68,10 +104,11 @@ function executeRequest<T>(
 	return async function (dispatch: AppDispatch, getState: () => RootState) {
 		try {
 			dispatch(requestStart());
-			await cb(dispatch, getState());
+			const result = await cb(dispatch, getState());
 			const files = await getFilesApi();
 			const rootFolder = parseGetFilesResponse(files);
 			dispatch(requestSuccess(rootFolder));
+			return result;
 		} catch (e) {
 			console.error(e);
 			dispatch(requestFailure(errorToString(e)));
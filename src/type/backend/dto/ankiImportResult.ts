@@ -0,0 +1,9 @@
+export default interface AnkiImportResult {
+	importedNotesCount: number;
+	unmappedNoteTypes: UnmappedNoteType[];
+}
+
+export interface UnmappedNoteType {
+	name: string;
+	notesCount: number;
+}
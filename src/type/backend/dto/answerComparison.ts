@@ -0,0 +1,15 @@
+import Rating from "../model/rating";
+
+export type DiffKind = "Equal" | "Extra" | "Missing";
+
+export interface DiffSegment {
+	kind: DiffKind;
+	text: string;
+}
+
+export default interface AnswerComparison {
+	isCorrect: boolean;
+	expectedAnswer: string;
+	diff: DiffSegment[];
+	suggestedRating: Rating;
+}
@@ -0,0 +1,7 @@
+export default interface CsvImportOptions {
+	questionColumn: number;
+	answerColumn: number;
+	typeColumn: number | null;
+	hasHeader: boolean;
+	delimiter: string | null;
+}
@@ -0,0 +1,9 @@
+export default interface CsvImportResult {
+	importedCellsCount: number;
+	rowErrors: CsvRowError[];
+}
+
+export interface CsvRowError {
+	line: number;
+	message: string;
+}
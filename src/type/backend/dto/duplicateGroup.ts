@@ -0,0 +1,12 @@
+import Cell from "../entity/cell";
+
+export default interface DuplicateGroup {
+	similarity: number;
+	cells: DuplicateCell[];
+}
+
+export interface DuplicateCell {
+	cell: Cell;
+	filePath: string;
+	reviewsCount: number;
+}
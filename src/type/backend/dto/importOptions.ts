@@ -0,0 +1,6 @@
+export default interface ImportOptions {
+	mode: ImportMode;
+	dryRun: boolean;
+}
+
+export type ImportMode = "Rename" | "Merge" | "SkipDuplicates" | "Overwrite";
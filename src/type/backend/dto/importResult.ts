@@ -0,0 +1,17 @@
+export default interface ImportResult {
+	actions: ImportAction[];
+}
+
+export interface ImportAction {
+	path: string;
+	kind: ImportActionKind;
+	addedCellsCount: number;
+	skippedCellsCount: number;
+}
+
+export type ImportActionKind =
+	| "CreateFolder"
+	| "MergeFolder"
+	| "CreateFile"
+	| "MergeFile"
+	| "OverwriteFile";
@@ -0,0 +1,5 @@
+export default interface MediaFile {
+	name: string;
+	url: string;
+	size: number;
+}
@@ -0,0 +1,8 @@
+export default interface OptimizationResult {
+	weights: number[];
+	logLossBefore: number;
+	logLossAfter: number;
+	rmseBefore: number;
+	rmseAfter: number;
+	reviewCount: number;
+}
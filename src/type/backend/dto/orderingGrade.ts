@@ -0,0 +1,9 @@
+import Rating from "../model/rating";
+
+export default interface OrderingGrade {
+	isCorrect: boolean;
+	inPlace: boolean[];
+	itemsInPlaceCount: number;
+	longestOrderedCount: number;
+	suggestedRating: Rating;
+}
@@ -0,0 +1,6 @@
+export default interface ReviewPreview {
+	again: string;
+	hard: string;
+	good: string;
+	easy: string;
+}
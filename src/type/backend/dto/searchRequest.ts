@@ -0,0 +1,13 @@
+export type SearchSort =
+	| "Relevance"
+	| "FilePath"
+	| "Due"
+	| "LastReview"
+	| "CreationTime";
+
+export default interface SearchRequest {
+	searchText: string;
+	sort?: SearchSort;
+	offset?: number;
+	limit?: number;
+}
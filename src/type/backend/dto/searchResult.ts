@@ -4,6 +4,8 @@ import Repetition from "../entity/repetition";
 interface SearchResult {
 	cells: Cell[];
 	repetitions: Repetition[];
+	snippets: Record<number, string>;
+	totalCount: number;
 }
 
 export default SearchResult;
@@ -0,0 +1,5 @@
+export default interface TagWithCellsCount {
+	id: number;
+	name: string;
+	cellsCount: number;
+}
@@ -0,0 +1,6 @@
+export default interface TrashItem {
+	id: number;
+	path: string;
+	isFolder: boolean;
+	deletedAt: string;
+}
@@ -0,0 +1,4 @@
+export default interface UndoRedoState {
+	undoDescription: string | null;
+	redoDescription: string | null;
+}
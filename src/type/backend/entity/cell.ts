@@ -1,15 +1,31 @@
-export type CellType = "FlashCard" | "Note" | "Cloze" | "TrueFalse";
+export type CellType =
+	| "FlashCard"
+	| "Note"
+	| "Cloze"
+	| "TrueFalse"
+	| "MultipleChoice"
+	| "TypeAnswer"
+	| "ImageOcclusion"
+	| "Ordering";
 export const allCellTypes: CellType[] = [
 	"Cloze",
 	"FlashCard",
+	"ImageOcclusion",
+	"MultipleChoice",
 	"Note",
+	"Ordering",
 	"TrueFalse",
+	"TypeAnswer",
 ];
 export const cellTypesDisplayNames: Record<CellType, string> = {
 	Note: "Note",
 	Cloze: "Cloze",
 	FlashCard: "Flash Card",
 	TrueFalse: "True/False",
+	MultipleChoice: "Multiple Choice",
+	TypeAnswer: "Type Answer",
+	ImageOcclusion: "Image Occlusion",
+	Ordering: "Ordering",
 };
 
 export default interface Cell {
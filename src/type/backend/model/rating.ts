@@ -0,0 +1,3 @@
+type Rating = "Again" | "Hard" | "Good" | "Easy";
+
+export default Rating;
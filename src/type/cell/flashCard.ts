@@ -1,4 +1,6 @@
 export default interface FlashCard {
 	question: string;
 	answer: string;
+	/** Also reviewed from the answer to the question, older cells lack it. */
+	isReversible?: boolean;
 }
@@ -0,0 +1,22 @@
+/** The coordinates are fractions of the width and height of the image. */
+export type MaskShape =
+	| {
+			kind: "rectangle";
+			x: number;
+			y: number;
+			width: number;
+			height: number;
+	  }
+	| { kind: "polygon"; points: [number, number][] };
+
+export interface OcclusionMask {
+	/** The additional content of the repetition of the mask. */
+	id: number;
+	label: string;
+	shape: MaskShape;
+}
+
+export default interface ImageOcclusion {
+	image: string;
+	masks: OcclusionMask[];
+}
@@ -0,0 +1,7 @@
+export default interface MultipleChoice {
+	question: string;
+	options: string[];
+	correctIndices: number[];
+	explanation?: string;
+	shuffleOptions: boolean;
+}
@@ -0,0 +1,5 @@
+export default interface Ordering {
+	question: string;
+	/** The items in their correct order. */
+	items: string[];
+}
@@ -0,0 +1,8 @@
+export default interface TypeAnswer {
+	question: string;
+	answer: string;
+	alternativeAnswers: string[];
+	caseSensitive: boolean;
+	accentSensitive: boolean;
+	whitespaceSensitive: boolean;
+}
@@ -14,6 +14,8 @@ import ImageResize from "tiptap-extension-resize-image";
 import BubbleMenuCommand, { Command } from "./Command";
 import { defaultCommands } from "./defaultCommands";
 import { useEffect, useState } from "react";
+import resolveMediaSources from "../../util/resolveMediaSources";
+import referenceMediaSources from "../../util/referenceMediaSources";
 
 const extensions = [
 	StarterKit,
@@ -54,7 +56,9 @@ function RichTextEditor({ editable: initialEditable, ...props }: Props) {
 					<div className={`${styles.editor}`}>
 						<div
 							dangerouslySetInnerHTML={{
-								__html: props.initialContent,
+								__html: resolveMediaSources(
+									props.initialContent,
+								),
 							}}
 							onMouseEnter={() => setEditable(true)}
 						/>
@@ -89,10 +93,10 @@ function TiptapEditor({
 	const editor = useEditor(
 		{
 			extensions: [...extensions, ...(extraExtensions ?? [])],
-			content: initialContent,
+			content: resolveMediaSources(initialContent),
 			onUpdate: e => {
-				if (e.editor.getHTML() !== initialContent)
-					onUpdate(e.editor.getHTML());
+				const html = referenceMediaSources(e.editor.getHTML());
+				if (html !== initialContent) onUpdate(html);
 			},
 			onFocus: onFocus ? e => onFocus(e.editor) : undefined,
 			onBlur,
@@ -1,6 +1,10 @@
 import Cell, { CellType } from "../type/backend/entity/cell";
 import FlashCard from "../type/cell/flashCard";
+import ImageOcclusion from "../type/cell/imageOcclusion";
+import MultipleChoice from "../type/cell/multipleChoice";
+import Ordering from "../type/cell/ordering";
 import TrueFalse from "../type/cell/trueFalse";
+import TypeAnswer from "../type/cell/typeAnswer";
 
 function createDefaultCell(cellType: CellType, fileId: number, index: number) {
 	const cell: Cell = {
@@ -16,6 +20,7 @@ function createDefaultCell(cellType: CellType, fileId: number, index: number) {
 			cell.content = JSON.stringify({
 				question: "",
 				answer: "",
+				isReversible: false,
 			} as FlashCard);
 			break;
 		case "TrueFalse":
@@ -24,6 +29,36 @@ function createDefaultCell(cellType: CellType, fileId: number, index: number) {
 				isTrue: true,
 			} as TrueFalse);
 			break;
+		case "MultipleChoice":
+			cell.content = JSON.stringify({
+				question: "",
+				options: ["", ""],
+				correctIndices: [0],
+				shuffleOptions: true,
+			} as MultipleChoice);
+			break;
+		case "TypeAnswer":
+			cell.content = JSON.stringify({
+				question: "",
+				answer: "",
+				alternativeAnswers: [],
+				caseSensitive: false,
+				accentSensitive: false,
+				whitespaceSensitive: false,
+			} as TypeAnswer);
+			break;
+		case "ImageOcclusion":
+			cell.content = JSON.stringify({
+				image: "",
+				masks: [],
+			} as ImageOcclusion);
+			break;
+		case "Ordering":
+			cell.content = JSON.stringify({
+				question: "",
+				items: ["", ""],
+			} as Ordering);
+			break;
 		case "Note":
 		case "Cloze":
 			break;
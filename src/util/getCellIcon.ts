@@ -2,7 +2,11 @@ import {
 	mdiCardMultipleOutline,
 	mdiCheckCircleOutline,
 	mdiDotsHorizontal,
+	mdiFormatListChecks,
+	mdiFormTextbox,
+	mdiImageArea,
 	mdiNoteOutline,
+	mdiOrderNumericAscending,
 } from "@mdi/js";
 import { CellType } from "../type/backend/entity/cell";
 
@@ -16,6 +20,14 @@ function getCellIcon(cellType: CellType): string {
 			return mdiDotsHorizontal;
 		case "TrueFalse":
 			return mdiCheckCircleOutline;
+		case "MultipleChoice":
+			return mdiFormatListChecks;
+		case "TypeAnswer":
+			return mdiFormTextbox;
+		case "ImageOcclusion":
+			return mdiImageArea;
+		case "Ordering":
+			return mdiOrderNumericAscending;
 	}
 }
 
@@ -0,0 +1,17 @@
+import { MaskShape } from "../type/cell/imageOcclusion";
+
+/** Returns the corners of the mask as the `points` of an SVG polygon. */
+function getMaskPoints(shape: MaskShape): string {
+	const points: [number, number][] =
+		shape.kind === "rectangle"
+			? [
+					[shape.x, shape.y],
+					[shape.x + shape.width, shape.y],
+					[shape.x + shape.width, shape.y + shape.height],
+					[shape.x, shape.y + shape.height],
+				]
+			: shape.points;
+	return points.map(([x, y]) => `${x},${y}`).join(" ");
+}
+
+export default getMaskPoints;
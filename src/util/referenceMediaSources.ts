@@ -0,0 +1,13 @@
+import { convertFileSrc } from "@tauri-apps/api/core";
+import { MEDIA_SCHEME } from "./resolveMediaSources";
+
+/**
+ * Turns the URLs made by `resolveMediaSources` back into `brainy-media://`
+ * references, so that the cells never store machine specific URLs.
+ */
+function referenceMediaSources(html: string): string {
+	const urlPrefix = convertFileSrc("", MEDIA_SCHEME);
+	return html.split(urlPrefix).join(`${MEDIA_SCHEME}://`);
+}
+
+export default referenceMediaSources;
@@ -0,0 +1,17 @@
+import { convertFileSrc } from "@tauri-apps/api/core";
+
+export const MEDIA_SCHEME = "brainy-media";
+
+const MEDIA_REFERENCE = new RegExp(`${MEDIA_SCHEME}://([A-Za-z0-9.]+)`, "g");
+
+/**
+ * Replaces the `brainy-media://<name>` references to the media store with URLs
+ * the webview can load.
+ */
+function resolveMediaSources(html: string): string {
+	return html.replace(MEDIA_REFERENCE, (_, name: string) =>
+		convertFileSrc(name, MEDIA_SCHEME),
+	);
+}
+
+export default resolveMediaSources;
@@ -0,0 +1,10 @@
+/** Shuffles the array in place with the Fisher-Yates algorithm. */
+function shuffle<T>(items: T[]) {
+	for (let i = items.length - 1; i > 0; i--) {
+		const j = Math.floor(Math.random() * (i + 1));
+		[items[i], items[j]] = [items[j], items[i]];
+	}
+	return items;
+}
+
+export default shuffle;